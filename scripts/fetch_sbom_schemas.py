@@ -0,0 +1,34 @@
+#!/usr/bin/env python3
+"""Download the official SPDX 2.3 and CycloneDX 1.5 JSON schemas into tests/fixtures/schemas.
+
+The export e2e test validates both documents against these files. They are vendored
+byte-for-byte; do not edit them after downloading.
+"""
+import sys
+import urllib.request
+from pathlib import Path
+
+
+ROOT = Path(__file__).resolve().parent.parent
+TARGET = ROOT / "tests" / "fixtures" / "schemas"
+SCHEMAS = {
+    "spdx-2.3.schema.json": "https://raw.githubusercontent.com/spdx/spdx-spec/v2.3/schemas/spdx-schema.json",
+    "bom-1.5.schema.json": "https://raw.githubusercontent.com/CycloneDX/specification/1.5/schema/bom-1.5.schema.json",
+    # bom-1.5.schema.json references these two by relative URL.
+    "spdx.schema.json": "https://raw.githubusercontent.com/CycloneDX/specification/1.5/schema/spdx.schema.json",
+    "jsf-0.82.schema.json": "https://raw.githubusercontent.com/CycloneDX/specification/1.5/schema/jsf-0.82.schema.json",
+}
+
+
+def main() -> int:
+    TARGET.mkdir(parents=True, exist_ok=True)
+    for name, url in SCHEMAS.items():
+        with urllib.request.urlopen(url, timeout=30) as resp:
+            data = resp.read()
+        (TARGET / name).write_bytes(data)
+        print(f"{name}: {len(data)} bytes from {url}")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -1,15 +1,23 @@
+mod risk;
+
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
-use std::collections::{HashMap, HashSet};
-use std::sync::atomic::{AtomicU64, Ordering};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
 
 use clap::{Args, Parser, Subcommand};
+use regex::{Regex, RegexBuilder};
 use rusqlite::types::Value;
-use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, TransactionBehavior};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use thiserror::Error;
+use url::Url;
+
+use risk::{evaluate as evaluate_risk, RiskAssessment, RiskFacts};
 
 #[derive(Debug, Error)]
 enum CliError {
@@ -28,6 +36,8 @@ enum CliError {
     Json(#[from] serde_json::Error),
     #[error("config error: {0}")]
     Config(String),
+    #[error("drift detected: {0}")]
+    Drift(String),
 }
 
 impl CliError {
@@ -37,6 +47,7 @@ impl CliError {
             CliError::Security(_) => 3,
             CliError::Integration(_) | CliError::Io(_) | CliError::Db(_) | CliError::Json(_) => 4,
             CliError::Config(_) => 4,
+            CliError::Drift(_) => 5,
         }
     }
 }
@@ -94,6 +105,10 @@ enum TopCommand {
         #[command(subcommand)]
         command: DownloadCommand,
     },
+    Risk {
+        #[command(subcommand)]
+        command: RiskCommand,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -111,12 +126,58 @@ enum SoftwareCommand {
         command: DiscoverCommand,
     },
     List(SoftwareListArgs),
+    History(SoftwareHistoryArgs),
+    Merge(SoftwareMergeArgs),
+    Duplicates(SoftwareDuplicatesArgs),
+    Add(SoftwareAddArgs),
+    Edit(SoftwareEditArgs),
+    Remove(SoftwareRemoveArgs),
+    Audit(SoftwareAuditArgs),
+    Tag(SoftwareTagArgs),
+    Annotate(SoftwareAnnotateArgs),
+    Export(SoftwareExportArgs),
+    Vulns(SoftwareVulnsArgs),
+    Eol(SoftwareEolArgs),
+    Pin(SoftwarePinArgs),
+    Unpin(SoftwareUnpinArgs),
+    Pins(SoftwarePinsArgs),
+    Baseline {
+        #[command(subcommand)]
+        command: BaselineCommand,
+    },
+    Publisher {
+        #[command(subcommand)]
+        command: PublisherCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum BaselineCommand {
+    Check(BaselineCheckArgs),
+    List(OutputArgs),
+    History(BaselineHistoryArgs),
+}
+
+#[derive(Debug, Subcommand)]
+enum PublisherCommand {
+    Alias {
+        #[command(subcommand)]
+        command: PublisherAliasCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum PublisherAliasCommand {
+    Add(PublisherAliasAddArgs),
+    List(OutputArgs),
+    Remove(PublisherAliasRemoveArgs),
 }
 
 #[derive(Debug, Subcommand)]
 enum DiscoverCommand {
     Scan(OutputArgs),
     History(DiscoverHistoryArgs),
+    Diff(DiscoverDiffArgs),
 }
 
 #[derive(Debug, Subcommand)]
@@ -124,11 +185,30 @@ enum SourceCommand {
     Suggest(SourceSuggestArgs),
     Review(SourceReviewArgs),
     ReviewBulk(SourceReviewBulkArgs),
+    ReviewHistory(SourceReviewHistoryArgs),
+    ReviewUndo(SourceReviewUndoArgs),
+    AutoReview(SourceAutoReviewArgs),
+    Calibrate(SourceCalibrateArgs),
     List(SourceListArgs),
     ApplyApproved(SourceApplyApprovedArgs),
     RegistryList(SourceRegistryListArgs),
     RegistryDisable(SourceRegistryDisableArgs),
     RegistryEnable(SourceRegistryEnableArgs),
+    RegistryExpiring(SourceRegistryExpiringArgs),
+    RegistryExport(SourceRegistryExportArgs),
+    RegistryImport(SourceRegistryImportArgs),
+    Probe(SourceProbeArgs),
+    ProbeHistory(SourceProbeHistoryArgs),
+    Rules {
+        #[command(subcommand)]
+        command: SourceRulesCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum SourceRulesCommand {
+    List(OutputArgs),
+    Test(SourceRulesTestArgs),
 }
 
 #[derive(Debug, Subcommand)]
@@ -151,6 +231,11 @@ enum UiCommand {
     ActionRun(UiActionRunArgs),
 }
 
+#[derive(Debug, Subcommand)]
+enum RiskCommand {
+    Explain(RiskExplainArgs),
+}
+
 #[derive(Debug, Subcommand)]
 enum JobCommand {
     Submit(JobSubmitArgs),
@@ -234,343 +319,307 @@ struct GateHistoryArgs {
 }
 
 #[derive(Debug, Clone, Args)]
-struct SourceSuggestArgs {
+struct SourceRulesTestArgs {
     #[arg(long)]
-    limit: Option<u32>,
-    #[arg(long)]
-    min_confidence: Option<i64>,
-    #[arg(long)]
-    domain: Option<String>,
+    name: String,
     #[arg(long)]
-    contains: Option<String>,
+    publisher: Option<String>,
     #[arg(long)]
-    status: Option<String>,
+    discovery_source: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct SoftwareListArgs {
-    #[arg(long)]
-    limit: Option<u32>,
-    #[arg(long)]
-    offset: Option<u32>,
-    #[arg(long)]
-    contains: Option<String>,
+struct SourceCalibrateArgs {
     #[arg(long)]
-    active_only: bool,
+    dry_run: bool,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct SourceReviewArgs {
+struct SourceAutoReviewArgs {
     #[arg(long)]
-    candidate_id: i64,
+    dry_run: bool,
     #[arg(long)]
-    approve: bool,
+    apply: bool,
     #[arg(long)]
-    reject: bool,
+    limit: Option<u32>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct SourceReviewBulkArgs {
+struct SourceSuggestArgs {
     #[arg(long)]
-    approve: bool,
+    auto_review: bool,
     #[arg(long)]
-    reject: bool,
+    limit: Option<u32>,
     #[arg(long)]
-    status: Option<String>,
+    min_confidence: Option<i64>,
     #[arg(long)]
     domain: Option<String>,
     #[arg(long)]
     contains: Option<String>,
     #[arg(long)]
-    limit: Option<u32>,
+    status: Option<String>,
+    #[arg(long = "software-id")]
+    software_id: Vec<i64>,
+    #[arg(long)]
+    full: bool,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct SourceListArgs {
+struct SoftwareListArgs {
     #[arg(long)]
     limit: Option<u32>,
     #[arg(long)]
     offset: Option<u32>,
     #[arg(long)]
-    status: Option<String>,
-    #[arg(long)]
-    domain: Option<String>,
-    #[arg(long)]
     contains: Option<String>,
     #[arg(long)]
-    json: bool,
-}
-
-#[derive(Debug, Clone, Args)]
-struct SourceApplyApprovedArgs {
-    #[arg(long)]
-    limit: Option<u32>,
-    #[arg(long)]
-    domain: Option<String>,
+    active_only: bool,
     #[arg(long)]
-    contains: Option<String>,
+    scope: Option<String>,
     #[arg(long)]
-    json: bool,
-}
-
-#[derive(Debug, Clone, Args)]
-struct SourceRegistryListArgs {
+    changed_since: Option<i64>,
     #[arg(long)]
-    limit: Option<u32>,
+    publisher: Option<String>,
     #[arg(long)]
-    offset: Option<u32>,
+    tag: Option<String>,
     #[arg(long)]
-    status: Option<String>,
+    owner: Option<String>,
     #[arg(long)]
-    domain: Option<String>,
+    team: Option<String>,
     #[arg(long)]
-    contains: Option<String>,
+    criticality: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct SourceRegistryDisableArgs {
-    #[arg(long)]
-    candidate_id: Option<i64>,
-    #[arg(long)]
-    status: Option<String>,
+struct SoftwareHistoryArgs {
     #[arg(long)]
-    domain: Option<String>,
+    software_id: i64,
     #[arg(long)]
-    contains: Option<String>,
+    change_type: Option<String>,
     #[arg(long)]
     limit: Option<u32>,
     #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct SourceRegistryEnableArgs {
-    #[arg(long)]
-    candidate_id: Option<i64>,
-    #[arg(long)]
-    status: Option<String>,
+struct SoftwareMergeArgs {
     #[arg(long)]
-    domain: Option<String>,
+    into: i64,
     #[arg(long)]
-    contains: Option<String>,
+    from: i64,
     #[arg(long)]
-    limit: Option<u32>,
+    dry_run: bool,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct DiscoverHistoryArgs {
+struct SoftwareDuplicatesArgs {
     #[arg(long)]
-    limit: Option<u32>,
-    #[arg(long)]
-    offset: Option<u32>,
+    include_inactive: bool,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct UpdateCheckArgs {
+struct SoftwareAddArgs {
     #[arg(long)]
-    limit: Option<u32>,
+    name: String,
     #[arg(long)]
-    offset: Option<u32>,
+    version: Option<String>,
     #[arg(long)]
-    domain: Option<String>,
+    publisher: Option<String>,
     #[arg(long)]
-    contains: Option<String>,
+    install_location: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct UpdateApplyArgs {
-    #[arg(long)]
-    candidate_id: i64,
+struct SoftwareEditArgs {
     #[arg(long)]
-    dry_run: bool,
+    software_id: i64,
     #[arg(long)]
-    confirm: bool,
+    name: Option<String>,
     #[arg(long)]
-    execution_ticket: Option<String>,
+    version: Option<String>,
     #[arg(long)]
-    simulate_failure: bool,
+    publisher: Option<String>,
     #[arg(long)]
-    simulate_rollback_failure: bool,
+    install_location: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct UpdateHistoryArgs {
-    #[arg(long)]
-    limit: Option<u32>,
+struct SoftwareRemoveArgs {
     #[arg(long)]
-    offset: Option<u32>,
+    software_id: i64,
     #[arg(long)]
-    status: Option<String>,
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct SoftwareAuditArgs {
     #[arg(long)]
-    mode: Option<String>,
+    software_id: Option<i64>,
     #[arg(long)]
-    candidate_id: Option<i64>,
+    limit: Option<u32>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct CleanupApplyArgs {
+struct SoftwareTagArgs {
     #[arg(long)]
     software_id: i64,
-    #[arg(long)]
-    dry_run: bool,
-    #[arg(long)]
-    confirm: bool,
-    #[arg(long)]
-    execution_ticket: Option<String>,
-    #[arg(long)]
-    simulate_failure: bool,
-    #[arg(long)]
-    simulate_rollback_failure: bool,
+    #[arg(long = "add")]
+    add: Vec<String>,
+    #[arg(long = "remove")]
+    remove: Vec<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct CleanupHistoryArgs {
-    #[arg(long)]
-    limit: Option<u32>,
-    #[arg(long)]
-    offset: Option<u32>,
+struct SoftwareAnnotateArgs {
     #[arg(long)]
-    status: Option<String>,
+    software_id: i64,
     #[arg(long)]
-    mode: Option<String>,
+    owner: Option<String>,
     #[arg(long)]
-    software_id: Option<i64>,
+    team: Option<String>,
     #[arg(long)]
-    execution_ticket: Option<String>,
+    criticality: Option<String>,
     #[arg(long)]
-    rollback_status: Option<String>,
+    notes: Option<String>,
     #[arg(long)]
-    contains: Option<String>,
+    license: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct AiRepairPlanArgs {
+struct SoftwareExportArgs {
     #[arg(long)]
-    software: String,
+    format: String,
     #[arg(long)]
-    issue: String,
+    active_only: bool,
     #[arg(long)]
-    verbose: bool,
+    tag: Option<String>,
     #[arg(long)]
-    json: bool,
+    output: Option<PathBuf>,
 }
 
-#[derive(Debug, Clone, Args)]
-struct AiAnalyzeArgs {
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+struct SoftwareVulnsArgs {
+    #[command(subcommand)]
+    command: Option<VulnsCommand>,
     #[arg(long)]
-    verbose: bool,
+    software_id: Option<i64>,
+    #[arg(long)]
+    min_severity: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
+#[derive(Debug, Subcommand)]
+enum VulnsCommand {
+    Sync(VulnsSyncArgs),
+}
+
 #[derive(Debug, Clone, Args)]
-struct AiRecommendArgs {
-    #[arg(long)]
-    goal: String,
+struct VulnsSyncArgs {
     #[arg(long)]
-    verbose: bool,
+    file: PathBuf,
     #[arg(long)]
     json: bool,
 }
 
-#[derive(Debug, Clone, Args)]
-struct UiSearchArgs {
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+struct SoftwareEolArgs {
+    #[command(subcommand)]
+    command: Option<EolCommand>,
     #[arg(long)]
-    q: String,
+    soon_days: Option<u32>,
     #[arg(long)]
-    limit: Option<u32>,
+    state: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
+#[derive(Debug, Subcommand)]
+enum EolCommand {
+    Sync(EolSyncArgs),
+}
+
 #[derive(Debug, Clone, Args)]
-struct UiActionRunArgs {
-    #[arg(long)]
-    id: String,
+struct EolSyncArgs {
     #[arg(long)]
-    confirm: bool,
+    file: PathBuf,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct JobSubmitArgs {
-    #[arg(long = "type")]
-    job_type: String,
-    #[arg(long)]
-    payload: String,
+struct SoftwarePinArgs {
     #[arg(long)]
-    priority: Option<i64>,
+    software_id: i64,
     #[arg(long)]
-    schedule_at: Option<i64>,
+    version: String,
     #[arg(long)]
-    simulate_failed: bool,
+    reason: Option<String>,
     #[arg(long)]
-    simulate_deadletter: bool,
+    expires_in_days: Option<u32>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct JobListArgs {
-    #[arg(long)]
-    status: Option<String>,
-    #[arg(long = "type")]
-    job_type: Option<String>,
-    #[arg(long)]
-    limit: Option<u32>,
+struct SoftwareUnpinArgs {
     #[arg(long)]
-    offset: Option<u32>,
+    software_id: i64,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct JobRetryArgs {
+struct SoftwarePinsArgs {
     #[arg(long)]
-    id: i64,
+    include_expired: bool,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct JobDeadletterListArgs {
-    #[arg(long)]
-    limit: Option<u32>,
+struct BaselineCheckArgs {
     #[arg(long)]
-    offset: Option<u32>,
+    baseline: String,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct JobReplayDeadletterArgs {
+struct BaselineHistoryArgs {
     #[arg(long)]
-    id: Option<i64>,
+    baseline: Option<String>,
+    #[arg(long)]
+    check_id: Option<String>,
     #[arg(long)]
     limit: Option<u32>,
     #[arg(long)]
@@ -578,93 +627,180 @@ struct JobReplayDeadletterArgs {
 }
 
 #[derive(Debug, Clone, Args)]
-struct JobWorkerRunArgs {
+struct PublisherAliasAddArgs {
     #[arg(long)]
-    once: bool,
+    alias: String,
+    #[arg(long)]
+    canonical: String,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct JobSchedulerRunArgs {
+struct PublisherAliasRemoveArgs {
     #[arg(long)]
-    limit: Option<u32>,
+    alias: String,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct RepoListArgs {
+struct SourceReviewArgs {
     #[arg(long)]
-    limit: Option<u32>,
+    candidate_id: Option<i64>,
     #[arg(long)]
-    offset: Option<u32>,
+    interactive: bool,
     #[arg(long)]
-    status: Option<String>,
+    approve: bool,
     #[arg(long)]
-    kind: Option<String>,
+    reject: bool,
+    #[arg(long)]
+    reviewer: Option<String>,
+    #[arg(long)]
+    note: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct RepoAddArgs {
+struct SourceReviewHistoryArgs {
     #[arg(long)]
-    name: String,
+    candidate_id: Option<i64>,
     #[arg(long)]
-    url: String,
+    reviewer: Option<String>,
     #[arg(long)]
-    kind: String,
+    batch_id: Option<String>,
     #[arg(long)]
-    priority: Option<i64>,
+    status: Option<String>,
+    #[arg(long)]
+    limit: Option<u32>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct RepoRemoveArgs {
+struct SourceReviewBulkArgs {
     #[arg(long)]
-    repo_key: String,
+    approve: bool,
+    #[arg(long)]
+    reject: bool,
+    #[arg(long)]
+    status: Option<String>,
+    #[arg(long)]
+    domain: Option<String>,
+    #[arg(long)]
+    contains: Option<String>,
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    reviewer: Option<String>,
+    #[arg(long)]
+    note: Option<String>,
+    #[arg(long)]
+    preview: bool,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct RepoSyncArgs {
+struct SourceReviewUndoArgs {
     #[arg(long)]
-    repo_key: Option<String>,
+    batch_id: String,
     #[arg(long)]
-    limit: Option<u32>,
+    reviewer: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct PackageSearchArgs {
+struct SourceListArgs {
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
+    status: Option<String>,
+    #[arg(long)]
+    domain: Option<String>,
     #[arg(long)]
     contains: Option<String>,
     #[arg(long)]
-    repo_key: Option<String>,
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct SourceApplyApprovedArgs {
     #[arg(long)]
     limit: Option<u32>,
     #[arg(long)]
-    offset: Option<u32>,
+    domain: Option<String>,
+    #[arg(long)]
+    contains: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct DownloadStartArgs {
+struct SourceRegistryExpiringArgs {
     #[arg(long)]
-    package_id: String,
+    within: Option<u32>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct SourceRegistryExportArgs {
+    #[arg(long)]
+    format: Option<String>,
+    #[arg(long)]
+    status: Option<String>,
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+struct SourceRegistryImportArgs {
+    #[arg(long)]
+    file: PathBuf,
     #[arg(long)]
     dry_run: bool,
     #[arg(long)]
+    on_conflict: Option<String>,
+    /// Re-approve candidates a local reviewer rejected (skipped otherwise).
+    #[arg(long)]
+    allow_rejected: bool,
+    #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct DownloadListArgs {
+struct SourceProbeArgs {
+    #[arg(long)]
+    candidate_id: Option<i64>,
+    #[arg(long)]
+    all: bool,
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    disable_broken: bool,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct SourceProbeHistoryArgs {
+    #[arg(long)]
+    candidate_id: Option<i64>,
+    #[arg(long)]
+    health: Option<String>,
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct SourceRegistryListArgs {
     #[arg(long)]
     limit: Option<u32>,
     #[arg(long)]
@@ -672,47 +808,105 @@ struct DownloadListArgs {
     #[arg(long)]
     status: Option<String>,
     #[arg(long)]
-    verification_status: Option<String>,
+    health: Option<String>,
+    #[arg(long)]
+    domain: Option<String>,
+    #[arg(long)]
+    contains: Option<String>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct DownloadShowArgs {
+struct SourceRegistryDisableArgs {
     #[arg(long)]
-    job_id: String,
+    candidate_id: Option<i64>,
+    #[arg(long)]
+    status: Option<String>,
+    #[arg(long)]
+    domain: Option<String>,
+    #[arg(long)]
+    contains: Option<String>,
+    #[arg(long)]
+    limit: Option<u32>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct DownloadRetryArgs {
+struct SourceRegistryEnableArgs {
     #[arg(long)]
-    job_id: String,
+    candidate_id: Option<i64>,
     #[arg(long)]
-    dry_run: bool,
+    status: Option<String>,
+    #[arg(long)]
+    domain: Option<String>,
+    #[arg(long)]
+    contains: Option<String>,
+    #[arg(long)]
+    limit: Option<u32>,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct DownloadVerifyArgs {
+struct DiscoverHistoryArgs {
     #[arg(long)]
-    job_id: String,
+    limit: Option<u32>,
     #[arg(long)]
-    simulate_failure: bool,
+    offset: Option<u32>,
     #[arg(long)]
-    simulate_hash_failure: bool,
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct DiscoverDiffArgs {
     #[arg(long)]
-    simulate_signature_failure: bool,
+    from: String,
     #[arg(long)]
-    simulate_source_policy_failure: bool,
+    to: String,
     #[arg(long)]
     json: bool,
 }
 
 #[derive(Debug, Clone, Args)]
-struct DownloadHistoryArgs {
+struct UpdateCheckArgs {
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
+    domain: Option<String>,
+    #[arg(long)]
+    contains: Option<String>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct UpdateApplyArgs {
+    #[arg(long)]
+    candidate_id: i64,
+    #[arg(long)]
+    target_version: Option<String>,
+    #[arg(long)]
+    override_pin: bool,
+    #[arg(long)]
+    dry_run: bool,
+    #[arg(long)]
+    confirm: bool,
+    #[arg(long)]
+    execution_ticket: Option<String>,
+    #[arg(long)]
+    simulate_failure: bool,
+    #[arg(long)]
+    simulate_rollback_failure: bool,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct UpdateHistoryArgs {
     #[arg(long)]
     limit: Option<u32>,
     #[arg(long)]
@@ -720,58 +914,563 @@ struct DownloadHistoryArgs {
     #[arg(long)]
     status: Option<String>,
     #[arg(long)]
-    failure_type: Option<String>,
+    mode: Option<String>,
+    #[arg(long)]
+    candidate_id: Option<i64>,
     #[arg(long)]
     json: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct AppConfig {
-    execution: ExecutionConfig,
+#[derive(Debug, Clone, Args)]
+struct CleanupApplyArgs {
+    #[arg(long)]
+    software_id: i64,
+    #[arg(long)]
+    dry_run: bool,
+    #[arg(long)]
+    confirm: bool,
+    #[arg(long)]
+    execution_ticket: Option<String>,
+    #[arg(long)]
+    simulate_failure: bool,
+    #[arg(long)]
+    simulate_rollback_failure: bool,
+    #[arg(long)]
+    json: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ExecutionConfig {
-    real_mutation_enabled: bool,
-    gate_version: String,
-    approval_record_ref: String,
+#[derive(Debug, Clone, Args)]
+struct CleanupHistoryArgs {
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
+    status: Option<String>,
+    #[arg(long)]
+    mode: Option<String>,
+    #[arg(long)]
+    software_id: Option<i64>,
+    #[arg(long)]
+    execution_ticket: Option<String>,
+    #[arg(long)]
+    rollback_status: Option<String>,
+    #[arg(long)]
+    contains: Option<String>,
+    #[arg(long)]
+    json: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct DiscoveredSoftware {
-    name: String,
-    version: String,
-    publisher: String,
-    install_location: String,
-    discovery_source: String,
+#[derive(Debug, Clone, Args)]
+struct AiRepairPlanArgs {
+    #[arg(long)]
+    software: String,
+    #[arg(long)]
+    issue: String,
+    #[arg(long)]
+    verbose: bool,
+    #[arg(long)]
+    json: bool,
 }
 
-impl Default for AppConfig {
-    fn default() -> Self {
-        Self {
-            execution: ExecutionConfig {
-                real_mutation_enabled: false,
-                gate_version: "phase3-draft-v1".to_string(),
-                approval_record_ref: String::new(),
-            },
-        }
-    }
+#[derive(Debug, Clone, Args)]
+struct AiAnalyzeArgs {
+    #[arg(long)]
+    verbose: bool,
+    #[arg(long)]
+    json: bool,
 }
 
-pub fn run() -> i32 {
-    match run_impl() {
-        Ok(()) => 0,
-        Err(err) => {
-            eprintln!("Error: {err}");
-            err.code()
-        }
-    }
+#[derive(Debug, Clone, Args)]
+struct AiRecommendArgs {
+    #[arg(long)]
+    goal: String,
+    #[arg(long)]
+    verbose: bool,
+    #[arg(long)]
+    json: bool,
 }
 
-fn run_impl() -> Result<(), CliError> {
-    let cli = Cli::parse();
-    match cli.command {
-        TopCommand::Config { command } => handle_config(command),
+#[derive(Debug, Clone, Args)]
+struct UiSearchArgs {
+    #[arg(long)]
+    q: String,
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    tag: Option<String>,
+    #[arg(long)]
+    criticality: Option<String>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct UiActionRunArgs {
+    #[arg(long)]
+    id: String,
+    #[arg(long)]
+    confirm: bool,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct RiskExplainArgs {
+    #[arg(long)]
+    action_id: String,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct JobSubmitArgs {
+    #[arg(long = "type")]
+    job_type: String,
+    #[arg(long)]
+    payload: String,
+    #[arg(long)]
+    priority: Option<i64>,
+    #[arg(long)]
+    schedule_at: Option<i64>,
+    #[arg(long)]
+    simulate_failed: bool,
+    #[arg(long)]
+    simulate_deadletter: bool,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct JobListArgs {
+    #[arg(long)]
+    status: Option<String>,
+    #[arg(long = "type")]
+    job_type: Option<String>,
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct JobRetryArgs {
+    #[arg(long)]
+    id: i64,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct JobDeadletterListArgs {
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct JobReplayDeadletterArgs {
+    #[arg(long)]
+    id: Option<i64>,
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct JobWorkerRunArgs {
+    #[arg(long)]
+    once: bool,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct JobSchedulerRunArgs {
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct RepoListArgs {
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
+    status: Option<String>,
+    #[arg(long)]
+    kind: Option<String>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct RepoAddArgs {
+    #[arg(long)]
+    name: String,
+    #[arg(long)]
+    url: String,
+    #[arg(long)]
+    kind: String,
+    #[arg(long)]
+    priority: Option<i64>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct RepoRemoveArgs {
+    #[arg(long)]
+    repo_key: String,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct RepoSyncArgs {
+    #[arg(long)]
+    repo_key: Option<String>,
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct PackageSearchArgs {
+    #[arg(long)]
+    contains: Option<String>,
+    #[arg(long)]
+    repo_key: Option<String>,
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct DownloadStartArgs {
+    #[arg(long)]
+    package_id: String,
+    #[arg(long)]
+    dry_run: bool,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct DownloadListArgs {
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
+    status: Option<String>,
+    #[arg(long)]
+    verification_status: Option<String>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct DownloadShowArgs {
+    #[arg(long)]
+    job_id: String,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct DownloadRetryArgs {
+    #[arg(long)]
+    job_id: String,
+    #[arg(long)]
+    dry_run: bool,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct DownloadVerifyArgs {
+    #[arg(long)]
+    job_id: String,
+    #[arg(long)]
+    simulate_failure: bool,
+    #[arg(long)]
+    simulate_hash_failure: bool,
+    #[arg(long)]
+    simulate_signature_failure: bool,
+    #[arg(long)]
+    simulate_source_policy_failure: bool,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct DownloadHistoryArgs {
+    #[arg(long)]
+    limit: Option<u32>,
+    #[arg(long)]
+    offset: Option<u32>,
+    #[arg(long)]
+    status: Option<String>,
+    #[arg(long)]
+    failure_type: Option<String>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AppConfig {
+    execution: ExecutionConfig,
+    #[serde(default)]
+    retention: RetentionConfig,
+    #[serde(default)]
+    eol: EolConfig,
+    #[serde(default)]
+    download_source_policy: DownloadSourcePolicyConfig,
+    #[serde(default)]
+    auto_review: AutoReviewConfig,
+    #[serde(default)]
+    calibration: CalibrationConfig,
+    #[serde(default)]
+    source_url_policy: SourceUrlPolicyConfig,
+    #[serde(default)]
+    source_probe: SourceProbeConfig,
+    #[serde(default)]
+    source_registry: SourceRegistryConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExecutionConfig {
+    real_mutation_enabled: bool,
+    gate_version: String,
+    approval_record_ref: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RetentionConfig {
+    discovery_snapshots: u32,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            discovery_snapshots: 30,
+        }
+    }
+}
+
+impl RetentionConfig {
+    fn discovery_snapshot_scans(&self) -> Result<i64, CliError> {
+        if self.discovery_snapshots == 0 {
+            return Err(CliError::Config(
+                "retention.discovery_snapshots must be >= 1".to_string(),
+            ));
+        }
+        Ok(i64::from(self.discovery_snapshots))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EolConfig {
+    soon_days: u32,
+}
+
+impl Default for EolConfig {
+    fn default() -> Self {
+        Self { soon_days: 90 }
+    }
+}
+
+/// Domains are matched exactly; a `*.` prefix also covers subdomains.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DownloadSourcePolicyConfig {
+    #[serde(default)]
+    untrusted_domains: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AutoReviewConfig {
+    #[serde(default)]
+    trusted_domains: Vec<String>,
+    #[serde(default = "default_auto_review_min_confidence")]
+    min_confidence: i64,
+    #[serde(default)]
+    reject_below_confidence: Option<i64>,
+    #[serde(default = "default_true")]
+    require_publisher_match: bool,
+    #[serde(default)]
+    deny_patterns: Vec<String>,
+    #[serde(default)]
+    run_on_suggest: bool,
+}
+
+fn default_auto_review_min_confidence() -> i64 {
+    70
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct CalibrationConfig {
+    max_adjustment: u32,
+    min_samples: u32,
+    rejected_rule_threshold: f64,
+}
+
+impl Default for CalibrationConfig {
+    fn default() -> Self {
+        Self {
+            max_adjustment: 10,
+            min_samples: 3,
+            rejected_rule_threshold: 0.25,
+        }
+    }
+}
+
+/// Source URLs must be HTTPS, name a host and carry no credentials unless relaxed here.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SourceUrlPolicyConfig {
+    allow_http: bool,
+    allow_ip_literals: bool,
+    allow_credentials: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct SourceProbeConfig {
+    timeout_secs: u64,
+    max_redirects: u32,
+    auto_disable_broken: bool,
+    /// Lets probes reach loopback, private and `.local`-style hosts (off outside test setups).
+    allow_private_hosts: bool,
+}
+
+impl Default for SourceProbeConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 10,
+            max_redirects: 5,
+            auto_disable_broken: false,
+            allow_private_hosts: false,
+        }
+    }
+}
+
+/// Registry approvals expire this many days after the candidate's latest approval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct SourceRegistryConfig {
+    review_interval_days: u32,
+}
+
+impl Default for SourceRegistryConfig {
+    fn default() -> Self {
+        Self {
+            review_interval_days: 180,
+        }
+    }
+}
+
+impl SourceRegistryConfig {
+    fn review_interval_secs(&self) -> Result<i64, CliError> {
+        if self.review_interval_days == 0 {
+            return Err(CliError::Config(
+                "source_registry.review_interval_days must be >= 1".to_string(),
+            ));
+        }
+        Ok(i64::from(self.review_interval_days) * 86_400)
+    }
+}
+
+impl Default for AutoReviewConfig {
+    fn default() -> Self {
+        Self {
+            trusted_domains: Vec::new(),
+            min_confidence: default_auto_review_min_confidence(),
+            reject_below_confidence: None,
+            require_publisher_match: true,
+            deny_patterns: Vec::new(),
+            run_on_suggest: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DiscoveredSoftware {
+    name: String,
+    version: String,
+    publisher: String,
+    install_location: String,
+    discovery_source: String,
+    #[serde(default)]
+    uninstall_string: Option<String>,
+    #[serde(default)]
+    quiet_uninstall_string: Option<String>,
+    #[serde(default)]
+    install_date: Option<String>,
+    #[serde(default)]
+    estimated_size_kb: Option<i64>,
+    #[serde(default)]
+    product_code: Option<String>,
+    #[serde(default)]
+    upgrade_code: Option<String>,
+    #[serde(default)]
+    install_scope: Option<String>,
+    #[serde(default)]
+    license: Option<String>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            execution: ExecutionConfig {
+                real_mutation_enabled: false,
+                gate_version: "phase3-draft-v1".to_string(),
+                approval_record_ref: String::new(),
+            },
+            retention: RetentionConfig::default(),
+            eol: EolConfig::default(),
+            download_source_policy: DownloadSourcePolicyConfig::default(),
+            auto_review: AutoReviewConfig::default(),
+            calibration: CalibrationConfig::default(),
+            source_url_policy: SourceUrlPolicyConfig::default(),
+            source_probe: SourceProbeConfig::default(),
+            source_registry: SourceRegistryConfig::default(),
+        }
+    }
+}
+
+pub fn run() -> i32 {
+    match run_impl() {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            err.code()
+        }
+    }
+}
+
+fn run_impl() -> Result<(), CliError> {
+    let cli = Cli::parse();
+    match cli.command {
+        TopCommand::Config { command } => handle_config(command),
         TopCommand::Software { command } => handle_software(command),
         TopCommand::Source { command } => handle_source(command),
         TopCommand::Update { command } => handle_update(command),
@@ -782,341 +1481,5431 @@ fn run_impl() -> Result<(), CliError> {
         TopCommand::Repo { command } => handle_repo(command),
         TopCommand::Package { command } => handle_package(command),
         TopCommand::Download { command } => handle_download(command),
+        TopCommand::Risk { command } => handle_risk(command),
+    }
+}
+
+fn handle_config(command: ConfigCommand) -> Result<(), CliError> {
+    match command {
+        ConfigCommand::Init(args) => config_init(args.json),
+        ConfigCommand::GateShow(args) => config_gate_show(args.json),
+        ConfigCommand::GateSet(args) => config_gate_set(args),
+        ConfigCommand::GateHistory(args) => config_gate_history(args),
+    }
+}
+
+fn handle_software(command: SoftwareCommand) -> Result<(), CliError> {
+    match command {
+        SoftwareCommand::Discover { command } => match command {
+            DiscoverCommand::Scan(args) => software_discover_scan(args.json),
+            DiscoverCommand::History(args) => software_discover_history(args),
+            DiscoverCommand::Diff(args) => software_discover_diff(args),
+        },
+        SoftwareCommand::List(args) => software_list(args),
+        SoftwareCommand::History(args) => software_history(args),
+        SoftwareCommand::Merge(args) => software_merge(args),
+        SoftwareCommand::Duplicates(args) => software_duplicates(args),
+        SoftwareCommand::Add(args) => software_add(args),
+        SoftwareCommand::Edit(args) => software_edit(args),
+        SoftwareCommand::Remove(args) => software_remove(args),
+        SoftwareCommand::Audit(args) => software_audit(args),
+        SoftwareCommand::Tag(args) => software_tag(args),
+        SoftwareCommand::Annotate(args) => software_annotate(args),
+        SoftwareCommand::Export(args) => software_export(args),
+        SoftwareCommand::Vulns(args) => match args.command {
+            Some(VulnsCommand::Sync(sync)) => software_vulns_sync(sync),
+            None => software_vulns(args.software_id, args.min_severity.as_deref(), args.json),
+        },
+        SoftwareCommand::Pin(args) => software_pin(args),
+        SoftwareCommand::Unpin(args) => software_unpin(args),
+        SoftwareCommand::Pins(args) => software_pins(args),
+        SoftwareCommand::Eol(args) => match args.command {
+            Some(EolCommand::Sync(sync)) => software_eol_sync(sync),
+            None => software_eol(args.soon_days, args.state.as_deref(), args.json),
+        },
+        SoftwareCommand::Baseline { command } => match command {
+            BaselineCommand::Check(args) => software_baseline_check(args),
+            BaselineCommand::List(args) => software_baseline_list(args.json),
+            BaselineCommand::History(args) => software_baseline_history(args),
+        },
+        SoftwareCommand::Publisher {
+            command: PublisherCommand::Alias { command },
+        } => match command {
+            PublisherAliasCommand::Add(args) => publisher_alias_add(args),
+            PublisherAliasCommand::List(args) => publisher_alias_list(args.json),
+            PublisherAliasCommand::Remove(args) => publisher_alias_remove(args),
+        },
+    }
+}
+
+fn handle_source(command: SourceCommand) -> Result<(), CliError> {
+    match command {
+        SourceCommand::Suggest(args) => source_suggest(args),
+        SourceCommand::Review(args) => source_review(args),
+        SourceCommand::ReviewBulk(args) => source_review_bulk(args),
+        SourceCommand::ReviewHistory(args) => source_review_history(args),
+        SourceCommand::ReviewUndo(args) => source_review_undo(args),
+        SourceCommand::AutoReview(args) => source_auto_review(args),
+        SourceCommand::Calibrate(args) => source_calibrate(args),
+        SourceCommand::List(args) => source_list(args),
+        SourceCommand::ApplyApproved(args) => source_apply_approved(args),
+        SourceCommand::RegistryList(args) => source_registry_list(args),
+        SourceCommand::RegistryDisable(args) => source_registry_disable(args),
+        SourceCommand::RegistryEnable(args) => source_registry_enable(args),
+        SourceCommand::RegistryExpiring(args) => source_registry_expiring(args),
+        SourceCommand::RegistryExport(args) => source_registry_export(args),
+        SourceCommand::RegistryImport(args) => source_registry_import(args),
+        SourceCommand::Probe(args) => source_probe(args),
+        SourceCommand::ProbeHistory(args) => source_probe_history(args),
+        SourceCommand::Rules { command } => match command {
+            SourceRulesCommand::List(args) => source_rules_list(args.json),
+            SourceRulesCommand::Test(args) => source_rules_test(args),
+        },
+    }
+}
+
+fn handle_update(command: UpdateCommand) -> Result<(), CliError> {
+    match command {
+        UpdateCommand::Check(args) => update_check(args),
+        UpdateCommand::Apply(args) => update_apply(args),
+        UpdateCommand::History(args) => update_history(args),
+    }
+}
+
+fn handle_ai(command: AiCommand) -> Result<(), CliError> {
+    match command {
+        AiCommand::Analyze(args) => ai_analyze(args),
+        AiCommand::Recommend(args) => ai_recommend(args),
+        AiCommand::RepairPlan(args) => ai_repair_plan(args),
+    }
+}
+
+fn handle_ui(command: UiCommand) -> Result<(), CliError> {
+    match command {
+        UiCommand::Search(args) => ui_search(args),
+        UiCommand::ActionRun(args) => ui_action_run(args),
+    }
+}
+
+fn handle_risk(command: RiskCommand) -> Result<(), CliError> {
+    match command {
+        RiskCommand::Explain(args) => risk_explain(args),
+    }
+}
+
+fn handle_job(command: JobCommand) -> Result<(), CliError> {
+    match command {
+        JobCommand::Submit(args) => job_submit(args),
+        JobCommand::List(args) => job_list(args),
+        JobCommand::Retry(args) => job_retry(args),
+        JobCommand::DeadletterList(args) => job_deadletter_list(args),
+        JobCommand::ReplayDeadletter(args) => job_replay_deadletter(args),
+        JobCommand::WorkerRun(args) => job_worker_run(args),
+        JobCommand::SchedulerRun(args) => job_scheduler_run(args),
+    }
+}
+
+fn handle_cleanup(command: CleanupCommand) -> Result<(), CliError> {
+    match command {
+        CleanupCommand::Apply(args) => cleanup_apply(args),
+        CleanupCommand::History(args) => cleanup_history(args),
+    }
+}
+
+fn handle_repo(command: RepoCommand) -> Result<(), CliError> {
+    match command {
+        RepoCommand::List(args) => repo_list(args),
+        RepoCommand::Add(args) => repo_add(args),
+        RepoCommand::Remove(args) => repo_remove(args),
+        RepoCommand::Sync(args) => repo_sync(args),
+    }
+}
+
+fn handle_package(command: PackageCommand) -> Result<(), CliError> {
+    match command {
+        PackageCommand::Search(args) => package_search(args),
+    }
+}
+
+fn handle_download(command: DownloadCommand) -> Result<(), CliError> {
+    match command {
+        DownloadCommand::Start(args) => download_start(args),
+        DownloadCommand::List(args) => download_list(args),
+        DownloadCommand::Show(args) => download_show(args),
+        DownloadCommand::Retry(args) => download_retry(args),
+        DownloadCommand::Verify(args) => download_verify(args),
+        DownloadCommand::History(args) => download_history(args),
+    }
+}
+
+fn software_discover_scan(as_json: bool) -> Result<(), CliError> {
+    let retained_scans = load_config()?.retention.discovery_snapshot_scans()?;
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let discovered = match discovery_fixture_path() {
+        Some(path) => load_discovery_fixture(&path)?,
+        None => discover_registry_software()?,
+    };
+    let total_seen = discovered.len() as i64;
+    let scan_id = next_operation_id("discover", total_seen);
+    let mut inserted = 0_i64;
+    let mut updated = 0_i64;
+    let mut reactivated = 0_i64;
+    let mut deactivated = 0_i64;
+    let mut skipped = 0_i64;
+    let mut version_changes = 0_i64;
+    let now = unix_ts();
+    let mut seen_ids: HashSet<i64> = HashSet::new();
+    let mut identity_matched = 0_i64;
+    let mut manual_skipped = 0_i64;
+    let aliases = load_publisher_aliases(&conn)?;
+    let scan_fingerprints: HashSet<String> = discovered
+        .iter()
+        .map(|item| make_fingerprint(&item.name, &item.publisher, &item.install_location))
+        .collect();
+
+    for item in discovered {
+        let fingerprint = make_fingerprint(&item.name, &item.publisher, &item.install_location);
+        if fingerprint.is_empty() {
+            skipped += 1;
+            continue;
+        }
+        let publisher_canonical = canonical_publisher(&item.publisher, &aliases);
+        let identity_key = make_identity_key(
+            &item.name,
+            &publisher_canonical,
+            item.product_code.as_deref(),
+            item.upgrade_code.as_deref(),
+        );
+
+        let existing = match find_inventory_by_fingerprint(&conn, &fingerprint)? {
+            Some(found) => Some(found),
+            None => find_inventory_by_identity(
+                &conn,
+                &identity_key,
+                &item.discovery_source,
+                &seen_ids,
+                &scan_fingerprints,
+            )?,
+        };
+
+        // Manual entries belong to whoever added them: a scan neither rewrites nor reactivates
+        // them, so edits and removals stay exactly as audited.
+        if let Some(found) = existing
+            .as_ref()
+            .filter(|found| found.discovery_source == "manual")
+        {
+            seen_ids.insert(found.id);
+            manual_skipped += 1;
+            continue;
+        }
+
+        let software_id = match existing {
+            None => {
+                conn.execute(
+                    r#"
+                    INSERT INTO software_inventory
+                    (name, version, publisher, install_location, discovery_source, source_confidence, first_seen_at, last_seen_at, is_active, fingerprint,
+                     uninstall_string, quiet_uninstall_string, install_date, estimated_size_kb, product_code, install_scope, identity_key,
+                     publisher_canonical, license, upgrade_code)
+                    VALUES (?1, ?2, ?3, ?4, ?5, 80, ?6, ?6, 1, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+                    "#,
+                    params![
+                        item.name,
+                        item.version,
+                        item.publisher,
+                        item.install_location,
+                        item.discovery_source,
+                        now,
+                        fingerprint,
+                        item.uninstall_string,
+                        item.quiet_uninstall_string,
+                        item.install_date,
+                        item.estimated_size_kb,
+                        item.product_code,
+                        item.install_scope,
+                        identity_key,
+                        publisher_canonical,
+                        item.license,
+                        item.upgrade_code
+                    ],
+                )?;
+                inserted += 1;
+                let software_id = conn.last_insert_rowid();
+                record_version_change(
+                    &conn,
+                    software_id,
+                    &scan_id,
+                    now,
+                    "installed",
+                    None,
+                    Some(&item.version),
+                )?;
+                software_id
+            }
+            Some(found) => {
+                // A fingerprint match keeps the row's fingerprint; an identity match re-keys the
+                // row to the new fingerprint; a merged fingerprint feeds the surviving row as-is.
+                let rekey = found.matched_via == "identity";
+                let refresh_identity = found.matched_via != "merged";
+                conn.execute(
+                    r#"
+                    UPDATE software_inventory SET
+                        name = ?1,
+                        version = ?2,
+                        publisher = ?3,
+                        install_location = ?4,
+                        discovery_source = ?5,
+                        source_confidence = 80,
+                        last_seen_at = ?6,
+                        is_active = 1,
+                        uninstall_string = ?7,
+                        quiet_uninstall_string = ?8,
+                        install_date = ?9,
+                        estimated_size_kb = ?10,
+                        product_code = ?11,
+                        install_scope = ?12,
+                        fingerprint = CASE WHEN ?13 THEN ?14 ELSE fingerprint END,
+                        identity_key = CASE WHEN ?15 THEN ?16 ELSE identity_key END,
+                        publisher_canonical = ?17,
+                        license = COALESCE(?18, license),
+                        upgrade_code = ?19
+                    WHERE id = ?20
+                    "#,
+                    params![
+                        item.name,
+                        item.version,
+                        item.publisher,
+                        item.install_location,
+                        item.discovery_source,
+                        now,
+                        item.uninstall_string,
+                        item.quiet_uninstall_string,
+                        item.install_date,
+                        item.estimated_size_kb,
+                        item.product_code,
+                        item.install_scope,
+                        rekey,
+                        fingerprint,
+                        refresh_identity,
+                        identity_key,
+                        publisher_canonical,
+                        item.license,
+                        item.upgrade_code,
+                        found.id
+                    ],
+                )?;
+                updated += 1;
+                if rekey {
+                    identity_matched += 1;
+                }
+                if !found.is_active {
+                    reactivated += 1;
+                    record_version_change(
+                        &conn,
+                        found.id,
+                        &scan_id,
+                        now,
+                        "installed",
+                        Some(&found.version),
+                        Some(&item.version),
+                    )?;
+                } else if found.version != item.version {
+                    version_changes += 1;
+                    record_version_change(
+                        &conn,
+                        found.id,
+                        &scan_id,
+                        now,
+                        "version_changed",
+                        Some(&found.version),
+                        Some(&item.version),
+                    )?;
+                }
+                found.id
+            }
+        };
+        seen_ids.insert(software_id);
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO software_scan_snapshot (scan_id, software_id, fingerprint, version)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+            params![scan_id, software_id, fingerprint, item.version],
+        )?;
+    }
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, version FROM software_inventory
+        WHERE discovery_source = 'registry' AND is_active = 1 AND merged_into IS NULL
+        "#,
+    )?;
+    let existing_rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (id, version) in existing_rows {
+        if !seen_ids.contains(&id) {
+            conn.execute(
+                "UPDATE software_inventory SET is_active = 0 WHERE id = ?1",
+                params![id],
+            )?;
+            record_version_change(&conn, id, &scan_id, now, "removed", Some(&version), None)?;
+            deactivated += 1;
+        }
+    }
+
+    let active_after: i64 = conn.query_row(
+        "SELECT COUNT(1) FROM software_inventory WHERE discovery_source = 'registry' AND is_active = 1",
+        [],
+        |r| r.get(0),
+    )?;
+
+    conn.execute(
+        r#"
+        INSERT INTO software_discovery_history
+        (scan_id, ts, source, total_seen, inserted, updated, reactivated, deactivated, skipped, active_after)
+        VALUES (?1, ?2, 'registry', ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        "#,
+        params![
+            scan_id,
+            now,
+            total_seen,
+            inserted,
+            updated,
+            reactivated,
+            deactivated,
+            skipped,
+            active_after
+        ],
+    )?;
+
+    let snapshots_pruned = conn.execute(
+        r#"
+        DELETE FROM software_scan_snapshot
+        WHERE scan_id NOT IN (
+            SELECT scan_id FROM software_discovery_history ORDER BY id DESC LIMIT ?1
+        )
+        "#,
+        params![retained_scans],
+    )?;
+
+    let payload = json!({
+        "scan_id": scan_id,
+        "source": "registry",
+        "total_seen": total_seen,
+        "inserted": inserted,
+        "updated": updated,
+        "reactivated": reactivated,
+        "deactivated": deactivated,
+        "version_changes": version_changes,
+        "identity_matched": identity_matched,
+        "active_after": active_after,
+        "skipped": skipped,
+        "manual_skipped": manual_skipped,
+        "snapshot_scans_retained": retained_scans,
+        "snapshot_rows_pruned": snapshots_pruned,
+        "duration_ms": 0
+    });
+    print_payload(as_json, payload, "Discovery scan finished.")
+}
+
+fn software_discover_history(args: DiscoverHistoryArgs) -> Result<(), CliError> {
+    let limit = i64::from(args.limit.unwrap_or(100));
+    let offset = i64::from(args.offset.unwrap_or(0));
+    if limit <= 0 {
+        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT scan_id, ts, source, total_seen, inserted, updated, reactivated, deactivated, skipped, active_after
+        FROM software_discovery_history
+        ORDER BY id DESC
+        LIMIT ?1 OFFSET ?2
+        "#,
+    )?;
+    let rows = stmt.query_map(params![limit, offset], |row| {
+        Ok(json!({
+            "scan_id": row.get::<_, String>(0)?,
+            "timestamp": row.get::<_, i64>(1)?,
+            "source": row.get::<_, String>(2)?,
+            "total_seen": row.get::<_, i64>(3)?,
+            "inserted": row.get::<_, i64>(4)?,
+            "updated": row.get::<_, i64>(5)?,
+            "reactivated": row.get::<_, i64>(6)?,
+            "deactivated": row.get::<_, i64>(7)?,
+            "skipped": row.get::<_, i64>(8)?,
+            "active_after": row.get::<_, i64>(9)?
+        }))
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(args.json, json!([]), "No discover history entries found.");
+    }
+    print_payload(args.json, json!(payload), "Discover history listed.")
+}
+
+fn software_discover_diff(args: DiscoverDiffArgs) -> Result<(), CliError> {
+    let from = args.from.trim();
+    let to = args.to.trim();
+    if from.is_empty() || to.is_empty() {
+        return Err(CliError::Usage("--from and --to are required".to_string()));
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let before = load_scan_snapshot(&conn, from)?;
+    let after = load_scan_snapshot(&conn, to)?;
+
+    let mut added: Vec<serde_json::Value> = Vec::new();
+    let mut removed: Vec<serde_json::Value> = Vec::new();
+    let mut upgraded: Vec<serde_json::Value> = Vec::new();
+    let mut downgraded: Vec<serde_json::Value> = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+
+    for (software_id, (name, version)) in &after {
+        match before.get(software_id) {
+            None => {
+                lines.push(format!("+ {name} {version}"));
+                added.push(json!({"software_id": software_id, "name": name, "version": version}));
+            }
+            Some((_, old_version)) => {
+                let entry = json!({
+                    "software_id": software_id,
+                    "name": name,
+                    "from_version": old_version,
+                    "to_version": version
+                });
+                match compare_versions(old_version, version) {
+                    std::cmp::Ordering::Less => {
+                        lines.push(format!("^ {name} {old_version} -> {version}"));
+                        upgraded.push(entry);
+                    }
+                    std::cmp::Ordering::Greater => {
+                        lines.push(format!("v {name} {old_version} -> {version}"));
+                        downgraded.push(entry);
+                    }
+                    std::cmp::Ordering::Equal => {}
+                }
+            }
+        }
+    }
+    for (software_id, (name, version)) in &before {
+        if !after.contains_key(software_id) {
+            lines.push(format!("- {name} {version}"));
+            removed.push(json!({"software_id": software_id, "name": name, "version": version}));
+        }
+    }
+
+    let payload = json!({
+        "from": from,
+        "to": to,
+        "summary": {
+            "added": added.len(),
+            "removed": removed.len(),
+            "upgraded": upgraded.len(),
+            "downgraded": downgraded.len()
+        },
+        "added": added,
+        "removed": removed,
+        "upgraded": upgraded,
+        "downgraded": downgraded
+    });
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        for line in &lines {
+            println!("{line}");
+        }
+        println!(
+            "Discover diff {from} -> {to}: added={}, removed={}, upgraded={}, downgraded={}",
+            added.len(),
+            removed.len(),
+            upgraded.len(),
+            downgraded.len()
+        );
+    }
+    Ok(())
+}
+
+fn load_scan_snapshot(
+    conn: &Connection,
+    scan_id: &str,
+) -> Result<std::collections::BTreeMap<i64, (String, String)>, CliError> {
+    let known: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM software_discovery_history WHERE scan_id = ?1)",
+        params![scan_id],
+        |r| r.get::<_, i64>(0).map(|v| v == 1),
+    )?;
+    if !known {
+        return Err(CliError::Usage(format!("scan_id {scan_id} not found")));
+    }
+    let retained: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM software_scan_snapshot WHERE scan_id = ?1)",
+        params![scan_id],
+        |r| r.get::<_, i64>(0).map(|v| v == 1),
+    )?;
+    let total_seen: i64 = conn.query_row(
+        "SELECT total_seen FROM software_discovery_history WHERE scan_id = ?1",
+        params![scan_id],
+        |r| r.get(0),
+    )?;
+    if !retained && total_seen > 0 {
+        return Err(CliError::Usage(format!(
+            "snapshot for scan_id {scan_id} is no longer retained"
+        )));
+    }
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT s.software_id, COALESCE(i.name, s.fingerprint), s.version
+        FROM software_scan_snapshot s
+        LEFT JOIN software_inventory i ON i.id = s.software_id
+        WHERE s.scan_id = ?1
+        ORDER BY s.id ASC
+        "#,
+    )?;
+    let rows = stmt.query_map(params![scan_id], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            (row.get::<_, String>(1)?, row.get::<_, String>(2)?),
+        ))
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn software_list(args: SoftwareListArgs) -> Result<(), CliError> {
+    let limit = i64::from(args.limit.unwrap_or(200));
+    let offset = i64::from(args.offset.unwrap_or(0));
+    if limit <= 0 {
+        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+    }
+    if let Some(scope) = args.scope.as_deref() {
+        validate_install_scope(scope)?;
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut sql = String::from(
+        r#"
+        SELECT id, name, version, publisher, install_location, discovery_source, source_confidence, first_seen_at, last_seen_at, is_active,
+               uninstall_string, quiet_uninstall_string, install_date, estimated_size_kb, product_code, install_scope,
+               identity_key, merged_into, publisher_canonical, owner, team, criticality, notes,
+               license, upgrade_code,
+               (SELECT group_concat(tag, ',') FROM (SELECT tag FROM software_tag t WHERE t.software_id = software_inventory.id ORDER BY tag))
+        FROM software_inventory
+        "#,
+    );
+
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if args.active_only {
+        clauses.push("is_active = 1".to_string());
+    }
+    if let Some(contains) = args.contains.clone() {
+        clauses.push(
+            "(name LIKE ? OR publisher LIKE ? OR publisher_canonical LIKE ? OR version LIKE ?)"
+                .to_string(),
+        );
+        let like = format!("%{contains}%");
+        values.push(Value::Text(like.clone()));
+        values.push(Value::Text(like.clone()));
+        values.push(Value::Text(like.clone()));
+        values.push(Value::Text(like));
+    }
+    if let Some(publisher) = args.publisher.as_deref() {
+        let aliases = load_publisher_aliases(&conn)?;
+        clauses.push("publisher_canonical = ? COLLATE NOCASE".to_string());
+        values.push(Value::Text(canonical_publisher(publisher, &aliases)));
+    }
+    if let Some(tag) = args.tag.as_deref() {
+        clauses.push("id IN (SELECT software_id FROM software_tag WHERE tag = ?)".to_string());
+        values.push(Value::Text(normalize_tag(tag)?));
+    }
+    if let Some(owner) = args.owner.clone() {
+        clauses.push("owner = ? COLLATE NOCASE".to_string());
+        values.push(Value::Text(owner));
+    }
+    if let Some(team) = args.team.clone() {
+        clauses.push("team = ? COLLATE NOCASE".to_string());
+        values.push(Value::Text(team));
+    }
+    if let Some(criticality) = args.criticality.as_deref() {
+        validate_criticality(criticality)?;
+        clauses.push("criticality = ?".to_string());
+        values.push(Value::Text(criticality.to_string()));
+    }
+    if let Some(scope) = args.scope.clone() {
+        clauses.push("install_scope = ?".to_string());
+        values.push(Value::Text(scope));
+    }
+    if let Some(since) = args.changed_since {
+        clauses.push(
+            "id IN (SELECT software_id FROM software_version_history WHERE ts >= ?)".to_string(),
+        );
+        values.push(Value::Integer(since));
+    }
+    if !clauses.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&clauses.join(" AND "));
+    }
+    sql.push_str(" ORDER BY name ASC LIMIT ? OFFSET ?");
+    values.push(Value::Integer(limit));
+    values.push(Value::Integer(offset));
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+        Ok(json!({
+            "id": row.get::<_, i64>(0)?,
+            "name": row.get::<_, String>(1)?,
+            "version": row.get::<_, String>(2)?,
+            "publisher": row.get::<_, String>(3)?,
+            "publisher_canonical": row.get::<_, Option<String>>(18)?,
+            "install_location": row.get::<_, String>(4)?,
+            "discovery_source": row.get::<_, String>(5)?,
+            "source_confidence": row.get::<_, i64>(6)?,
+            "first_seen_at": row.get::<_, i64>(7)?,
+            "last_seen_at": row.get::<_, i64>(8)?,
+            "is_active": row.get::<_, i64>(9)? == 1,
+            "uninstall_string": row.get::<_, Option<String>>(10)?,
+            "quiet_uninstall_string": row.get::<_, Option<String>>(11)?,
+            "install_date": row.get::<_, Option<String>>(12)?,
+            "estimated_size_kb": row.get::<_, Option<i64>>(13)?,
+            "product_code": row.get::<_, Option<String>>(14)?,
+            "upgrade_code": row.get::<_, Option<String>>(24)?,
+            "install_scope": row.get::<_, Option<String>>(15)?,
+            "identity_key": row.get::<_, Option<String>>(16)?,
+            "merged_into": row.get::<_, Option<i64>>(17)?,
+            "owner": row.get::<_, Option<String>>(19)?,
+            "team": row.get::<_, Option<String>>(20)?,
+            "criticality": row.get::<_, Option<String>>(21)?,
+            "notes": row.get::<_, Option<String>>(22)?,
+            "license": row.get::<_, Option<String>>(23)?,
+            "tags": split_tags(row.get::<_, Option<String>>(25)?)
+        }))
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(args.json, json!([]), "No software entries found.");
+    }
+    print_payload(args.json, json!(payload), "Software entries listed.")
+}
+
+fn software_history(args: SoftwareHistoryArgs) -> Result<(), CliError> {
+    let limit = i64::from(args.limit.unwrap_or(100));
+    let offset = i64::from(args.offset.unwrap_or(0));
+    if limit <= 0 {
+        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+    }
+    if let Some(change_type) = args.change_type.as_deref() {
+        match change_type {
+            "installed" | "version_changed" | "removed" => {}
+            _ => {
+                return Err(CliError::Usage(
+                    "--change-type must be one of: installed, version_changed, removed".to_string(),
+                ));
+            }
+        }
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let software_name: Option<String> = conn
+        .query_row(
+            "SELECT name FROM software_inventory WHERE id = ?1",
+            params![args.software_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    let Some(software_name) = software_name else {
+        return Err(CliError::Usage(format!(
+            "software not found for --software-id {}",
+            args.software_id
+        )));
+    };
+
+    let mut sql = String::from(
+        r#"
+        SELECT id, software_id, scan_id, ts, change_type, old_version, new_version
+        FROM software_version_history
+        WHERE software_id = ?
+        "#,
+    );
+    let mut values: Vec<Value> = vec![Value::Integer(args.software_id)];
+    if let Some(change_type) = args.change_type.clone() {
+        sql.push_str(" AND change_type = ?");
+        values.push(Value::Text(change_type));
+    }
+    sql.push_str(" ORDER BY ts DESC, id DESC LIMIT ? OFFSET ?");
+    values.push(Value::Integer(limit));
+    values.push(Value::Integer(offset));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+        Ok(json!({
+            "id": row.get::<_, i64>(0)?,
+            "software_id": row.get::<_, i64>(1)?,
+            "software_name": software_name,
+            "scan_id": row.get::<_, String>(2)?,
+            "timestamp": row.get::<_, i64>(3)?,
+            "change_type": row.get::<_, String>(4)?,
+            "old_version": row.get::<_, Option<String>>(5)?,
+            "new_version": row.get::<_, Option<String>>(6)?
+        }))
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(args.json, json!([]), "No version history entries found.");
+    }
+    print_payload(args.json, json!(payload), "Version history listed.")
+}
+
+fn record_version_change(
+    conn: &Connection,
+    software_id: i64,
+    scan_id: &str,
+    ts: i64,
+    change_type: &str,
+    old_version: Option<&str>,
+    new_version: Option<&str>,
+) -> Result<(), CliError> {
+    conn.execute(
+        r#"
+        INSERT INTO software_version_history
+        (software_id, scan_id, ts, change_type, old_version, new_version)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        params![
+            software_id,
+            scan_id,
+            ts,
+            change_type,
+            old_version,
+            new_version
+        ],
+    )?;
+    Ok(())
+}
+
+struct InventoryMatch {
+    id: i64,
+    is_active: bool,
+    version: String,
+    discovery_source: String,
+    matched_via: &'static str,
+}
+
+fn load_inventory_match(
+    conn: &Connection,
+    id: i64,
+    matched_via: &'static str,
+) -> Result<Option<InventoryMatch>, CliError> {
+    Ok(conn
+        .query_row(
+            "SELECT id, is_active, version, discovery_source FROM software_inventory WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok(InventoryMatch {
+                    id: row.get(0)?,
+                    is_active: row.get::<_, i64>(1)? == 1,
+                    version: row.get(2)?,
+                    discovery_source: row.get(3)?,
+                    matched_via,
+                })
+            },
+        )
+        .optional()?)
+}
+
+fn find_inventory_by_fingerprint(
+    conn: &Connection,
+    fingerprint: &str,
+) -> Result<Option<InventoryMatch>, CliError> {
+    let found: Option<(i64, Option<i64>)> = conn
+        .query_row(
+            "SELECT id, merged_into FROM software_inventory WHERE fingerprint = ?1",
+            params![fingerprint],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let Some((id, merged_into)) = found else {
+        return Ok(None);
+    };
+    match merged_into {
+        None => load_inventory_match(conn, id, "fingerprint"),
+        Some(target) => load_inventory_match(conn, resolve_merge_target(conn, target)?, "merged"),
+    }
+}
+
+fn resolve_merge_target(conn: &Connection, id: i64) -> Result<i64, CliError> {
+    let mut current = id;
+    // Merges re-point earlier merges, so chains are short; the bound only guards bad data.
+    for _ in 0..16 {
+        let next: Option<i64> = conn
+            .query_row(
+                "SELECT merged_into FROM software_inventory WHERE id = ?1",
+                params![current],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        match next {
+            Some(next) if next != current => current = next,
+            _ => break,
+        }
+    }
+    Ok(current)
+}
+
+fn find_inventory_by_identity(
+    conn: &Connection,
+    identity_key: &str,
+    discovery_source: &str,
+    seen_ids: &HashSet<i64>,
+    scan_fingerprints: &HashSet<String>,
+) -> Result<Option<InventoryMatch>, CliError> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, fingerprint FROM software_inventory
+        WHERE identity_key = ?1 AND discovery_source = ?2 AND merged_into IS NULL
+        ORDER BY is_active DESC, last_seen_at DESC, id DESC
+        "#,
+    )?;
+    let rows = stmt
+        .query_map(params![identity_key, discovery_source], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    // A row whose own fingerprint is still in this scan is installed side by side (Python 3.11
+    // next to 3.12) and keeps its identity; only rows that disappeared can be re-keyed.
+    let id = rows
+        .into_iter()
+        .find(|(id, fingerprint)| {
+            !seen_ids.contains(id) && !scan_fingerprints.contains(fingerprint)
+        })
+        .map(|(id, _)| id);
+    match id {
+        Some(id) => load_inventory_match(conn, id, "identity"),
+        None => Ok(None),
+    }
+}
+
+fn registry_status_rank(status: &str) -> u8 {
+    match status {
+        "active" => 0,
+        "disabled" => 1,
+        _ => 2,
+    }
+}
+
+fn criticality_rank(level: Option<&str>) -> u8 {
+    match level {
+        Some("critical") => 4,
+        Some("high") => 3,
+        Some("medium") => 2,
+        Some("low") => 1,
+        _ => 0,
+    }
+}
+
+/// Fills the survivor's empty owner, team, notes and license from the merged-away software and
+/// keeps the stricter criticality of the two. Returns the fields that were taken over.
+fn merge_software_annotations(
+    tx: &rusqlite::Transaction,
+    into: i64,
+    from: i64,
+) -> Result<Vec<&'static str>, CliError> {
+    let load = |id: i64| {
+        tx.query_row(
+            "SELECT owner, team, notes, license, criticality FROM software_inventory WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok([
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ])
+            },
+        )
+    };
+    let (survivor, merged) = (load(into)?, load(from)?);
+    let mut taken = Vec::new();
+    for (idx, column) in ["owner", "team", "notes", "license"]
+        .into_iter()
+        .enumerate()
+    {
+        if survivor[idx].is_none() && merged[idx].is_some() {
+            tx.execute(
+                &format!("UPDATE software_inventory SET {column} = ?1 WHERE id = ?2"),
+                params![merged[idx], into],
+            )?;
+            taken.push(column);
+        }
+    }
+    if criticality_rank(merged[4].as_deref()) > criticality_rank(survivor[4].as_deref()) {
+        tx.execute(
+            "UPDATE software_inventory SET criticality = ?1 WHERE id = ?2",
+            params![merged[4], into],
+        )?;
+        taken.push("criticality");
+    }
+    Ok(taken)
+}
+
+/// Moves the merged-away software's registry rows to the survivor. A row whose URL the survivor
+/// already has is folded into the survivor's row, keeping the stricter status and earlier expiry.
+fn move_registry_rows(
+    tx: &rusqlite::Transaction,
+    into: i64,
+    from: i64,
+    collisions: &[(i64, i64)],
+) -> Result<(usize, usize), CliError> {
+    let mut stmt = tx.prepare(
+        "SELECT id, candidate_id, url, status FROM source_registry WHERE software_id = ?1",
+    )?;
+    let rows = stmt
+        .query_map(params![from], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    let (mut moved, mut merged) = (0, 0);
+    for (registry_id, candidate_id, url, status) in rows {
+        let target_candidate = collisions
+            .iter()
+            .find(|(from_candidate, _)| *from_candidate == candidate_id)
+            .map_or(candidate_id, |(_, into_candidate)| *into_candidate);
+        let survivor: Option<(i64, String)> = tx
+            .query_row(
+                "SELECT id, status FROM source_registry WHERE software_id = ?1 AND (url = ?2 OR candidate_id = ?3)",
+                params![into, url, target_candidate],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        match survivor {
+            None => {
+                tx.execute(
+                    "UPDATE source_registry SET software_id = ?1, candidate_id = ?2 WHERE id = ?3",
+                    params![into, target_candidate, registry_id],
+                )?;
+                moved += 1;
+            }
+            Some((survivor_id, survivor_status)) => {
+                let status =
+                    if registry_status_rank(&status) > registry_status_rank(&survivor_status) {
+                        status
+                    } else {
+                        survivor_status
+                    };
+                tx.execute(
+                    r#"
+                    UPDATE source_registry
+                    SET status = ?1,
+                        expires_at = CASE
+                            WHEN expires_at IS NULL THEN (SELECT expires_at FROM source_registry WHERE id = ?3)
+                            ELSE MIN(expires_at, COALESCE((SELECT expires_at FROM source_registry WHERE id = ?3), expires_at))
+                        END
+                    WHERE id = ?2
+                    "#,
+                    params![status, survivor_id, registry_id],
+                )?;
+                tx.execute(
+                    "UPDATE source_probe_history SET registry_id = ?1 WHERE registry_id = ?2",
+                    params![survivor_id, registry_id],
+                )?;
+                tx.execute(
+                    "DELETE FROM source_registry WHERE id = ?1",
+                    params![registry_id],
+                )?;
+                merged += 1;
+            }
+        }
+    }
+    Ok((moved, merged))
+}
+
+fn software_merge(args: SoftwareMergeArgs) -> Result<(), CliError> {
+    if args.into == args.from {
+        return Err(CliError::Usage(
+            "--into and --from must be different".to_string(),
+        ));
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+
+    let mut names = Vec::new();
+    for (flag, id) in [("--into", args.into), ("--from", args.from)] {
+        let row: Option<(String, Option<i64>)> = conn
+            .query_row(
+                "SELECT name, merged_into FROM software_inventory WHERE id = ?1",
+                params![id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()?;
+        match row {
+            None => {
+                return Err(CliError::Usage(format!(
+                    "software not found for {flag} {id}"
+                )));
+            }
+            Some((_, Some(target))) => {
+                return Err(CliError::Usage(format!(
+                    "software {id} was already merged into {target}"
+                )));
+            }
+            Some((name, None)) => names.push(name),
+        }
+    }
+
+    let tx = conn.transaction()?;
+    let mut moved = serde_json::Map::new();
+
+    // Candidates whose URL already exists on the target collapse onto the target's candidate.
+    let mut stmt = tx.prepare(
+        r#"
+        SELECT f.id, i.id, f.url, f.status, i.status
+        FROM source_candidate f
+        JOIN source_candidate i ON i.software_id = ?1 AND i.url = f.url
+        WHERE f.software_id = ?2
+        "#,
+    )?;
+    let collided = stmt
+        .query_map(params![args.into, args.from], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+    // Opposite decisions on the same URL need a reviewer; a review always beats pending.
+    if let Some((_, _, url, _, _)) = collided.iter().find(|(_, _, _, from_status, into_status)| {
+        from_status != into_status && from_status != "pending" && into_status != "pending"
+    }) {
+        return Err(CliError::Usage(format!(
+            "source candidate {url} is approved on one software and rejected on the other; review it before merging"
+        )));
+    }
+    let collisions: Vec<(i64, i64)> = collided
+        .iter()
+        .map(|(from_candidate, into_candidate, ..)| (*from_candidate, *into_candidate))
+        .collect();
+    let (registry, registry_merged) = move_registry_rows(&tx, args.into, args.from, &collisions)?;
+    moved.insert("source_registry".to_string(), json!(registry));
+    moved.insert("source_registry_merged".to_string(), json!(registry_merged));
+
+    for (from_candidate, into_candidate) in &collisions {
+        for table in [
+            "source_review_history",
+            "source_auto_review_decision",
+            "source_registry_import",
+            "source_probe_history",
+            "update_operation_history",
+        ] {
+            tx.execute(
+                &format!("UPDATE {table} SET candidate_id = ?1 WHERE candidate_id = ?2"),
+                params![into_candidate, from_candidate],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM source_candidate WHERE id = ?1",
+            params![from_candidate],
+        )?;
+    }
+    let mut decisions_taken = 0;
+    for (from_candidate, into_candidate, url, from_status, into_status) in &collided {
+        if review_status_rank(from_status) <= review_status_rank(into_status) {
+            continue;
+        }
+        let Some(review) = load_candidate_review_state(&tx, *into_candidate)? else {
+            continue;
+        };
+        tx.execute(
+            "UPDATE source_candidate SET status = ?1 WHERE id = ?2",
+            params![from_status, into_candidate],
+        )?;
+        if from_status == "rejected" {
+            tx.execute(
+                "UPDATE source_registry SET status = 'disabled' WHERE candidate_id = ?1 AND status = 'active'",
+                params![into_candidate],
+            )?;
+        }
+        let note = format!(
+            "software {} merged: candidate {from_candidate} ({url}) was {from_status}",
+            args.from
+        );
+        record_source_review(&tx, &review, from_status, "software-merge", &note, None)?;
+        decisions_taken += 1;
+    }
+    moved.insert(
+        "source_candidate_collapsed".to_string(),
+        json!(collisions.len()),
+    );
+    moved.insert(
+        "source_candidate_decisions_taken".to_string(),
+        json!(decisions_taken),
+    );
+
+    let candidates = tx.execute(
+        "UPDATE source_candidate SET software_id = ?1 WHERE software_id = ?2",
+        params![args.into, args.from],
+    )?;
+    moved.insert("source_candidate".to_string(), json!(candidates));
+    tx.execute(
+        "DELETE FROM source_suggest_state WHERE software_id IN (?1, ?2)",
+        params![args.into, args.from],
+    )?;
+
+    for (table, column) in [
+        ("cleanup_operation_history", "software_id"),
+        ("software_version_history", "software_id"),
+        ("software_scan_snapshot", "software_id"),
+        ("ai_repair_plan_history", "target_software_id"),
+        ("source_review_history", "software_id"),
+        ("source_registry_import", "software_id"),
+        ("software_manual_audit", "software_id"),
+        ("baseline_drift_item", "software_id"),
+    ] {
+        let changed = tx.execute(
+            &format!("UPDATE {table} SET {column} = ?1 WHERE {column} = ?2"),
+            params![args.into, args.from],
+        )?;
+        moved.insert(table.to_string(), json!(changed));
+    }
+
+    // A software has at most one pin; when both have one, the survivor keeps its own.
+    let pin_conflicts = tx.execute(
+        r#"
+        DELETE FROM software_pin
+        WHERE software_id = ?2 AND EXISTS (SELECT 1 FROM software_pin WHERE software_id = ?1)
+        "#,
+        params![args.into, args.from],
+    )?;
+    let pins = tx.execute(
+        "UPDATE software_pin SET software_id = ?1 WHERE software_id = ?2",
+        params![args.into, args.from],
+    )?;
+    moved.insert("software_pin".to_string(), json!(pins));
+    moved.insert("software_pin_kept_into".to_string(), json!(pin_conflicts));
+
+    let tags = tx.execute(
+        r#"
+        INSERT OR IGNORE INTO software_tag (software_id, tag, created_at)
+        SELECT ?1, tag, created_at FROM software_tag WHERE software_id = ?2
+        "#,
+        params![args.into, args.from],
+    )?;
+    tx.execute(
+        "DELETE FROM software_tag WHERE software_id = ?1",
+        params![args.from],
+    )?;
+    moved.insert("software_tag".to_string(), json!(tags));
+    let annotations = merge_software_annotations(&tx, args.into, args.from)?;
+    moved.insert("annotations".to_string(), json!(annotations));
+
+    let redirected = tx.execute(
+        "UPDATE software_inventory SET merged_into = ?1 WHERE merged_into = ?2",
+        params![args.into, args.from],
+    )?;
+    moved.insert("earlier_merges_redirected".to_string(), json!(redirected));
+    tx.execute(
+        r#"
+        UPDATE software_inventory
+        SET is_active = MAX(is_active, (SELECT is_active FROM software_inventory WHERE id = ?2))
+        WHERE id = ?1
+        "#,
+        params![args.into, args.from],
+    )?;
+    tx.execute(
+        "UPDATE software_inventory SET is_active = 0, merged_into = ?1 WHERE id = ?2",
+        params![args.into, args.from],
+    )?;
+
+    let moved = serde_json::Value::Object(moved);
+    if !args.dry_run {
+        tx.execute(
+            r#"
+            INSERT INTO software_merge_history (ts, into_software_id, from_software_id, moved_json)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+            params![unix_ts(), args.into, args.from, moved.to_string()],
+        )?;
+        tx.commit()?;
+    }
+    // Dropping an uncommitted transaction rolls it back, so a dry run reports real counts only.
+
+    let payload = json!({
+        "into": {"software_id": args.into, "name": names[0]},
+        "from": {"software_id": args.from, "name": names[1]},
+        "dry_run": args.dry_run,
+        "moved": moved
+    });
+    let plain = if args.dry_run {
+        format!(
+            "Dry run: software {} ({}) would be merged into {} ({}).",
+            args.from, names[1], args.into, names[0]
+        )
+    } else {
+        format!(
+            "Software {} ({}) merged into {} ({}).",
+            args.from, names[1], args.into, names[0]
+        )
+    };
+    print_payload(args.json, payload, &plain)
+}
+
+fn software_duplicates(args: SoftwareDuplicatesArgs) -> Result<(), CliError> {
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, name, version, publisher, install_location, is_active, identity_key
+        FROM software_inventory
+        WHERE merged_into IS NULL AND (?1 = 1 OR is_active = 1)
+        ORDER BY id ASC
+        "#,
+    )?;
+    let rows = stmt
+        .query_map(params![args.include_inactive as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                json!({
+                    "software_id": row.get::<_, i64>(0)?,
+                    "name": row.get::<_, String>(1)?,
+                    "version": row.get::<_, String>(2)?,
+                    "publisher": row.get::<_, String>(3)?,
+                    "install_location": row.get::<_, String>(4)?,
+                    "is_active": row.get::<_, i64>(5)? == 1
+                }),
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut by_identity: std::collections::BTreeMap<String, Vec<usize>> = Default::default();
+    let mut by_name: std::collections::BTreeMap<String, Vec<usize>> = Default::default();
+    for (idx, (_, _, name, identity_key)) in rows.iter().enumerate() {
+        by_identity
+            .entry(identity_key.clone())
+            .or_default()
+            .push(idx);
+        by_name
+            .entry(strip_version_tokens(name))
+            .or_default()
+            .push(idx);
+    }
+
+    let mut grouped: HashSet<Vec<i64>> = HashSet::new();
+    let mut groups: Vec<serde_json::Value> = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+    let candidates = by_identity
+        .into_iter()
+        .map(|(key, members)| ("same_identity", key, members))
+        .chain(
+            by_name
+                .into_iter()
+                .map(|(key, members)| ("same_name", key, members)),
+        );
+    for (reason, key, members) in candidates {
+        if members.len() < 2 || key.is_empty() {
+            continue;
+        }
+        let ids: Vec<i64> = members.iter().map(|idx| rows[*idx].0).collect();
+        if !grouped.insert(ids.clone()) {
+            continue;
+        }
+        lines.push(format!(
+            "{reason} {key}: {}",
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        groups.push(json!({
+            "reason": reason,
+            "key": key,
+            "software": members.iter().map(|idx| rows[*idx].1.clone()).collect::<Vec<_>>()
+        }));
+    }
+
+    if groups.is_empty() {
+        return print_payload(args.json, json!([]), "No probable duplicates found.");
+    }
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&json!(groups))?);
+    } else {
+        for line in &lines {
+            println!("{line}");
+        }
+        println!("{} probable duplicate groups found.", groups.len());
+    }
+    Ok(())
+}
+
+fn software_add(args: SoftwareAddArgs) -> Result<(), CliError> {
+    let name = validate_manual_field("--name", Some(&args.name), true)?;
+    let version = validate_manual_field("--version", args.version.as_deref(), false)?;
+    let publisher = validate_manual_field("--publisher", args.publisher.as_deref(), false)?;
+    let install_location = validate_manual_field(
+        "--install-location",
+        args.install_location.as_deref(),
+        false,
+    )?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let tx = conn.transaction()?;
+
+    let fingerprint = make_fingerprint(&name, &publisher, &install_location);
+    // A removed manual entry keeps its fingerprint, so adding it again brings the same row back.
+    let removed: Option<i64> = tx
+        .query_row(
+            r#"
+            SELECT id FROM software_inventory
+            WHERE fingerprint = ?1 AND discovery_source = 'manual' AND is_active = 0 AND merged_into IS NULL
+            "#,
+            params![fingerprint],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if removed.is_none() {
+        ensure_fingerprint_free(&tx, &fingerprint, None)?;
+    }
+    let aliases = load_publisher_aliases(&tx)?;
+    let publisher_canonical = canonical_publisher(&publisher, &aliases);
+    let identity_key = make_identity_key(&name, &publisher_canonical, None, None);
+    let now = unix_ts();
+
+    let (software_id, before) = match removed {
+        Some(software_id) => {
+            let before = load_manual_snapshot(&tx, software_id)?;
+            tx.execute(
+                r#"
+                UPDATE software_inventory
+                SET name = ?1, version = ?2, publisher = ?3, install_location = ?4, source_confidence = 100, last_seen_at = ?5, is_active = 1, identity_key = ?6, publisher_canonical = ?7
+                WHERE id = ?8
+                "#,
+                params![name, version, publisher, install_location, now, identity_key, publisher_canonical, software_id],
+            )?;
+            (software_id, Some(before))
+        }
+        None => {
+            tx.execute(
+                r#"
+                INSERT INTO software_inventory
+                (name, version, publisher, install_location, discovery_source, source_confidence, first_seen_at, last_seen_at, is_active, fingerprint,
+                 identity_key, publisher_canonical)
+                VALUES (?1, ?2, ?3, ?4, 'manual', 100, ?5, ?5, 1, ?6, ?7, ?8)
+                "#,
+                params![name, version, publisher, install_location, now, fingerprint, identity_key, publisher_canonical],
+            )?;
+            (tx.last_insert_rowid(), None)
+        }
+    };
+    let after = load_manual_snapshot(&tx, software_id)?;
+    record_manual_audit(&tx, software_id, "add", before.as_ref(), Some(&after))?;
+    record_version_change(
+        &tx,
+        software_id,
+        &next_operation_id("manual", software_id),
+        now,
+        "installed",
+        None,
+        Some(&version),
+    )?;
+    tx.commit()?;
+
+    print_payload(
+        args.json,
+        after,
+        &format!("Manual software {software_id} ({name}) added."),
+    )
+}
+
+fn software_edit(args: SoftwareEditArgs) -> Result<(), CliError> {
+    if args.name.is_none()
+        && args.version.is_none()
+        && args.publisher.is_none()
+        && args.install_location.is_none()
+    {
+        return Err(CliError::Usage(
+            "at least one of --name, --version, --publisher, --install-location is required"
+                .to_string(),
+        ));
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let tx = conn.transaction()?;
+
+    let before = load_manual_snapshot(&tx, args.software_id)?;
+    let current = |field: &str| before[field].as_str().unwrap_or_default().to_string();
+    let name = match args.name.as_deref() {
+        Some(v) => validate_manual_field("--name", Some(v), true)?,
+        None => current("name"),
+    };
+    let version = match args.version.as_deref() {
+        Some(v) => validate_manual_field("--version", Some(v), false)?,
+        None => current("version"),
+    };
+    let publisher = match args.publisher.as_deref() {
+        Some(v) => validate_manual_field("--publisher", Some(v), false)?,
+        None => current("publisher"),
+    };
+    let install_location = match args.install_location.as_deref() {
+        Some(v) => validate_manual_field("--install-location", Some(v), false)?,
+        None => current("install_location"),
+    };
+
+    let fingerprint = make_fingerprint(&name, &publisher, &install_location);
+    ensure_fingerprint_free(&tx, &fingerprint, Some(args.software_id))?;
+    let aliases = load_publisher_aliases(&tx)?;
+    let publisher_canonical = canonical_publisher(&publisher, &aliases);
+    let identity_key = make_identity_key(&name, &publisher_canonical, None, None);
+    let now = unix_ts();
+
+    tx.execute(
+        r#"
+        UPDATE software_inventory
+        SET name = ?1, version = ?2, publisher = ?3, install_location = ?4, fingerprint = ?5,
+            identity_key = ?6, publisher_canonical = ?7, last_seen_at = ?8
+        WHERE id = ?9
+        "#,
+        params![
+            name,
+            version,
+            publisher,
+            install_location,
+            fingerprint,
+            identity_key,
+            publisher_canonical,
+            now,
+            args.software_id
+        ],
+    )?;
+    let after = load_manual_snapshot(&tx, args.software_id)?;
+    record_manual_audit(&tx, args.software_id, "edit", Some(&before), Some(&after))?;
+    let old_version = current("version");
+    if old_version != version {
+        record_version_change(
+            &tx,
+            args.software_id,
+            &next_operation_id("manual", args.software_id),
+            now,
+            "version_changed",
+            Some(&old_version),
+            Some(&version),
+        )?;
+    }
+    tx.commit()?;
+
+    print_payload(
+        args.json,
+        after,
+        &format!("Manual software {} updated.", args.software_id),
+    )
+}
+
+fn software_remove(args: SoftwareRemoveArgs) -> Result<(), CliError> {
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let tx = conn.transaction()?;
+
+    let before = load_manual_snapshot(&tx, args.software_id)?;
+    if before["is_active"] != json!(true) {
+        return Err(CliError::Usage(format!(
+            "manual software {} is already removed",
+            args.software_id
+        )));
+    }
+    tx.execute(
+        "UPDATE software_inventory SET is_active = 0 WHERE id = ?1",
+        params![args.software_id],
+    )?;
+    let after = load_manual_snapshot(&tx, args.software_id)?;
+    record_manual_audit(&tx, args.software_id, "remove", Some(&before), Some(&after))?;
+    record_version_change(
+        &tx,
+        args.software_id,
+        &next_operation_id("manual", args.software_id),
+        unix_ts(),
+        "removed",
+        before["version"].as_str(),
+        None,
+    )?;
+    tx.commit()?;
+
+    print_payload(
+        args.json,
+        after,
+        &format!("Manual software {} removed.", args.software_id),
+    )
+}
+
+fn software_audit(args: SoftwareAuditArgs) -> Result<(), CliError> {
+    let limit = i64::from(args.limit.unwrap_or(100));
+    if limit <= 0 {
+        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, ts, software_id, action, actor, before_json, after_json
+        FROM software_manual_audit
+        WHERE (?1 IS NULL OR software_id = ?1)
+        ORDER BY id DESC
+        LIMIT ?2
+        "#,
+    )?;
+    let rows = stmt.query_map(params![args.software_id, limit], |row| {
+        let parse = |raw: Option<String>| {
+            raw.and_then(|v| serde_json::from_str::<serde_json::Value>(&v).ok())
+                .unwrap_or(serde_json::Value::Null)
+        };
+        Ok(json!({
+            "id": row.get::<_, i64>(0)?,
+            "timestamp": row.get::<_, i64>(1)?,
+            "software_id": row.get::<_, i64>(2)?,
+            "action": row.get::<_, String>(3)?,
+            "actor": row.get::<_, String>(4)?,
+            "before": parse(row.get::<_, Option<String>>(5)?),
+            "after": parse(row.get::<_, Option<String>>(6)?)
+        }))
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(args.json, json!([]), "No manual audit entries found.");
+    }
+    print_payload(args.json, json!(payload), "Manual audit entries listed.")
+}
+
+fn validate_manual_field(
+    flag: &str,
+    value: Option<&str>,
+    required: bool,
+) -> Result<String, CliError> {
+    let value = value.unwrap_or_default().trim();
+    if required && value.is_empty() {
+        return Err(CliError::Usage(format!("{flag} must not be empty")));
+    }
+    if value.chars().count() > 256 {
+        return Err(CliError::Usage(format!(
+            "{flag} must be at most 256 characters"
+        )));
+    }
+    if value.chars().any(char::is_control) {
+        return Err(CliError::Usage(format!(
+            "{flag} must not contain control characters"
+        )));
+    }
+    Ok(value.to_string())
+}
+
+fn ensure_fingerprint_free(
+    conn: &Connection,
+    fingerprint: &str,
+    except_id: Option<i64>,
+) -> Result<(), CliError> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM software_inventory WHERE fingerprint = ?1",
+            params![fingerprint],
+            |r| r.get(0),
+        )
+        .optional()?;
+    match existing {
+        Some(id) if Some(id) != except_id => Err(CliError::Usage(format!(
+            "software with the same name, publisher and install location already exists: {id}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn load_manual_snapshot(
+    conn: &Connection,
+    software_id: i64,
+) -> Result<serde_json::Value, CliError> {
+    let row = conn
+        .query_row(
+            r#"
+            SELECT id, name, version, publisher, install_location, discovery_source, is_active
+            FROM software_inventory
+            WHERE id = ?1
+            "#,
+            params![software_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(5)?,
+                    json!({
+                        "software_id": row.get::<_, i64>(0)?,
+                        "name": row.get::<_, String>(1)?,
+                        "version": row.get::<_, String>(2)?,
+                        "publisher": row.get::<_, String>(3)?,
+                        "install_location": row.get::<_, String>(4)?,
+                        "discovery_source": row.get::<_, String>(5)?,
+                        "is_active": row.get::<_, i64>(6)? == 1
+                    }),
+                ))
+            },
+        )
+        .optional()?;
+    match row {
+        None => Err(CliError::Usage(format!(
+            "software not found for --software-id {software_id}"
+        ))),
+        Some((source, _)) if source != "manual" => Err(CliError::Usage(format!(
+            "software {software_id} was discovered by {source}; only manual entries can be changed"
+        ))),
+        Some((_, snapshot)) => Ok(snapshot),
+    }
+}
+
+fn record_manual_audit(
+    conn: &Connection,
+    software_id: i64,
+    action: &str,
+    before: Option<&serde_json::Value>,
+    after: Option<&serde_json::Value>,
+) -> Result<(), CliError> {
+    conn.execute(
+        r#"
+        INSERT INTO software_manual_audit (ts, software_id, action, actor, before_json, after_json)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        params![
+            unix_ts(),
+            software_id,
+            action,
+            current_os_user(),
+            before.map(|v| v.to_string()),
+            after.map(|v| v.to_string())
+        ],
+    )?;
+    Ok(())
+}
+
+fn current_os_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn software_tag(args: SoftwareTagArgs) -> Result<(), CliError> {
+    if args.add.is_empty() && args.remove.is_empty() {
+        return Err(CliError::Usage(
+            "at least one of --add, --remove is required".to_string(),
+        ));
+    }
+    let add = args
+        .add
+        .iter()
+        .map(|t| normalize_tag(t))
+        .collect::<Result<Vec<_>, _>>()?;
+    let remove = args
+        .remove
+        .iter()
+        .map(|t| normalize_tag(t))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+    ensure_software_exists(&conn, args.software_id)?;
+
+    let now = unix_ts();
+    for tag in &add {
+        conn.execute(
+            "INSERT OR IGNORE INTO software_tag (software_id, tag, created_at) VALUES (?1, ?2, ?3)",
+            params![args.software_id, tag, now],
+        )?;
+    }
+    for tag in &remove {
+        conn.execute(
+            "DELETE FROM software_tag WHERE software_id = ?1 AND tag = ?2",
+            params![args.software_id, tag],
+        )?;
+    }
+
+    let mut stmt =
+        conn.prepare("SELECT tag FROM software_tag WHERE software_id = ?1 ORDER BY tag ASC")?;
+    let tags = stmt
+        .query_map(params![args.software_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let plain = format!("Software {} tags: {}.", args.software_id, tags.join(", "));
+    print_payload(
+        args.json,
+        json!({"software_id": args.software_id, "tags": tags}),
+        &plain,
+    )
+}
+
+fn software_annotate(args: SoftwareAnnotateArgs) -> Result<(), CliError> {
+    if args.owner.is_none()
+        && args.team.is_none()
+        && args.criticality.is_none()
+        && args.notes.is_none()
+        && args.license.is_none()
+    {
+        return Err(CliError::Usage(
+            "at least one of --owner, --team, --criticality, --notes, --license is required"
+                .to_string(),
+        ));
+    }
+    if let Some(criticality) = args.criticality.as_deref().filter(|v| !v.trim().is_empty()) {
+        validate_criticality(criticality)?;
+    }
+    if args
+        .notes
+        .as_deref()
+        .is_some_and(|v| v.chars().count() > 4000)
+    {
+        return Err(CliError::Usage(
+            "--notes must be at most 4000 characters".to_string(),
+        ));
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+    ensure_software_exists(&conn, args.software_id)?;
+
+    // An explicitly empty value clears the field.
+    for (column, value) in [
+        ("owner", &args.owner),
+        ("team", &args.team),
+        ("criticality", &args.criticality),
+        ("notes", &args.notes),
+        ("license", &args.license),
+    ] {
+        if let Some(value) = value {
+            let value = value.trim();
+            conn.execute(
+                &format!("UPDATE software_inventory SET {column} = ?1 WHERE id = ?2"),
+                params![(!value.is_empty()).then_some(value), args.software_id],
+            )?;
+        }
+    }
+
+    let payload = conn.query_row(
+        "SELECT id, name, owner, team, criticality, notes, license FROM software_inventory WHERE id = ?1",
+        params![args.software_id],
+        |row| {
+            Ok(json!({
+                "software_id": row.get::<_, i64>(0)?,
+                "name": row.get::<_, String>(1)?,
+                "owner": row.get::<_, Option<String>>(2)?,
+                "team": row.get::<_, Option<String>>(3)?,
+                "criticality": row.get::<_, Option<String>>(4)?,
+                "notes": row.get::<_, Option<String>>(5)?,
+                "license": row.get::<_, Option<String>>(6)?
+            }))
+        },
+    )?;
+    print_payload(
+        args.json,
+        payload,
+        &format!("Software {} annotated.", args.software_id),
+    )
+}
+
+fn ensure_software_exists(conn: &Connection, software_id: i64) -> Result<(), CliError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM software_inventory WHERE id = ?1)",
+        params![software_id],
+        |r| r.get::<_, i64>(0).map(|v| v == 1),
+    )?;
+    if !exists {
+        return Err(CliError::Usage(format!(
+            "software not found for --software-id {software_id}"
+        )));
+    }
+    Ok(())
+}
+
+fn normalize_tag(raw: &str) -> Result<String, CliError> {
+    let tag = raw.trim().to_lowercase();
+    if tag.is_empty() || tag.len() > 64 {
+        return Err(CliError::Usage("tags must be 1-64 characters".to_string()));
+    }
+    if !tag
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+    {
+        return Err(CliError::Usage(format!(
+            "invalid tag '{raw}': use letters, digits, '-', '_', '.' or ':'"
+        )));
+    }
+    Ok(tag)
+}
+
+fn split_tags(raw: Option<String>) -> Vec<String> {
+    raw.map(|v| v.split(',').map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn validate_criticality(value: &str) -> Result<(), CliError> {
+    match value {
+        "low" | "medium" | "high" | "critical" => Ok(()),
+        _ => Err(CliError::Usage(
+            "--criticality must be one of: low, medium, high, critical".to_string(),
+        )),
+    }
+}
+
+fn is_auto_approvable(risk_level: &str, criticality: Option<&str>) -> bool {
+    risk_level == "low" && criticality != Some("critical")
+}
+
+fn software_criticality(conn: &Connection, software_id: i64) -> Result<Option<String>, CliError> {
+    Ok(conn
+        .query_row(
+            "SELECT criticality FROM software_inventory WHERE id = ?1",
+            params![software_id],
+            |r| r.get::<_, Option<String>>(0),
+        )
+        .optional()?
+        .flatten())
+}
+
+/// Adds what is known about a registry source: its trust, probe health, update history and target.
+fn add_registry_risk_facts(
+    conn: &Connection,
+    config: &AppConfig,
+    facts: &mut RiskFacts,
+    candidate_id: i64,
+) -> Result<(), CliError> {
+    let row = conn
+        .query_row(
+            r#"
+            SELECT r.software_id, r.confidence, r.url, r.domain, r.health, i.criticality
+            FROM source_registry r
+            LEFT JOIN software_inventory i ON i.id = r.software_id
+            WHERE r.candidate_id = ?1
+            "#,
+            params![candidate_id],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                ))
+            },
+        )
+        .optional()?;
+    let Some((software_id, confidence, url, domain, health, criticality)) = row else {
+        return Ok(());
+    };
+    facts.criticality = criticality;
+    facts.source_confidence = Some(confidence);
+    facts.source_untrusted = source_untrusted(
+        &url,
+        &domain,
+        &config.download_source_policy.untrusted_domains,
+    );
+    facts.source_health = Some(health);
+    (facts.prior_failures, facts.prior_successes) = conn.query_row(
+        r#"
+        SELECT COALESCE(SUM(status = 'failed'), 0), COALESCE(SUM(status = 'succeeded'), 0)
+        FROM update_operation_history
+        WHERE candidate_id = ?1
+        "#,
+        params![candidate_id],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+    )?;
+    if facts.action.starts_with("update.") {
+        facts.vulnerability_severity = match_vulnerabilities(conn, Some(software_id))?
+            .into_iter()
+            .map(|m| m.severity)
+            .max_by_key(|s| severity_rank(s));
+    }
+    Ok(())
+}
+
+fn repair_issue_signals(issue: &str) -> Vec<&'static str> {
+    let issue = issue.to_lowercase();
+    if issue.contains("crash") || issue.contains("data") || issue.contains("corrupt") {
+        vec!["data_loss_reported"]
+    } else {
+        Vec::new()
+    }
+}
+
+fn is_media_goal(goal: &str) -> bool {
+    let goal = goal.to_lowercase();
+    goal.contains("video") || goal.contains("剪辑")
+}
+
+/// Gathers risk facts for an action id of the form `<family>:<target>` (as used by `ui search`).
+fn load_action_risk_facts(
+    conn: &Connection,
+    config: &AppConfig,
+    action_id: &str,
+) -> Result<RiskFacts, CliError> {
+    let (family, target) = action_id
+        .split_once(':')
+        .map(|(family, target)| (family.trim(), target.trim()))
+        .unwrap_or((action_id.trim(), ""));
+    let mut facts = RiskFacts::new(family);
+    let numeric = target.parse::<i64>().ok();
+
+    if family.starts_with("software.") || family.starts_with("cleanup.") {
+        if let Some(software_id) = numeric {
+            facts.criticality = software_criticality(conn, software_id)?;
+        }
+    } else if family == "update.history" {
+        let operation = conn
+            .query_row(
+                "SELECT candidate_id, status FROM update_operation_history WHERE operation_id = ?1",
+                params![target],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()?;
+        if let Some((candidate_id, status)) = operation {
+            add_registry_risk_facts(conn, config, &mut facts, candidate_id)?;
+            facts.verification = match status.as_str() {
+                "failed" => Some("failed".to_string()),
+                "succeeded" => Some("verified".to_string()),
+                _ => None,
+            };
+        }
+    } else if family == "source.registry" || family.starts_with("update.") {
+        if let Some(candidate_id) = numeric {
+            add_registry_risk_facts(conn, config, &mut facts, candidate_id)?;
+        }
+    } else if family.starts_with("download.") {
+        let job = conn
+            .query_row(
+                "SELECT package_id, status FROM download_job_history WHERE job_id = ?1",
+                params![target],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()?;
+        if let Some((package_id, status)) = job {
+            facts.verification = match status.as_str() {
+                "failed" => Some("failed".to_string()),
+                "verified" => Some("verified".to_string()),
+                _ => None,
+            };
+            (facts.prior_failures, facts.prior_successes) = conn.query_row(
+                r#"
+                SELECT COALESCE(SUM(status = 'failed'), 0), COALESCE(SUM(status = 'verified'), 0)
+                FROM download_job_history
+                WHERE package_id = ?1 AND job_id != ?2
+                "#,
+                params![package_id, target],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )?;
+        }
+    } else if family == "baseline.drift" {
+        let forbidden: i64 = conn.query_row(
+            "SELECT COUNT(1) FROM baseline_drift_item WHERE check_id = ?1 AND kind = 'forbidden_present'",
+            params![target],
+            |row| row.get(0),
+        )?;
+        if forbidden > 0 {
+            facts.signals.push("forbidden_present");
+        }
+    } else if family == "ai.repair-plan" {
+        let issue = conn
+            .query_row(
+                "SELECT issue_text FROM ai_repair_plan_history WHERE plan_id = ?1",
+                params![target],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        facts.signals = repair_issue_signals(issue.as_deref().unwrap_or_default());
+    } else if family == "ai.recommend" {
+        let goal = conn
+            .query_row(
+                "SELECT goal FROM ai_recommend_history WHERE recommendation_id = ?1",
+                params![target],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        if goal.as_deref().is_some_and(is_media_goal) {
+            facts.signals.push("system_components");
+        }
+    }
+    Ok(facts)
+}
+
+fn action_risk(
+    conn: &Connection,
+    config: &AppConfig,
+    action_id: &str,
+) -> Result<RiskAssessment, CliError> {
+    Ok(evaluate_risk(&load_action_risk_facts(
+        conn, config, action_id,
+    )?))
+}
+
+/// Keeps an audit trail of the risk decisions commands enforced.
+fn record_risk_evaluation(
+    conn: &Connection,
+    action_id: &str,
+    command: &str,
+    facts: &RiskFacts,
+    assessment: &RiskAssessment,
+) -> Result<(), CliError> {
+    conn.execute(
+        r#"
+        INSERT INTO risk_evaluation_history (action_id, command, level, score, reasons_json, facts_json, ts)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        "#,
+        params![
+            action_id,
+            command,
+            assessment.level,
+            assessment.score,
+            assessment.reasons_json().to_string(),
+            facts.to_json().to_string(),
+            unix_ts()
+        ],
+    )?;
+    Ok(())
+}
+
+fn risk_explain(args: RiskExplainArgs) -> Result<(), CliError> {
+    let action_id = args.action_id.trim();
+    if action_id.is_empty() {
+        return Err(CliError::Usage("--action-id is required".to_string()));
+    }
+    let config = load_config()?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+    let facts = load_action_risk_facts(&conn, &config, action_id)?;
+    let assessment = evaluate_risk(&facts);
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT command, level, score, ts
+        FROM risk_evaluation_history
+        WHERE action_id = ?1
+        ORDER BY id DESC
+        LIMIT 10
+        "#,
+    )?;
+    let recent = stmt
+        .query_map(params![action_id], |row| {
+            Ok(json!({
+                "command": row.get::<_, String>(0)?,
+                "level": row.get::<_, String>(1)?,
+                "score": row.get::<_, i64>(2)?,
+                "ts": row.get::<_, i64>(3)?
+            }))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if args.json {
+        let payload = json!({
+            "action_id": action_id,
+            "level": assessment.level,
+            "score": assessment.score,
+            "reasons": assessment.reasons_json(),
+            "facts": facts.to_json(),
+            "recent_evaluations": recent
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        println!(
+            "{action_id}: {} risk (score {})",
+            assessment.level, assessment.score
+        );
+        for reason in &assessment.reasons {
+            println!(
+                "  {:<24} {:+4}  {}",
+                reason.rule, reason.points, reason.detail
+            );
+        }
+        if !recent.is_empty() {
+            println!("{} recorded evaluations for this action.", recent.len());
+        }
+    }
+    Ok(())
+}
+
+struct ExportComponent {
+    software_id: i64,
+    name: String,
+    version: String,
+    publisher: String,
+    install_location: String,
+    discovery_source: String,
+    license: Option<String>,
+    is_active: bool,
+    tags: Vec<String>,
+}
+
+fn software_export(args: SoftwareExportArgs) -> Result<(), CliError> {
+    let format = args.format.trim();
+    match format {
+        "cyclonedx-json" | "spdx-json" | "csv" => {}
+        _ => {
+            return Err(CliError::Usage(
+                "--format must be one of: cyclonedx-json, spdx-json, csv".to_string(),
+            ));
+        }
+    }
+    let tag = args.tag.as_deref().map(normalize_tag).transpose()?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, name, version, COALESCE(publisher_canonical, publisher), install_location, discovery_source, license, is_active,
+               (SELECT group_concat(tag, ',') FROM (SELECT tag FROM software_tag t WHERE t.software_id = software_inventory.id ORDER BY tag))
+        FROM software_inventory
+        WHERE merged_into IS NULL
+          AND (?1 = 0 OR is_active = 1)
+          AND (?2 IS NULL OR id IN (SELECT software_id FROM software_tag WHERE tag = ?2))
+        ORDER BY name ASC, id ASC
+        "#,
+    )?;
+    let components = stmt
+        .query_map(params![args.active_only as i64, tag], |row| {
+            Ok(ExportComponent {
+                software_id: row.get(0)?,
+                name: row.get(1)?,
+                version: row.get(2)?,
+                publisher: row.get(3)?,
+                install_location: row.get(4)?,
+                discovery_source: row.get(5)?,
+                license: row.get(6)?,
+                is_active: row.get::<_, i64>(7)? == 1,
+                tags: split_tags(row.get(8)?),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let now = unix_ts();
+    let rendered = match format {
+        "cyclonedx-json" => serde_json::to_string_pretty(&render_cyclonedx(&components, now))?,
+        "spdx-json" => serde_json::to_string_pretty(&render_spdx(&components, now))?,
+        _ => render_inventory_csv(&components),
+    };
+
+    match args.output {
+        Some(path) => {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, rendered)?;
+            println!(
+                "Exported {} components as {format} to {}.",
+                components.len(),
+                path.display()
+            );
+        }
+        None => println!("{}", rendered.trim_end()),
+    }
+    Ok(())
+}
+
+fn render_cyclonedx(components: &[ExportComponent], now: i64) -> serde_json::Value {
+    let items: Vec<serde_json::Value> = components
+        .iter()
+        .map(|c| {
+            let mut component = json!({
+                "type": "application",
+                "bom-ref": format!("synora-software-{}", c.software_id),
+                "name": c.name,
+                "version": c.version,
+                "properties": [
+                    {"name": "synora:install_location", "value": c.install_location},
+                    {"name": "synora:discovery_source", "value": c.discovery_source},
+                    {"name": "synora:is_active", "value": c.is_active.to_string()},
+                    {"name": "synora:tags", "value": c.tags.join(",")}
+                ]
+            });
+            if !c.publisher.is_empty() {
+                component["supplier"] = json!({"name": c.publisher});
+                component["publisher"] = json!(c.publisher);
+            }
+            if let Some(purl) = derive_purl(&c.discovery_source, &c.name, &c.version) {
+                component["purl"] = json!(purl);
+            }
+            if let Some(license) = c.license.as_deref() {
+                component["licenses"] = json!([{"license": {"name": license}}]);
+            }
+            component
+        })
+        .collect();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", random_uuid_v4()),
+        "version": 1,
+        "metadata": {
+            "timestamp": format_rfc3339(now),
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "synora",
+                    "version": env!("CARGO_PKG_VERSION")
+                }]
+            }
+        },
+        "components": items
+    })
+}
+
+fn render_spdx(components: &[ExportComponent], now: i64) -> serde_json::Value {
+    let mut packages = Vec::new();
+    let mut relationships = Vec::new();
+    let mut extracted: std::collections::BTreeMap<String, String> = Default::default();
+    for c in components {
+        let spdx_id = format!("SPDXRef-Package-{}", c.software_id);
+        let supplier = if c.publisher.is_empty() {
+            "NOASSERTION".to_string()
+        } else {
+            format!("Organization: {}", c.publisher)
+        };
+        let declared = match c
+            .license
+            .as_deref()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+        {
+            Some(license) if is_spdx_license_expression(license) => license.to_string(),
+            Some(license) => match license_ref_id(license) {
+                Some(license_id) => {
+                    extracted.insert(license_id.clone(), license.to_string());
+                    license_id
+                }
+                None => "NOASSERTION".to_string(),
+            },
+            None => "NOASSERTION".to_string(),
+        };
+        let mut package = json!({
+            "SPDXID": spdx_id,
+            "name": c.name,
+            "versionInfo": c.version,
+            "supplier": supplier,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": declared,
+            "copyrightText": "NOASSERTION",
+            "comment": format!(
+                "install_location={}; discovery_source={}; is_active={}",
+                c.install_location, c.discovery_source, c.is_active
+            )
+        });
+        if let Some(purl) = derive_purl(&c.discovery_source, &c.name, &c.version) {
+            package["externalRefs"] = json!([{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": purl
+            }]);
+        }
+        packages.push(package);
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": spdx_id
+        }));
+    }
+
+    let mut document = json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": "synora-installed-software",
+        "documentNamespace": format!("https://synora.local/spdx/installed-software-{}", random_uuid_v4()),
+        "creationInfo": {
+            "created": format_rfc3339(now),
+            "creators": [format!("Tool: synora-{}", env!("CARGO_PKG_VERSION"))]
+        },
+        "packages": packages,
+        "relationships": relationships
+    });
+    if !extracted.is_empty() {
+        document["hasExtractedLicensingInfos"] = json!(extracted
+            .into_iter()
+            .map(|(license_id, text)| json!({"licenseId": license_id, "name": text, "extractedText": text}))
+            .collect::<Vec<_>>());
+    }
+    document
+}
+
+fn render_inventory_csv(components: &[ExportComponent]) -> String {
+    let mut out = String::from(
+        "software_id,name,version,publisher,install_location,discovery_source,purl,license,is_active,tags\n",
+    );
+    for c in components {
+        let purl = derive_purl(&c.discovery_source, &c.name, &c.version).unwrap_or_default();
+        let fields = [
+            c.software_id.to_string(),
+            c.name.clone(),
+            c.version.clone(),
+            c.publisher.clone(),
+            c.install_location.clone(),
+            c.discovery_source.clone(),
+            purl,
+            c.license.clone().unwrap_or_default(),
+            c.is_active.to_string(),
+            c.tags.join(";"),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Package URL for package-manager sources; registry and manual entries have none.
+///
+/// Only deb is a registered purl type, and it carries the `debian` vendor namespace the spec
+/// requires. choco, flatpak and winget have no registered type, so their ids are emitted as
+/// `pkg:generic` with a `package_manager` qualifier naming the source.
+fn derive_purl(discovery_source: &str, name: &str, version: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let (path, package_manager) = match discovery_source {
+        "deb" => (
+            format!("deb/debian/{}", purl_encode(&name.to_lowercase())),
+            None,
+        ),
+        "flatpak" | "choco" | "winget" => (
+            format!("generic/{}", purl_encode(name)),
+            Some(discovery_source),
+        ),
+        _ => return None,
+    };
+    let mut purl = format!("pkg:{path}");
+    if !version.trim().is_empty() {
+        purl.push('@');
+        purl.push_str(&purl_encode(version.trim()));
+    }
+    if let Some(package_manager) = package_manager {
+        purl.push_str("?package_manager=");
+        purl.push_str(package_manager);
+    }
+    Some(purl)
+}
+
+fn purl_encode(value: &str) -> String {
+    let mut out = String::new();
+    for b in value.bytes() {
+        let c = b as char;
+        if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '~') {
+            out.push(c);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+/// True when every identifier in the expression is on the SPDX license (and exception) list.
+fn is_spdx_license_expression(value: &str) -> bool {
+    spdx::Expression::parse(value.trim()).is_ok()
+}
+
+/// `LicenseRef-` id for a free-text license, whose text goes into `hasExtractedLicensingInfos`.
+fn license_ref_id(license: &str) -> Option<String> {
+    let mut id = String::new();
+    for c in license.chars() {
+        if c.is_ascii_alphanumeric() || c == '.' {
+            id.push(c);
+        } else if !id.is_empty() && !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+    let id = id.trim_end_matches('-');
+    (!id.is_empty()).then(|| format!("LicenseRef-{id}"))
+}
+
+fn format_rfc3339(ts: i64) -> String {
+    let days = ts.div_euclid(86_400);
+    let secs = ts.rem_euclid(86_400);
+    // Civil-from-days conversion for the proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3_600,
+        (secs % 3_600) / 60,
+        secs % 60
+    )
+}
+
+fn random_uuid_v4() -> String {
+    use std::hash::{BuildHasher, Hasher};
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut bytes = [0_u8; 16];
+    for (i, chunk) in bytes.chunks_mut(8).enumerate() {
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        hasher.write_usize(i);
+        hasher.write_u32(std::process::id());
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+    }
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct VulnAdvisory {
+    advisory_id: String,
+    source: &'static str,
+    aliases: Vec<String>,
+    summary: String,
+    severity: String,
+    cvss_score: Option<f64>,
+    published: Option<String>,
+    modified: Option<String>,
+    affected: Vec<VulnRange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct VulnRange {
+    package_name: String,
+    vendor: String,
+    version_start: Option<String>,
+    start_inclusive: bool,
+    version_end: Option<String>,
+    end_inclusive: bool,
+    fixed_version: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct VulnMatch {
+    software_id: i64,
+    software_name: String,
+    software_version: String,
+    advisory_id: String,
+    aliases: Vec<String>,
+    summary: String,
+    severity: String,
+    cvss_score: Option<f64>,
+    fixed_versions: Vec<String>,
+}
+
+fn software_vulns_sync(args: VulnsSyncArgs) -> Result<(), CliError> {
+    let raw = fs::read_to_string(&args.file).map_err(|e| {
+        CliError::Usage(format!(
+            "failed to read vulnerability feed {}: {e}",
+            args.file.display()
+        ))
+    })?;
+    let feed: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| CliError::Usage(format!("vulnerability feed is not valid JSON: {e}")))?;
+    let (format, advisories) = parse_vuln_feed(&feed)?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let now = unix_ts();
+
+    let tx = conn.transaction()?;
+    let mut ranges = 0_i64;
+    for advisory in &advisories {
+        tx.execute(
+            r#"
+            INSERT INTO vuln_advisory
+            (advisory_id, source, aliases_json, summary, severity, cvss_score, published, modified, imported_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            ON CONFLICT(advisory_id) DO UPDATE SET
+                source = excluded.source,
+                aliases_json = excluded.aliases_json,
+                summary = excluded.summary,
+                severity = excluded.severity,
+                cvss_score = excluded.cvss_score,
+                published = excluded.published,
+                modified = excluded.modified,
+                imported_at = excluded.imported_at
+            "#,
+            params![
+                advisory.advisory_id,
+                advisory.source,
+                json!(advisory.aliases).to_string(),
+                advisory.summary,
+                advisory.severity,
+                advisory.cvss_score,
+                advisory.published,
+                advisory.modified,
+                now
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM vuln_affected WHERE advisory_id = ?1",
+            params![advisory.advisory_id],
+        )?;
+        for range in &advisory.affected {
+            tx.execute(
+                r#"
+                INSERT INTO vuln_affected
+                (advisory_id, package_name, vendor, version_start, start_inclusive, version_end, end_inclusive, fixed_version)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#,
+                params![
+                    advisory.advisory_id,
+                    range.package_name,
+                    range.vendor,
+                    range.version_start,
+                    range.start_inclusive,
+                    range.version_end,
+                    range.end_inclusive,
+                    range.fixed_version
+                ],
+            )?;
+            ranges += 1;
+        }
+    }
+    tx.execute(
+        r#"
+        INSERT INTO vuln_sync_history (ts, file, format, advisories, affected_ranges)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        "#,
+        params![
+            now,
+            args.file.display().to_string(),
+            format,
+            advisories.len() as i64,
+            ranges
+        ],
+    )?;
+    tx.commit()?;
+
+    let payload = json!({
+        "file": args.file.display().to_string(),
+        "format": format,
+        "advisories": advisories.len(),
+        "affected_ranges": ranges
+    });
+    print_payload(
+        args.json,
+        payload,
+        &format!(
+            "Imported {} advisories ({ranges} affected ranges) from {format} feed.",
+            advisories.len()
+        ),
+    )
+}
+
+fn software_vulns(
+    software_id: Option<i64>,
+    min_severity: Option<&str>,
+    as_json: bool,
+) -> Result<(), CliError> {
+    if let Some(level) = min_severity {
+        match level {
+            "low" | "medium" | "high" | "critical" => {}
+            _ => {
+                return Err(CliError::Usage(
+                    "--min-severity must be one of: low, medium, high, critical".to_string(),
+                ));
+            }
+        }
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let threshold = min_severity.map(severity_rank).unwrap_or(0);
+    let matches: Vec<VulnMatch> = match_vulnerabilities(&conn, software_id)?
+        .into_iter()
+        .filter(|m| severity_rank(&m.severity) >= threshold)
+        .collect();
+
+    let payload: Vec<serde_json::Value> = matches
+        .iter()
+        .map(|m| {
+            json!({
+                "software_id": m.software_id,
+                "software_name": m.software_name,
+                "software_version": m.software_version,
+                "advisory_id": m.advisory_id,
+                "aliases": m.aliases,
+                "severity": m.severity,
+                "cvss_score": m.cvss_score,
+                "fixed_versions": m.fixed_versions,
+                "summary": m.summary
+            })
+        })
+        .collect();
+    if payload.is_empty() {
+        return print_payload(as_json, json!([]), "No known vulnerabilities matched.");
+    }
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&json!(payload))?);
+    } else {
+        for m in &matches {
+            let fixed = if m.fixed_versions.is_empty() {
+                "no fix listed".to_string()
+            } else {
+                format!("fixed in {}", m.fixed_versions.join(", "))
+            };
+            println!(
+                "{} {} {} [{}] {}",
+                m.software_name, m.software_version, m.advisory_id, m.severity, fixed
+            );
+        }
+        println!("{} vulnerability matches found.", matches.len());
+    }
+    Ok(())
+}
+
+/// Matches active inventory against imported advisories by normalized name, vendor and version.
+fn match_vulnerabilities(
+    conn: &Connection,
+    software_id: Option<i64>,
+) -> Result<Vec<VulnMatch>, CliError> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT a.advisory_id, a.aliases_json, a.summary, a.severity, a.cvss_score,
+               f.package_name, f.vendor, f.version_start, f.start_inclusive, f.version_end, f.end_inclusive, f.fixed_version
+        FROM vuln_affected f
+        JOIN vuln_advisory a ON a.advisory_id = f.advisory_id
+        ORDER BY a.advisory_id ASC, f.id ASC
+        "#,
+    )?;
+    let ranges = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<f64>>(4)?,
+                VulnRange {
+                    package_name: row.get(5)?,
+                    vendor: row.get(6)?,
+                    version_start: row.get(7)?,
+                    start_inclusive: row.get::<_, i64>(8)? == 1,
+                    version_end: row.get(9)?,
+                    end_inclusive: row.get::<_, i64>(10)? == 1,
+                    fixed_version: row.get(11)?,
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if ranges.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, name, version, COALESCE(publisher_canonical, publisher)
+        FROM software_inventory
+        WHERE is_active = 1 AND (?1 IS NULL OR id = ?1)
+        ORDER BY id ASC
+        "#,
+    )?;
+    let software = stmt
+        .query_map(params![software_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut out: Vec<VulnMatch> = Vec::new();
+    for (id, name, version, publisher) in &software {
+        let name_key = vuln_name_key(&strip_version_tokens(name));
+        let vendor_key = vuln_name_key(&publisher_key(publisher));
+        for (advisory_id, aliases_json, summary, severity, cvss_score, range) in &ranges {
+            if !vuln_package_matches(&name_key, &vendor_key, range)
+                || !version_in_range(version, range)
+            {
+                continue;
+            }
+            if let Some(existing) = out
+                .iter_mut()
+                .find(|m| m.software_id == *id && &m.advisory_id == advisory_id)
+            {
+                if let Some(fixed) = range.fixed_version.clone() {
+                    if !existing.fixed_versions.contains(&fixed) {
+                        existing.fixed_versions.push(fixed);
+                    }
+                }
+                continue;
+            }
+            out.push(VulnMatch {
+                software_id: *id,
+                software_name: name.clone(),
+                software_version: version.clone(),
+                advisory_id: advisory_id.clone(),
+                aliases: serde_json::from_str(aliases_json).unwrap_or_default(),
+                summary: summary.clone(),
+                severity: severity.clone(),
+                cvss_score: *cvss_score,
+                fixed_versions: range.fixed_version.clone().into_iter().collect(),
+            });
+        }
+    }
+    Ok(out)
+}
+
+fn vuln_name_key(value: &str) -> String {
+    value
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn vuln_package_matches(name_key: &str, vendor_key: &str, range: &VulnRange) -> bool {
+    let package = vuln_name_key(&range.package_name);
+    let vendor = vuln_name_key(&range.vendor);
+    let vendor_ok = vendor.is_empty()
+        || vendor_key == vendor
+        || vendor_key.split_whitespace().next() == Some(vendor.as_str());
+    if package == name_key {
+        return vendor_ok;
+    }
+    // CPE products often omit the vendor prefix that display names carry ("google" + "chrome").
+    !vendor.is_empty() && format!("{vendor} {package}") == name_key
+}
+
+fn version_in_range(version: &str, range: &VulnRange) -> bool {
+    use std::cmp::Ordering;
+
+    if version.trim().is_empty() {
+        return false;
+    }
+    if let Some(start) = range.version_start.as_deref() {
+        match compare_versions(version, start) {
+            Ordering::Less => return false,
+            Ordering::Equal if !range.start_inclusive => return false,
+            _ => {}
+        }
+    }
+    if let Some(end) = range.version_end.as_deref() {
+        match compare_versions(version, end) {
+            Ordering::Greater => return false,
+            Ordering::Equal if !range.end_inclusive => return false,
+            _ => {}
+        }
+    }
+    true
+}
+
+fn parse_vuln_feed(
+    feed: &serde_json::Value,
+) -> Result<(&'static str, Vec<VulnAdvisory>), CliError> {
+    if let Some(items) = feed.get("vulnerabilities").and_then(|v| v.as_array()) {
+        let advisories = items
+            .iter()
+            .filter_map(|item| item.get("cve"))
+            .filter_map(parse_nvd_cve)
+            .collect();
+        return Ok(("nvd", advisories));
+    }
+    let items: Vec<&serde_json::Value> = match feed {
+        serde_json::Value::Array(items) => items.iter().collect(),
+        serde_json::Value::Object(map) if map.contains_key("vulns") => feed["vulns"]
+            .as_array()
+            .map(|items| items.iter().collect())
+            .unwrap_or_default(),
+        serde_json::Value::Object(_) => vec![feed],
+        _ => Vec::new(),
+    };
+    let advisories: Vec<VulnAdvisory> = items.into_iter().filter_map(parse_osv_entry).collect();
+    if advisories.is_empty() && !feed.as_array().is_some_and(|a| a.is_empty()) {
+        return Err(CliError::Usage(
+            "vulnerability feed contains no OSV or NVD entries".to_string(),
+        ));
+    }
+    Ok(("osv", advisories))
+}
+
+fn parse_osv_entry(entry: &serde_json::Value) -> Option<VulnAdvisory> {
+    let advisory_id = entry.get("id")?.as_str()?.trim().to_string();
+    if advisory_id.is_empty() {
+        return None;
+    }
+    let text = |key: &str| entry.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    let cvss_score = entry
+        .get("severity")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|s| {
+            s.get("type")
+                .and_then(|t| t.as_str())
+                .is_some_and(|t| t.starts_with("CVSS_V3"))
+        })
+        .filter_map(|s| s.get("score").and_then(|v| v.as_str()))
+        .filter_map(cvss3_base_score)
+        .fold(None, |acc: Option<f64>, score| {
+            Some(acc.map_or(score, |a| a.max(score)))
+        });
+    let labelled = entry
+        .pointer("/database_specific/severity")
+        .and_then(|v| v.as_str())
+        .map(normalize_severity_label);
+    let severity = labelled
+        .or_else(|| cvss_score.map(|score| severity_from_score(score).to_string()))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let mut affected = Vec::new();
+    for item in entry
+        .get("affected")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let Some(package_name) = item.pointer("/package/name").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let vendor = item
+            .pointer("/package/purl")
+            .and_then(|v| v.as_str())
+            .and_then(purl_namespace)
+            .unwrap_or_default();
+        let base = VulnRange {
+            package_name: package_name.to_string(),
+            vendor,
+            start_inclusive: true,
+            ..VulnRange::default()
+        };
+        for range in item
+            .get("ranges")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            // GIT ranges hold commit hashes, which cannot be compared with installed versions.
+            if !matches!(
+                range.get("type").and_then(|v| v.as_str()),
+                Some("SEMVER" | "ECOSYSTEM")
+            ) {
+                continue;
+            }
+            let mut current: Option<VulnRange> = None;
+            for event in range
+                .get("events")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+            {
+                if let Some(introduced) = event.get("introduced").and_then(|v| v.as_str()) {
+                    if let Some(open) = current.take() {
+                        affected.push(open);
+                    }
+                    let mut next = base.clone();
+                    next.version_start = (introduced != "0").then(|| introduced.to_string());
+                    current = Some(next);
+                } else if let Some(fixed) = event.get("fixed").and_then(|v| v.as_str()) {
+                    let mut closed = current.take().unwrap_or_else(|| base.clone());
+                    closed.version_end = Some(fixed.to_string());
+                    closed.end_inclusive = false;
+                    closed.fixed_version = Some(fixed.to_string());
+                    affected.push(closed);
+                } else if let Some(last) = event.get("last_affected").and_then(|v| v.as_str()) {
+                    let mut closed = current.take().unwrap_or_else(|| base.clone());
+                    closed.version_end = Some(last.to_string());
+                    closed.end_inclusive = true;
+                    affected.push(closed);
+                }
+            }
+            if let Some(open) = current.take() {
+                affected.push(open);
+            }
+        }
+        for version in item
+            .get("versions")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            if let Some(version) = version.as_str() {
+                affected.push(VulnRange {
+                    version_start: Some(version.to_string()),
+                    version_end: Some(version.to_string()),
+                    end_inclusive: true,
+                    ..base.clone()
+                });
+            }
+        }
+    }
+
+    Some(VulnAdvisory {
+        advisory_id,
+        source: "osv",
+        aliases: entry
+            .get("aliases")
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        summary: text("summary")
+            .or_else(|| text("details"))
+            .unwrap_or_default(),
+        severity,
+        cvss_score,
+        published: text("published"),
+        modified: text("modified"),
+        affected,
+    })
+}
+
+fn parse_nvd_cve(cve: &serde_json::Value) -> Option<VulnAdvisory> {
+    let advisory_id = cve.get("id")?.as_str()?.trim().to_string();
+    if advisory_id.is_empty() {
+        return None;
+    }
+    let summary = cve
+        .get("descriptions")
+        .and_then(|v| v.as_array())
+        .and_then(|items| {
+            items
+                .iter()
+                .find(|d| d.get("lang").and_then(|l| l.as_str()) == Some("en"))
+                .or_else(|| items.first())
+        })
+        .and_then(|d| d.get("value"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let metric = ["cvssMetricV31", "cvssMetricV30"]
+        .iter()
+        .find_map(|key| cve.pointer(&format!("/metrics/{key}/0/cvssData")));
+    let cvss_score = metric.and_then(|m| {
+        m.get("baseScore").and_then(|v| v.as_f64()).or_else(|| {
+            m.get("vectorString")
+                .and_then(|v| v.as_str())
+                .and_then(cvss3_base_score)
+        })
+    });
+    let severity = metric
+        .and_then(|m| m.get("baseSeverity"))
+        .and_then(|v| v.as_str())
+        .map(normalize_severity_label)
+        .or_else(|| cvss_score.map(|score| severity_from_score(score).to_string()))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let mut affected = Vec::new();
+    let configurations = cve.get("configurations").and_then(|v| v.as_array());
+    for config in configurations.into_iter().flatten() {
+        for node in config
+            .get("nodes")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            for cpe_match in node
+                .get("cpeMatch")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+            {
+                if cpe_match.get("vulnerable").and_then(|v| v.as_bool()) == Some(false) {
+                    continue;
+                }
+                let Some(criteria) = cpe_match.get("criteria").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let parts: Vec<&str> = criteria.split(':').collect();
+                if parts.len() < 6 || parts[2] != "a" {
+                    continue;
+                }
+                let field = |key: &str| {
+                    cpe_match
+                        .get(key)
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                };
+                let mut range = VulnRange {
+                    package_name: parts[4].replace('_', " "),
+                    vendor: parts[3].replace('_', " "),
+                    start_inclusive: true,
+                    ..VulnRange::default()
+                };
+                if let Some(start) = field("versionStartIncluding") {
+                    range.version_start = Some(start);
+                } else if let Some(start) = field("versionStartExcluding") {
+                    range.version_start = Some(start);
+                    range.start_inclusive = false;
+                }
+                if let Some(end) = field("versionEndExcluding") {
+                    range.fixed_version = Some(end.clone());
+                    range.version_end = Some(end);
+                } else if let Some(end) = field("versionEndIncluding") {
+                    range.version_end = Some(end);
+                    range.end_inclusive = true;
+                }
+                if range.version_start.is_none()
+                    && range.version_end.is_none()
+                    && !matches!(parts[5], "*" | "-")
+                {
+                    range.version_start = Some(parts[5].to_string());
+                    range.version_end = Some(parts[5].to_string());
+                    range.end_inclusive = true;
+                }
+                affected.push(range);
+            }
+        }
+    }
+
+    Some(VulnAdvisory {
+        advisory_id,
+        source: "nvd",
+        aliases: Vec::new(),
+        summary,
+        severity,
+        cvss_score,
+        published: cve
+            .get("published")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        modified: cve
+            .get("lastModified")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        affected,
+    })
+}
+
+fn purl_namespace(purl: &str) -> Option<String> {
+    let path = purl.strip_prefix("pkg:")?.split(['@', '?', '#']).next()?;
+    let segments: Vec<&str> = path.split('/').collect();
+    (segments.len() > 2).then(|| segments[1].to_string())
+}
+
+fn normalize_severity_label(label: &str) -> String {
+    match label.trim().to_lowercase().as_str() {
+        "critical" => "critical",
+        "high" => "high",
+        "moderate" | "medium" => "medium",
+        "low" => "low",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "critical" => 4,
+        "high" => 3,
+        "medium" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+fn severity_from_score(score: f64) -> &'static str {
+    if score >= 9.0 {
+        "critical"
+    } else if score >= 7.0 {
+        "high"
+    } else if score >= 4.0 {
+        "medium"
+    } else if score > 0.0 {
+        "low"
+    } else {
+        "unknown"
+    }
+}
+
+/// CVSS v3.0/v3.1 base score from a vector string such as `CVSS:3.1/AV:N/AC:L/...`.
+fn cvss3_base_score(vector: &str) -> Option<f64> {
+    let mut metrics: HashMap<&str, &str> = HashMap::new();
+    for part in vector.split('/') {
+        if let Some((key, value)) = part.split_once(':') {
+            metrics.insert(key, value);
+        }
+    }
+    let scope_changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let av = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (*metrics.get("PR")?, scope_changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let cia = |key: &str| -> Option<f64> {
+        match *metrics.get(key)? {
+            "H" => Some(0.56),
+            "L" => Some(0.22),
+            "N" => Some(0.0),
+            _ => None,
+        }
+    };
+    let iss = 1.0 - (1.0 - cia("C")?) * (1.0 - cia("I")?) * (1.0 - cia("A")?);
+    let impact = if scope_changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02_f64).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability = 8.22 * av * ac * pr * ui;
+    let raw = if scope_changed {
+        (1.08 * (impact + exploitability)).min(10.0)
+    } else {
+        (impact + exploitability).min(10.0)
+    };
+    Some(cvss_roundup(raw))
+}
+
+fn cvss_roundup(value: f64) -> f64 {
+    let int_input = (value * 100_000.0).round() as i64;
+    if int_input % 10_000 == 0 {
+        int_input as f64 / 100_000.0
+    } else {
+        ((int_input / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct EolCycle {
+    product: String,
+    cycle: String,
+    eol_date: Option<String>,
+    eol_flag: Option<bool>,
+    latest: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct EolMatch {
+    software_id: i64,
+    software_name: String,
+    software_version: String,
+    product: String,
+    cycle: String,
+    eol_date: Option<String>,
+    latest: Option<String>,
+    state: &'static str,
+    days_remaining: Option<i64>,
+}
+
+fn software_eol_sync(args: EolSyncArgs) -> Result<(), CliError> {
+    let raw = fs::read_to_string(&args.file).map_err(|e| {
+        CliError::Usage(format!(
+            "failed to read lifecycle dataset {}: {e}",
+            args.file.display()
+        ))
+    })?;
+    let dataset: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| CliError::Usage(format!("lifecycle dataset is not valid JSON: {e}")))?;
+    let cycles = parse_eol_dataset(&dataset)?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let now = unix_ts();
+
+    let tx = conn.transaction()?;
+    for cycle in &cycles {
+        tx.execute(
+            r#"
+            INSERT INTO eol_cycle (product, cycle, eol_date, eol_flag, latest, imported_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(product, cycle) DO UPDATE SET
+                eol_date = excluded.eol_date,
+                eol_flag = excluded.eol_flag,
+                latest = excluded.latest,
+                imported_at = excluded.imported_at
+            "#,
+            params![
+                cycle.product,
+                cycle.cycle,
+                cycle.eol_date,
+                cycle.eol_flag,
+                cycle.latest,
+                now
+            ],
+        )?;
+    }
+    let products: HashSet<&str> = cycles.iter().map(|c| c.product.as_str()).collect();
+    tx.execute(
+        "INSERT INTO eol_sync_history (ts, file, products, cycles) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            now,
+            args.file.display().to_string(),
+            products.len() as i64,
+            cycles.len() as i64
+        ],
+    )?;
+    tx.commit()?;
+
+    let payload = json!({
+        "file": args.file.display().to_string(),
+        "products": products.len(),
+        "cycles": cycles.len()
+    });
+    print_payload(
+        args.json,
+        payload,
+        &format!(
+            "Imported {} lifecycle cycles for {} products.",
+            cycles.len(),
+            products.len()
+        ),
+    )
+}
+
+fn software_eol(
+    soon_days: Option<u32>,
+    state: Option<&str>,
+    as_json: bool,
+) -> Result<(), CliError> {
+    if let Some(state) = state {
+        match state {
+            "eol" | "eol_soon" | "supported" | "unknown" => {}
+            _ => {
+                return Err(CliError::Usage(
+                    "--state must be one of: eol, eol_soon, supported, unknown".to_string(),
+                ));
+            }
+        }
+    }
+    let soon_days = match soon_days {
+        Some(days) => days,
+        None => load_config()?.eol.soon_days,
+    };
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let matches: Vec<EolMatch> = match_eol_cycles(&conn, i64::from(soon_days))?
+        .into_iter()
+        .filter(|m| state.is_none_or(|s| s == m.state))
+        .collect();
+    let payload: Vec<serde_json::Value> = matches
+        .iter()
+        .map(|m| {
+            json!({
+                "software_id": m.software_id,
+                "software_name": m.software_name,
+                "software_version": m.software_version,
+                "product": m.product,
+                "cycle": m.cycle,
+                "eol_date": m.eol_date,
+                "latest": m.latest,
+                "state": m.state,
+                "days_remaining": m.days_remaining
+            })
+        })
+        .collect();
+    if payload.is_empty() {
+        return print_payload(as_json, json!([]), "No lifecycle matches found.");
+    }
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&json!(payload))?);
+    } else {
+        for m in &matches {
+            println!(
+                "{} {} -> {} {} [{}] eol={}",
+                m.software_name,
+                m.software_version,
+                m.product,
+                m.cycle,
+                m.state,
+                m.eol_date.as_deref().unwrap_or("-")
+            );
+        }
+        println!("{} lifecycle matches found.", matches.len());
+    }
+    Ok(())
+}
+
+/// Matches active inventory to the most specific imported product cycle.
+fn match_eol_cycles(conn: &Connection, soon_days: i64) -> Result<Vec<EolMatch>, CliError> {
+    let mut stmt = conn.prepare(
+        "SELECT product, cycle, eol_date, eol_flag, latest FROM eol_cycle ORDER BY product ASC, cycle ASC",
+    )?;
+    let cycles = stmt
+        .query_map([], |row| {
+            Ok(EolCycle {
+                product: row.get(0)?,
+                cycle: row.get(1)?,
+                eol_date: row.get(2)?,
+                eol_flag: row.get::<_, Option<i64>>(3)?.map(|v| v == 1),
+                latest: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if cycles.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, name, version, COALESCE(publisher_canonical, publisher)
+        FROM software_inventory
+        WHERE is_active = 1
+        ORDER BY name ASC, id ASC
+        "#,
+    )?;
+    let software = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let today = unix_ts().div_euclid(86_400);
+    let mut out = Vec::new();
+    for (id, name, version, publisher) in software {
+        let name_key = vuln_name_key(&strip_version_tokens(&name));
+        let vendor_key = vuln_name_key(&publisher_key(&publisher));
+        let best = cycles
+            .iter()
+            .filter(|c| {
+                let product = vuln_name_key(&c.product);
+                product == name_key
+                    || (!vendor_key.is_empty() && format!("{vendor_key} {product}") == name_key)
+            })
+            .filter(|c| version_in_cycle(&version, &c.cycle))
+            .max_by_key(|c| version_segments(&c.cycle).len());
+        let Some(cycle) = best else {
+            continue;
+        };
+        let eol_day = cycle.eol_date.as_deref().and_then(parse_iso_date);
+        let days_remaining = eol_day.map(|day| day - today);
+        let state = match (days_remaining, cycle.eol_flag) {
+            (Some(days), _) if days <= 0 => "eol",
+            (Some(days), _) if days <= soon_days => "eol_soon",
+            (Some(_), _) => "supported",
+            (None, Some(true)) => "eol",
+            (None, Some(false)) => "supported",
+            (None, None) => "unknown",
+        };
+        out.push(EolMatch {
+            software_id: id,
+            software_name: name,
+            software_version: version,
+            product: cycle.product.clone(),
+            cycle: cycle.cycle.clone(),
+            eol_date: cycle.eol_date.clone(),
+            latest: cycle.latest.clone(),
+            state,
+            days_remaining,
+        });
+    }
+    Ok(out)
+}
+
+fn version_in_cycle(version: &str, cycle: &str) -> bool {
+    let version = version_segments(version);
+    let cycle = version_segments(cycle);
+    !cycle.is_empty() && version.len() >= cycle.len() && version[..cycle.len()] == cycle[..]
+}
+
+fn parse_eol_dataset(dataset: &serde_json::Value) -> Result<Vec<EolCycle>, CliError> {
+    let mut out = Vec::new();
+    match dataset {
+        // {"python": [{"cycle": "3.8", ...}], ...}
+        serde_json::Value::Object(map) if !map.contains_key("product") => {
+            for (product, cycles) in map {
+                for entry in cycles.as_array().into_iter().flatten() {
+                    out.extend(parse_eol_entry(product, entry));
+                }
+            }
+        }
+        // {"product": "python", "cycles": [...]}
+        serde_json::Value::Object(map) => {
+            let product = map
+                .get("product")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            for entry in map
+                .get("cycles")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+            {
+                out.extend(parse_eol_entry(product, entry));
+            }
+        }
+        // [{"product": "python", "cycle": "3.8", ...}]
+        serde_json::Value::Array(items) => {
+            for entry in items {
+                let product = entry
+                    .get("product")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                out.extend(parse_eol_entry(product, entry));
+            }
+        }
+        _ => {}
+    }
+    if out.is_empty() {
+        return Err(CliError::Usage(
+            "lifecycle dataset contains no product cycles".to_string(),
+        ));
+    }
+    Ok(out)
+}
+
+fn parse_eol_entry(product: &str, entry: &serde_json::Value) -> Option<EolCycle> {
+    let product = product.trim().to_lowercase();
+    let cycle = match entry.get("cycle")? {
+        serde_json::Value::String(v) => v.trim().to_string(),
+        serde_json::Value::Number(v) => v.to_string(),
+        _ => return None,
+    };
+    if product.is_empty() || cycle.is_empty() {
+        return None;
     }
+    let (eol_date, eol_flag) = match entry.get("eol") {
+        Some(serde_json::Value::String(v)) => (Some(v.trim().to_string()), None),
+        Some(serde_json::Value::Bool(v)) => (None, Some(*v)),
+        _ => (None, None),
+    };
+    let latest = match entry.get("latest") {
+        Some(serde_json::Value::String(v)) => Some(v.trim().to_string()),
+        Some(serde_json::Value::Number(v)) => Some(v.to_string()),
+        _ => None,
+    };
+    Some(EolCycle {
+        product,
+        cycle,
+        eol_date,
+        eol_flag,
+        latest,
+    })
+}
+
+/// Days since the Unix epoch for a `YYYY-MM-DD` date.
+fn parse_iso_date(value: &str) -> Option<i64> {
+    let mut parts = value.trim().get(..10)?.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+#[derive(Debug, Clone)]
+struct SoftwarePin {
+    software_id: i64,
+    version_constraint: String,
+    reason: String,
+    created_at: i64,
+    created_by: String,
+    expires_at: Option<i64>,
+}
+
+impl SoftwarePin {
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "software_id": self.software_id,
+            "constraint": self.version_constraint,
+            "reason": self.reason,
+            "created_at": self.created_at,
+            "created_by": self.created_by,
+            "expires_at": self.expires_at,
+            "expired": self.expires_at.is_some_and(|ts| ts <= unix_ts())
+        })
+    }
+}
+
+fn software_pin(args: SoftwarePinArgs) -> Result<(), CliError> {
+    let constraint = args.version.trim();
+    parse_version_constraint(constraint)?;
+    if args.expires_in_days == Some(0) {
+        return Err(CliError::Usage(
+            "--expires-in-days must be >= 1".to_string(),
+        ));
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+    ensure_software_exists(&conn, args.software_id)?;
+
+    let now = unix_ts();
+    let expires_at = args
+        .expires_in_days
+        .map(|days| now + i64::from(days) * 86_400);
+    conn.execute(
+        r#"
+        INSERT INTO software_pin (software_id, version_constraint, reason, created_at, created_by, expires_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT(software_id) DO UPDATE SET
+            version_constraint = excluded.version_constraint,
+            reason = excluded.reason,
+            created_at = excluded.created_at,
+            created_by = excluded.created_by,
+            expires_at = excluded.expires_at
+        "#,
+        params![
+            args.software_id,
+            constraint,
+            args.reason.as_deref().unwrap_or_default().trim(),
+            now,
+            current_os_user(),
+            expires_at
+        ],
+    )?;
+
+    let pin = load_active_pin(&conn, args.software_id)?.ok_or_else(|| {
+        CliError::Usage(format!(
+            "pin for software {} was not saved",
+            args.software_id
+        ))
+    })?;
+    print_payload(
+        args.json,
+        pin.to_json(),
+        &format!("Software {} pinned to {constraint}.", args.software_id),
+    )
+}
+
+fn software_unpin(args: SoftwareUnpinArgs) -> Result<(), CliError> {
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let removed = conn.execute(
+        "DELETE FROM software_pin WHERE software_id = ?1",
+        params![args.software_id],
+    )?;
+    if removed == 0 {
+        return Err(CliError::Usage(format!(
+            "no pin found for --software-id {}",
+            args.software_id
+        )));
+    }
+    print_payload(
+        args.json,
+        json!({"software_id": args.software_id, "unpinned": true}),
+        &format!("Software {} unpinned.", args.software_id),
+    )
+}
+
+fn software_pins(args: SoftwarePinsArgs) -> Result<(), CliError> {
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT p.software_id, p.version_constraint, p.reason, p.created_at, p.created_by, p.expires_at,
+               i.name, i.version
+        FROM software_pin p
+        LEFT JOIN software_inventory i ON i.id = p.software_id
+        WHERE ?1 = 1 OR p.expires_at IS NULL OR p.expires_at > ?2
+        ORDER BY p.software_id ASC
+        "#,
+    )?;
+    let rows = stmt.query_map(params![args.include_expired as i64, unix_ts()], |row| {
+        let pin = SoftwarePin {
+            software_id: row.get(0)?,
+            version_constraint: row.get(1)?,
+            reason: row.get(2)?,
+            created_at: row.get(3)?,
+            created_by: row.get(4)?,
+            expires_at: row.get(5)?,
+        };
+        let installed: Option<String> = row.get(7)?;
+        let mut value = pin.to_json();
+        value["software_name"] = json!(row.get::<_, Option<String>>(6)?);
+        value["installed_version"] = json!(installed);
+        value["installed_satisfies"] = json!(installed.as_deref().map(|v| {
+            parse_version_constraint(&pin.version_constraint)
+                .map(|c| version_satisfies(v, &c))
+                .unwrap_or(false)
+        }));
+        Ok(value)
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(args.json, json!([]), "No software pins found.");
+    }
+    print_payload(args.json, json!(payload), "Software pins listed.")
+}
+
+/// Returns the pin for a software item unless it has expired.
+fn load_active_pin(conn: &Connection, software_id: i64) -> Result<Option<SoftwarePin>, CliError> {
+    Ok(load_active_pins(conn)?.remove(&software_id))
+}
+
+fn load_active_pins(conn: &Connection) -> Result<HashMap<i64, SoftwarePin>, CliError> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT software_id, version_constraint, reason, created_at, created_by, expires_at
+        FROM software_pin
+        WHERE expires_at IS NULL OR expires_at > ?1
+        "#,
+    )?;
+    let rows = stmt.query_map(params![unix_ts()], |row| {
+        Ok(SoftwarePin {
+            software_id: row.get(0)?,
+            version_constraint: row.get(1)?,
+            reason: row.get(2)?,
+            created_at: row.get(3)?,
+            created_by: row.get(4)?,
+            expires_at: row.get(5)?,
+        })
+    })?;
+    let mut out = HashMap::new();
+    for pin in rows {
+        let pin = pin?;
+        out.insert(pin.software_id, pin);
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BaselineDefinition {
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    required: Vec<BaselineEntry>,
+    #[serde(default)]
+    forbidden: Vec<BaselineEntry>,
+    #[serde(default)]
+    optional: Vec<BaselineEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BaselineEntry {
+    name: String,
+    #[serde(default)]
+    publisher: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct BaselineSoftware {
+    id: i64,
+    name: String,
+    version: String,
+    publisher: String,
+}
+
+#[derive(Debug, Clone)]
+struct DriftItem {
+    kind: &'static str,
+    software_id: Option<i64>,
+    name: String,
+    version: Option<String>,
+    expected: Option<String>,
+    detail: String,
+}
+
+impl DriftItem {
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "kind": self.kind,
+            "software_id": self.software_id,
+            "name": self.name,
+            "version": self.version,
+            "expected": self.expected,
+            "detail": self.detail
+        })
+    }
+}
+
+fn baselines_dir() -> Result<PathBuf, CliError> {
+    Ok(synora_home()?.join("baselines"))
+}
+
+fn validate_baseline_name(raw: &str) -> Result<&str, CliError> {
+    let name = raw.trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        || name.starts_with('.')
+    {
+        return Err(CliError::Usage(
+            "--baseline must contain only letters, digits, '-', '_' or '.'".to_string(),
+        ));
+    }
+    Ok(name)
+}
+
+fn load_baseline(name: &str) -> Result<BaselineDefinition, CliError> {
+    let dir = baselines_dir()?;
+    let path = ["yaml", "yml"]
+        .iter()
+        .map(|ext| dir.join(format!("{name}.{ext}")))
+        .find(|p| p.is_file())
+        .ok_or_else(|| {
+            CliError::Usage(format!("baseline '{name}' not found in {}", dir.display()))
+        })?;
+    let raw = fs::read_to_string(&path)?;
+    let definition: BaselineDefinition = serde_yaml::from_str(&raw)
+        .map_err(|e| CliError::Config(format!("invalid baseline {}: {e}", path.display())))?;
+    for entry in definition
+        .required
+        .iter()
+        .chain(&definition.forbidden)
+        .chain(&definition.optional)
+    {
+        if entry.name.trim().is_empty() {
+            return Err(CliError::Config(format!(
+                "invalid baseline {}: entry name must not be empty",
+                path.display()
+            )));
+        }
+        if let Some(version) = entry.version.as_deref() {
+            parse_version_constraint(version).map_err(|_| {
+                CliError::Config(format!(
+                    "invalid baseline {}: invalid version constraint '{version}' for '{}'",
+                    path.display(),
+                    entry.name
+                ))
+            })?;
+        }
+    }
+    Ok(definition)
+}
+
+/// Matches a baseline entry name against a product; `*` in the entry acts as a wildcard.
+fn baseline_name_matches(pattern: &str, name: &str) -> bool {
+    let name = vuln_name_key(&strip_version_tokens(name));
+    let parts: Vec<String> = pattern.split('*').map(vuln_name_key).collect();
+    if parts.len() == 1 {
+        return name == parts[0] || name == vuln_name_key(&strip_version_tokens(pattern));
+    }
+    let mut rest = name.as_str();
+    for (idx, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        if idx == 0 {
+            let Some(tail) = rest.strip_prefix(part.as_str()) else {
+                return false;
+            };
+            rest = tail;
+        } else if idx == parts.len() - 1 {
+            return rest.ends_with(part.as_str());
+        } else {
+            let Some(pos) = rest.find(part.as_str()) else {
+                return false;
+            };
+            rest = &rest[pos + part.len()..];
+        }
+    }
+    true
+}
+
+fn baseline_entry_matches(entry: &BaselineEntry, software: &BaselineSoftware) -> bool {
+    if !baseline_name_matches(&entry.name, &software.name) {
+        return false;
+    }
+    entry
+        .publisher
+        .as_deref()
+        .is_none_or(|p| publisher_key(p) == publisher_key(&software.publisher))
+}
+
+fn evaluate_baseline(
+    definition: &BaselineDefinition,
+    inventory: &[BaselineSoftware],
+) -> Result<Vec<DriftItem>, CliError> {
+    let mut drift = Vec::new();
+    let mut accounted: HashSet<i64> = HashSet::new();
+
+    for entry in &definition.required {
+        let matched: Vec<&BaselineSoftware> = inventory
+            .iter()
+            .filter(|s| baseline_entry_matches(entry, s))
+            .collect();
+        if matched.is_empty() {
+            drift.push(DriftItem {
+                kind: "missing",
+                software_id: None,
+                name: entry.name.clone(),
+                version: None,
+                expected: entry.version.clone(),
+                detail: entry
+                    .reason
+                    .clone()
+                    .unwrap_or_else(|| "required software is not installed".to_string()),
+            });
+            continue;
+        }
+        accounted.extend(matched.iter().map(|s| s.id));
+        let Some(constraint) = entry.version.as_deref() else {
+            continue;
+        };
+        let comparators = parse_version_constraint(constraint)?;
+        // One satisfying install is enough; side-by-side older copies are not drift.
+        if !matched
+            .iter()
+            .any(|s| version_satisfies(&s.version, &comparators))
+        {
+            for software in matched {
+                drift.push(DriftItem {
+                    kind: "out_of_constraint",
+                    software_id: Some(software.id),
+                    name: software.name.clone(),
+                    version: Some(software.version.clone()),
+                    expected: Some(constraint.to_string()),
+                    detail: format!("installed version does not satisfy '{constraint}'"),
+                });
+            }
+        }
+    }
+
+    for entry in &definition.forbidden {
+        for software in inventory
+            .iter()
+            .filter(|s| baseline_entry_matches(entry, s))
+        {
+            let banned = entry
+                .version
+                .as_deref()
+                .map(parse_version_constraint)
+                .transpose()?
+                .is_none_or(|c| version_satisfies(&software.version, &c));
+            if !banned {
+                continue;
+            }
+            accounted.insert(software.id);
+            drift.push(DriftItem {
+                kind: "forbidden_present",
+                software_id: Some(software.id),
+                name: software.name.clone(),
+                version: Some(software.version.clone()),
+                expected: entry.version.clone(),
+                detail: entry
+                    .reason
+                    .clone()
+                    .unwrap_or_else(|| "software is forbidden by baseline".to_string()),
+            });
+        }
+    }
+
+    for entry in &definition.optional {
+        accounted.extend(
+            inventory
+                .iter()
+                .filter(|s| baseline_entry_matches(entry, s))
+                .map(|s| s.id),
+        );
+    }
+
+    for software in inventory.iter().filter(|s| !accounted.contains(&s.id)) {
+        drift.push(DriftItem {
+            kind: "extra",
+            software_id: Some(software.id),
+            name: software.name.clone(),
+            version: Some(software.version.clone()),
+            expected: None,
+            detail: "software is not listed in baseline".to_string(),
+        });
+    }
+    Ok(drift)
+}
+
+fn software_baseline_check(args: BaselineCheckArgs) -> Result<(), CliError> {
+    let baseline = validate_baseline_name(&args.baseline)?.to_string();
+    let definition = load_baseline(&baseline)?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+
+    let inventory = {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, name, version, COALESCE(publisher_canonical, publisher)
+            FROM software_inventory
+            WHERE is_active = 1
+            ORDER BY name ASC, id ASC
+            "#,
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(BaselineSoftware {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                version: row.get(2)?,
+                publisher: row.get(3)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+    let drift = evaluate_baseline(&definition, &inventory)?;
+
+    let count = |kind: &str| drift.iter().filter(|d| d.kind == kind).count();
+    let missing = count("missing");
+    let forbidden = count("forbidden_present");
+    let out_of_constraint = count("out_of_constraint");
+    let extra = count("extra");
+    // Extra software is reported but only policy violations count as drift.
+    let drifted = missing + forbidden + out_of_constraint > 0;
+    let status = if drifted { "drift" } else { "compliant" };
+    let check_id = next_operation_id("baseline", inventory.len() as i64);
+    let ts = unix_ts();
+
+    let tx = conn.transaction()?;
+    tx.execute(
+        r#"
+        INSERT INTO baseline_check_history
+        (check_id, baseline, ts, status, missing_count, forbidden_count, out_of_constraint_count, extra_count)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        "#,
+        params![
+            check_id,
+            baseline,
+            ts,
+            status,
+            missing as i64,
+            forbidden as i64,
+            out_of_constraint as i64,
+            extra as i64
+        ],
+    )?;
+    for item in &drift {
+        tx.execute(
+            r#"
+            INSERT INTO baseline_drift_item (check_id, baseline, kind, software_id, name, version, expected, detail)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            params![
+                check_id,
+                baseline,
+                item.kind,
+                item.software_id,
+                item.name,
+                item.version,
+                item.expected,
+                item.detail
+            ],
+        )?;
+    }
+    tx.commit()?;
+
+    let filter = |kind: &str| -> Vec<serde_json::Value> {
+        drift
+            .iter()
+            .filter(|d| d.kind == kind)
+            .map(DriftItem::to_json)
+            .collect()
+    };
+    let payload = json!({
+        "check_id": check_id,
+        "baseline": baseline,
+        "description": definition.description,
+        "status": status,
+        "checked_software": inventory.len(),
+        "missing": filter("missing"),
+        "forbidden_present": filter("forbidden_present"),
+        "out_of_constraint": filter("out_of_constraint"),
+        "extra": filter("extra")
+    });
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        for item in drift.iter().filter(|d| d.kind != "extra") {
+            println!(
+                "[{}] {} {} {}",
+                item.kind,
+                item.name,
+                item.version.as_deref().unwrap_or("-"),
+                item.detail
+            );
+        }
+        println!(
+            "Baseline '{baseline}' {status}: missing={missing}, forbidden_present={forbidden}, out_of_constraint={out_of_constraint}, extra={extra}."
+        );
+    }
+
+    if drifted {
+        return Err(CliError::Drift(format!(
+            "baseline '{baseline}' has {} violations (check_id={check_id})",
+            missing + forbidden + out_of_constraint
+        )));
+    }
+    Ok(())
+}
+
+fn software_baseline_list(as_json: bool) -> Result<(), CliError> {
+    let dir = baselines_dir()?;
+    let mut names: Vec<(String, PathBuf)> = Vec::new();
+    if dir.is_dir() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let is_yaml = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e == "yaml" || e == "yml");
+            if let (true, Some(stem)) = (is_yaml, path.file_stem().and_then(|s| s.to_str())) {
+                names.push((stem.to_string(), path.clone()));
+            }
+        }
+    }
+    names.sort();
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut payload = Vec::new();
+    for (name, path) in names {
+        let last = conn
+            .query_row(
+                r#"
+                SELECT check_id, ts, status FROM baseline_check_history
+                WHERE baseline = ?1 ORDER BY id DESC LIMIT 1
+                "#,
+                params![name],
+                |row| {
+                    Ok(json!({
+                        "check_id": row.get::<_, String>(0)?,
+                        "ts": row.get::<_, i64>(1)?,
+                        "status": row.get::<_, String>(2)?
+                    }))
+                },
+            )
+            .optional()?;
+        let mut item = json!({
+            "name": name,
+            "path": path.display().to_string(),
+            "last_check": last
+        });
+        match load_baseline(&name) {
+            Ok(def) => {
+                item["description"] = json!(def.description);
+                item["required"] = json!(def.required.len());
+                item["forbidden"] = json!(def.forbidden.len());
+                item["optional"] = json!(def.optional.len());
+            }
+            Err(err) => item["error"] = json!(err.to_string()),
+        }
+        payload.push(item);
+    }
+    if payload.is_empty() {
+        return print_payload(as_json, json!([]), "No baselines found.");
+    }
+    print_payload(as_json, json!(payload), "Baselines listed.")
+}
+
+fn software_baseline_history(args: BaselineHistoryArgs) -> Result<(), CliError> {
+    let limit = i64::from(args.limit.unwrap_or(20));
+    if limit <= 0 {
+        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT check_id, baseline, ts, status, missing_count, forbidden_count, out_of_constraint_count, extra_count
+        FROM baseline_check_history
+        WHERE (?1 IS NULL OR baseline = ?1) AND (?2 IS NULL OR check_id = ?2)
+        ORDER BY id DESC
+        LIMIT ?3
+        "#,
+    )?;
+    let mut payload: Vec<serde_json::Value> = stmt
+        .query_map(params![args.baseline, args.check_id, limit], |row| {
+            Ok(json!({
+                "check_id": row.get::<_, String>(0)?,
+                "baseline": row.get::<_, String>(1)?,
+                "ts": row.get::<_, i64>(2)?,
+                "status": row.get::<_, String>(3)?,
+                "missing": row.get::<_, i64>(4)?,
+                "forbidden_present": row.get::<_, i64>(5)?,
+                "out_of_constraint": row.get::<_, i64>(6)?,
+                "extra": row.get::<_, i64>(7)?
+            }))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if args.check_id.is_some() {
+        let mut item_stmt = conn.prepare(
+            r#"
+            SELECT kind, software_id, name, version, expected, detail
+            FROM baseline_drift_item
+            WHERE check_id = ?1
+            ORDER BY id ASC
+            "#,
+        )?;
+        for check in &mut payload {
+            let check_id = check["check_id"].as_str().unwrap_or_default().to_string();
+            let items: Vec<serde_json::Value> = item_stmt
+                .query_map(params![check_id], |row| {
+                    Ok(json!({
+                        "kind": row.get::<_, String>(0)?,
+                        "software_id": row.get::<_, Option<i64>>(1)?,
+                        "name": row.get::<_, String>(2)?,
+                        "version": row.get::<_, Option<String>>(3)?,
+                        "expected": row.get::<_, Option<String>>(4)?,
+                        "detail": row.get::<_, String>(5)?
+                    }))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            check["items"] = json!(items);
+        }
+    }
+
+    if payload.is_empty() {
+        return print_payload(args.json, json!([]), "No baseline checks found.");
+    }
+    print_payload(args.json, json!(payload), "Baseline history listed.")
+}
+
+fn publisher_alias_add(args: PublisherAliasAddArgs) -> Result<(), CliError> {
+    let alias_key = publisher_key(&args.alias);
+    let canonical = args.canonical.trim();
+    if alias_key.is_empty() {
+        return Err(CliError::Usage("--alias must not be empty".to_string()));
+    }
+    if canonical.is_empty() {
+        return Err(CliError::Usage("--canonical must not be empty".to_string()));
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    conn.execute(
+        r#"
+        INSERT INTO publisher_alias (alias_key, canonical, origin, enabled, updated_at)
+        VALUES (?1, ?2, 'user', 1, ?3)
+        ON CONFLICT(alias_key) DO UPDATE SET
+            canonical = excluded.canonical,
+            origin = 'user',
+            enabled = 1,
+            updated_at = excluded.updated_at
+        "#,
+        params![alias_key, canonical, unix_ts()],
+    )?;
+    let reapplied = apply_publisher_aliases(&conn, "1 = 1")?;
+
+    let payload = json!({
+        "alias_key": alias_key,
+        "canonical": canonical,
+        "software_updated": reapplied
+    });
+    print_payload(
+        args.json,
+        payload,
+        &format!(
+            "Publisher alias '{alias_key}' -> '{canonical}' saved ({reapplied} software updated)."
+        ),
+    )
 }
 
-fn handle_config(command: ConfigCommand) -> Result<(), CliError> {
-    match command {
-        ConfigCommand::Init(args) => config_init(args.json),
-        ConfigCommand::GateShow(args) => config_gate_show(args.json),
-        ConfigCommand::GateSet(args) => config_gate_set(args),
-        ConfigCommand::GateHistory(args) => config_gate_history(args),
+fn publisher_alias_list(as_json: bool) -> Result<(), CliError> {
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT a.alias_key, a.canonical, a.origin, a.updated_at,
+               (SELECT COUNT(1) FROM software_inventory i WHERE i.publisher_canonical = a.canonical AND i.is_active = 1)
+        FROM publisher_alias a
+        WHERE a.enabled = 1
+        ORDER BY a.canonical ASC, a.alias_key ASC
+        "#,
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(json!({
+            "alias_key": row.get::<_, String>(0)?,
+            "canonical": row.get::<_, String>(1)?,
+            "origin": row.get::<_, String>(2)?,
+            "updated_at": row.get::<_, i64>(3)?,
+            "active_software": row.get::<_, i64>(4)?
+        }))
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(as_json, json!([]), "No publisher aliases found.");
     }
+    print_payload(as_json, json!(payload), "Publisher aliases listed.")
 }
 
-fn handle_software(command: SoftwareCommand) -> Result<(), CliError> {
-    match command {
-        SoftwareCommand::Discover { command } => match command {
-            DiscoverCommand::Scan(args) => software_discover_scan(args.json),
-            DiscoverCommand::History(args) => software_discover_history(args),
-        },
-        SoftwareCommand::List(args) => software_list(args),
+fn publisher_alias_remove(args: PublisherAliasRemoveArgs) -> Result<(), CliError> {
+    let alias_key = publisher_key(&args.alias);
+    if alias_key.is_empty() {
+        return Err(CliError::Usage("--alias must not be empty".to_string()));
     }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let origin: Option<String> = conn
+        .query_row(
+            "SELECT origin FROM publisher_alias WHERE alias_key = ?1 AND enabled = 1",
+            params![alias_key],
+            |r| r.get(0),
+        )
+        .optional()?;
+    match origin.as_deref() {
+        None => {
+            return Err(CliError::Usage(format!(
+                "publisher alias not found: {alias_key}"
+            )));
+        }
+        // Built-in rows are disabled rather than deleted so the seed does not bring them back.
+        Some("builtin") => conn.execute(
+            "UPDATE publisher_alias SET enabled = 0, updated_at = ?1 WHERE alias_key = ?2",
+            params![unix_ts(), alias_key],
+        )?,
+        Some(_) => conn.execute(
+            "DELETE FROM publisher_alias WHERE alias_key = ?1",
+            params![alias_key],
+        )?,
+    };
+    let reapplied = apply_publisher_aliases(&conn, "1 = 1")?;
+
+    let payload = json!({
+        "alias_key": alias_key,
+        "removed": true,
+        "software_updated": reapplied
+    });
+    print_payload(
+        args.json,
+        payload,
+        &format!("Publisher alias '{alias_key}' removed ({reapplied} software updated)."),
+    )
 }
 
-fn handle_source(command: SourceCommand) -> Result<(), CliError> {
-    match command {
-        SourceCommand::Suggest(args) => source_suggest(args),
-        SourceCommand::Review(args) => source_review(args),
-        SourceCommand::ReviewBulk(args) => source_review_bulk(args),
-        SourceCommand::List(args) => source_list(args),
-        SourceCommand::ApplyApproved(args) => source_apply_approved(args),
-        SourceCommand::RegistryList(args) => source_registry_list(args),
-        SourceCommand::RegistryDisable(args) => source_registry_disable(args),
-        SourceCommand::RegistryEnable(args) => source_registry_enable(args),
+fn source_suggest(args: SourceSuggestArgs) -> Result<(), CliError> {
+    let limit = i64::from(args.limit.unwrap_or(50));
+    if limit <= 0 {
+        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+    }
+    let min_confidence = args.min_confidence.unwrap_or(0);
+    if let Some(status) = args.status.as_deref() {
+        match status {
+            "pending" | "approved" | "rejected" => {}
+            _ => {
+                return Err(CliError::Usage(
+                    "--status must be one of: pending, approved, rejected".to_string(),
+                ));
+            }
+        }
     }
-}
 
-fn handle_update(command: UpdateCommand) -> Result<(), CliError> {
-    match command {
-        UpdateCommand::Check(args) => update_check(args),
-        UpdateCommand::Apply(args) => update_apply(args),
-        UpdateCommand::History(args) => update_history(args),
+    let config = load_config()?;
+    let rules = load_source_rules(&config.source_url_policy)?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let (mut canonicalized, mut collapsed) = (0, 0);
+    run_migration_once(&conn, "0002_canonicalize_source_candidates", |tx| {
+        (canonicalized, collapsed) = canonicalize_source_candidates(tx, &config.source_url_policy)?;
+        Ok(())
+    })?;
+
+    let started = std::time::Instant::now();
+    let targets = load_suggest_targets(&conn, &args.software_id)?;
+    // Anything the candidates or their scores depend on besides the software itself.
+    let rules_signature = serde_json::to_string(&json!([
+        rules.iter().map(SourceRule::to_json).collect::<Vec<_>>(),
+        config.source_url_policy,
+        config.download_source_policy.untrusted_domains,
+        config.auto_review.trusted_domains
+    ]))?;
+    let scoring = ScoringContext::load(&conn, &config)?;
+    let force = args.full || !args.software_id.is_empty();
+    let now = unix_ts();
+    let mut processed = 0_i64;
+    let mut unchanged = 0_i64;
+    let mut generated = 0_i64;
+    let mut upserted = 0_i64;
+    let mut skipped_reviewed = 0_i64;
+
+    let tx = conn.transaction()?;
+    {
+        let mut state_stmt =
+            tx.prepare("SELECT input_hash FROM source_suggest_state WHERE software_id = ?1")?;
+        // Reviewed candidates keep the confidence and reason their reviewer saw.
+        let mut upsert_stmt = tx.prepare(
+            r#"
+            INSERT INTO source_candidate
+            (software_id, software_name, url, domain, confidence, reason, status, created_at, rule_id, score_breakdown)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending', ?7, ?8, ?9)
+            ON CONFLICT(software_id, url) DO UPDATE SET
+                confidence=excluded.confidence,
+                reason=excluded.reason,
+                rule_id=excluded.rule_id,
+                score_breakdown=excluded.score_breakdown
+            WHERE source_candidate.status = 'pending'
+            "#,
+        )?;
+        let mut save_state_stmt = tx.prepare(
+            r#"
+            INSERT INTO source_suggest_state (software_id, input_hash, candidates, processed_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(software_id) DO UPDATE SET
+                input_hash=excluded.input_hash,
+                candidates=excluded.candidates,
+                processed_at=excluded.processed_at
+            "#,
+        )?;
+
+        for target in &targets {
+            let input_hash = suggest_input_hash(target, &rules_signature);
+            if !force {
+                let previous: Option<String> = state_stmt
+                    .query_row(params![target.software_id], |row| row.get(0))
+                    .optional()?;
+                if previous.as_deref() == Some(input_hash.as_str()) {
+                    unchanged += 1;
+                    continue;
+                }
+            }
+            processed += 1;
+
+            let candidates = build_source_candidates(
+                &rules,
+                &SourceRuleInput {
+                    name: &target.name,
+                    publisher: &target.publisher,
+                    canonical_publisher: &target.canonical_publisher,
+                    discovery_source: &target.discovery_source,
+                },
+            );
+            generated += candidates.len() as i64;
+
+            for c in &candidates {
+                let score = scoring.score(target.software_id, c, &target.canonical_publisher);
+                let changed = upsert_stmt.execute(params![
+                    target.software_id,
+                    target.name,
+                    c.url,
+                    c.domain,
+                    score.total,
+                    c.reason,
+                    now,
+                    c.rule_id,
+                    score.to_json().to_string()
+                ])?;
+                if changed == 0 {
+                    skipped_reviewed += 1;
+                } else {
+                    upserted += 1;
+                }
+            }
+            save_state_stmt.execute(params![
+                target.software_id,
+                input_hash,
+                candidates.len() as i64,
+                now
+            ])?;
+        }
     }
-}
+    tx.commit()?;
+    let elapsed_ms = started.elapsed().as_millis();
 
-fn handle_ai(command: AiCommand) -> Result<(), CliError> {
-    match command {
-        AiCommand::Analyze(args) => ai_analyze(args),
-        AiCommand::Recommend(args) => ai_recommend(args),
-        AiCommand::RepairPlan(args) => ai_repair_plan(args),
+    let auto_review = if args.auto_review || config.auto_review.run_on_suggest {
+        Some(run_auto_review(&mut conn, &config, true, None)?)
+    } else {
+        None
+    };
+
+    let mut cte_clauses: Vec<String> = vec!["confidence >= ?".to_string()];
+    let mut values: Vec<Value> = vec![Value::Integer(min_confidence)];
+    if let Some(domain) = args.domain.clone() {
+        cte_clauses.push("domain = ?".to_string());
+        values.push(Value::Text(domain));
+    }
+    if let Some(contains) = args.contains.clone() {
+        cte_clauses.push("(software_name LIKE ? OR url LIKE ? OR reason LIKE ?)".to_string());
+        let like = format!("%{contains}%");
+        values.push(Value::Text(like.clone()));
+        values.push(Value::Text(like.clone()));
+        values.push(Value::Text(like));
+    }
+    if let Some(status) = args.status.clone() {
+        cte_clauses.push("status = ?".to_string());
+        values.push(Value::Text(status));
+    }
+    if !args.software_id.is_empty() {
+        let placeholders = vec!["?"; args.software_id.len()].join(", ");
+        cte_clauses.push(format!("software_id IN ({placeholders})"));
+        values.extend(args.software_id.iter().map(|id| Value::Integer(*id)));
     }
-}
 
-fn handle_ui(command: UiCommand) -> Result<(), CliError> {
-    match command {
-        UiCommand::Search(args) => ui_search(args),
-        UiCommand::ActionRun(args) => ui_action_run(args),
+    let mut sql = String::from(
+        r#"
+        WITH ranked AS (
+            SELECT
+                id,
+                software_id,
+                software_name,
+                url,
+                domain,
+                confidence,
+                reason,
+                status,
+                ROW_NUMBER() OVER (
+                    PARTITION BY software_id
+                    ORDER BY confidence DESC, id ASC
+                ) AS rn
+            FROM source_candidate
+        "#,
+    );
+    if !cte_clauses.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&cte_clauses.join(" AND "));
     }
-}
+    sql.push_str(
+        r#"
+        )
+        SELECT id, software_id, software_name, url, domain, confidence, reason, status
+        FROM ranked
+        WHERE rn = 1
+        ORDER BY confidence DESC, software_name ASC LIMIT ?
+        "#,
+    );
+    values.push(Value::Integer(limit));
 
-fn handle_job(command: JobCommand) -> Result<(), CliError> {
-    match command {
-        JobCommand::Submit(args) => job_submit(args),
-        JobCommand::List(args) => job_list(args),
-        JobCommand::Retry(args) => job_retry(args),
-        JobCommand::DeadletterList(args) => job_deadletter_list(args),
-        JobCommand::ReplayDeadletter(args) => job_replay_deadletter(args),
-        JobCommand::WorkerRun(args) => job_worker_run(args),
-        JobCommand::SchedulerRun(args) => job_scheduler_run(args),
+    let mut out_stmt = conn.prepare(&sql)?;
+    let out_rows = out_stmt.query_map(params_from_iter(values.iter()), |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, i64>(5)?,
+            row.get::<_, String>(6)?,
+            row.get::<_, String>(7)?,
+        ))
+    })?;
+
+    let raw_items: Vec<SuggestedCandidateRow> = out_rows.collect::<Result<Vec<_>, _>>()?;
+
+    let mut domain_counts: HashMap<String, usize> = HashMap::new();
+    let mut items: Vec<serde_json::Value> = Vec::new();
+    let mut capped = 0_i64;
+    const MAX_PER_DOMAIN: usize = 25;
+    for (candidate_id, software_id, software_name, url, domain, confidence, reason, status) in raw_items {
+        let count = domain_counts.entry(domain.clone()).or_insert(0);
+        if *count >= MAX_PER_DOMAIN {
+            capped += 1;
+            continue;
+        }
+        *count += 1;
+        items.push(json!({
+            "candidate_id": candidate_id,
+            "software_id": software_id,
+            "software_name": software_name,
+            "url": url,
+            "domain": domain,
+            "confidence": confidence,
+            "reason": reason,
+            "status": status
+        }));
+    }
+
+    if args.json {
+        let payload = json!({
+            "processed": processed,
+            "unchanged": unchanged,
+            "generated": generated,
+            "upserted": upserted,
+            "skipped_reviewed": skipped_reviewed,
+            "skipped_domain_cap": capped,
+            "canonicalized": canonicalized,
+            "collapsed": collapsed,
+            "elapsed_ms": elapsed_ms,
+            "auto_review": auto_review.as_ref().map(|run| json!({
+                "run_id": run.run_id,
+                "approved": run.approved,
+                "rejected": run.rejected,
+                "pending": run.pending
+            })),
+            "shown": items.len(),
+            "items": items
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        println!(
+            "Source suggest finished: processed={processed}, unchanged={unchanged}, generated={generated}, upserted={upserted}, skipped_reviewed={skipped_reviewed}, skipped_domain_cap={capped}, shown={}, elapsed_ms={elapsed_ms}",
+            items.len()
+        );
+        if canonicalized > 0 || collapsed > 0 {
+            println!(
+                "Canonicalized {canonicalized} candidate URLs, collapsed {collapsed} duplicates."
+            );
+        }
+        if let Some(run) = &auto_review {
+            println!(
+                "Auto-review {}: approved={}, rejected={}, pending={}",
+                run.run_id, run.approved, run.rejected, run.pending
+            );
+        }
+        println!(
+            "Filters: limit={limit}, min_confidence={min_confidence}, domain={:?}, contains={:?}, status={:?}",
+            args.domain, args.contains, args.status
+        );
     }
+    Ok(())
 }
 
-fn handle_cleanup(command: CleanupCommand) -> Result<(), CliError> {
-    match command {
-        CleanupCommand::Apply(args) => cleanup_apply(args),
-        CleanupCommand::History(args) => cleanup_history(args),
+struct SuggestTarget {
+    software_id: i64,
+    name: String,
+    publisher: String,
+    canonical_publisher: String,
+    discovery_source: String,
+}
+
+/// Active software to run source rules for; a non-empty `software_ids` restricts the set.
+fn load_suggest_targets(
+    conn: &Connection,
+    software_ids: &[i64],
+) -> Result<Vec<SuggestTarget>, CliError> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, name, publisher, COALESCE(publisher_canonical, publisher), discovery_source
+        FROM software_inventory
+        WHERE is_active = 1
+        ORDER BY id ASC
+        "#,
+    )?;
+    let targets = stmt
+        .query_map([], |row| {
+            Ok(SuggestTarget {
+                software_id: row.get(0)?,
+                name: row.get(1)?,
+                publisher: row.get(2)?,
+                canonical_publisher: row.get(3)?,
+                discovery_source: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if software_ids.is_empty() {
+        return Ok(targets);
+    }
+    let missing: Vec<String> = software_ids
+        .iter()
+        .filter(|id| !targets.iter().any(|t| t.software_id == **id))
+        .map(i64::to_string)
+        .collect();
+    if !missing.is_empty() {
+        return Err(CliError::Usage(format!(
+            "--software-id not found or inactive: {}",
+            missing.join(", ")
+        )));
     }
+    Ok(targets
+        .into_iter()
+        .filter(|t| software_ids.contains(&t.software_id))
+        .collect())
 }
 
-fn handle_repo(command: RepoCommand) -> Result<(), CliError> {
-    match command {
-        RepoCommand::List(args) => repo_list(args),
-        RepoCommand::Add(args) => repo_add(args),
-        RepoCommand::Remove(args) => repo_remove(args),
-        RepoCommand::Sync(args) => repo_sync(args),
+/// Change detector for incremental `source suggest`: SHA-256 over the length-prefixed inputs, so
+/// stored hashes stay valid across builds and toolchains.
+fn suggest_input_hash(target: &SuggestTarget, rules_signature: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        &target.name,
+        &target.publisher,
+        &target.canonical_publisher,
+        &target.discovery_source,
+        rules_signature,
+    ] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
     }
+    format!("{:x}", hasher.finalize())
 }
 
-fn handle_package(command: PackageCommand) -> Result<(), CliError> {
-    match command {
-        PackageCommand::Search(args) => package_search(args),
+fn source_rules_list(as_json: bool) -> Result<(), CliError> {
+    let rules = load_source_rules(&load_config()?.source_url_policy)?;
+    if as_json {
+        let payload: Vec<serde_json::Value> = rules.iter().map(SourceRule::to_json).collect();
+        println!("{}", serde_json::to_string_pretty(&json!(payload))?);
+    } else {
+        for rule in &rules {
+            println!(
+                "{} [{}] {} confidence={} {}",
+                rule.spec.id, rule.origin, rule.domain, rule.spec.confidence, rule.spec.reason
+            );
+        }
+        println!("{} source rules loaded.", rules.len());
     }
+    Ok(())
 }
 
-fn handle_download(command: DownloadCommand) -> Result<(), CliError> {
-    match command {
-        DownloadCommand::Start(args) => download_start(args),
-        DownloadCommand::List(args) => download_list(args),
-        DownloadCommand::Show(args) => download_show(args),
-        DownloadCommand::Retry(args) => download_retry(args),
-        DownloadCommand::Verify(args) => download_verify(args),
-        DownloadCommand::History(args) => download_history(args),
+fn source_rules_test(args: SourceRulesTestArgs) -> Result<(), CliError> {
+    let name = args.name.trim();
+    if name.is_empty() {
+        return Err(CliError::Usage("--name must not be empty".to_string()));
     }
-}
+    let publisher = args.publisher.as_deref().unwrap_or_default().trim();
+    let discovery_source = args
+        .discovery_source
+        .as_deref()
+        .unwrap_or("registry")
+        .trim();
+    let rules = load_source_rules(&load_config()?.source_url_policy)?;
 
-fn software_discover_scan(as_json: bool) -> Result<(), CliError> {
     let db_file = db_path()?;
     init_db(&db_file)?;
     let conn = Connection::open(db_file)?;
+    let canonical = canonical_publisher(publisher, &load_publisher_aliases(&conn)?);
 
-    let discovered = discover_registry_software()?;
-    let total_seen = discovered.len() as i64;
-    let mut inserted = 0_i64;
-    let mut updated = 0_i64;
-    let mut reactivated = 0_i64;
-    let mut deactivated = 0_i64;
-    let mut skipped = 0_i64;
-    let now = unix_ts();
-    let mut seen_fingerprints: HashSet<String> = HashSet::new();
-
-    for item in discovered {
-        let fingerprint = make_fingerprint(&item.name, &item.publisher, &item.install_location);
-        if fingerprint.is_empty() {
-            skipped += 1;
-            continue;
+    let input = SourceRuleInput {
+        name,
+        publisher,
+        canonical_publisher: &canonical,
+        discovery_source,
+    };
+    let outcomes = evaluate_source_rules(&rules, &input);
+    let payload = json!({
+        "name": name,
+        "publisher": publisher,
+        "canonical_publisher": canonical,
+        "discovery_source": discovery_source,
+        "rules": outcomes
+            .iter()
+            .map(|(rule, outcome)| {
+                let mut item = json!({"id": rule.spec.id, "origin": rule.origin, "outcome": outcome});
+                if *outcome == "fired" {
+                    match rule.render(&input) {
+                        Some(draft) => {
+                            item["url"] = json!(draft.url);
+                            item["domain"] = json!(draft.domain);
+                            item["confidence"] = json!(draft.confidence);
+                            item["reason"] = json!(draft.reason);
+                        }
+                        None => item["outcome"] = json!("rejected_url"),
+                    }
+                }
+                item
+            })
+            .collect::<Vec<_>>()
+    });
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        for (rule, outcome) in &outcomes {
+            println!("{} -> {}", rule.spec.id, outcome);
         }
-        seen_fingerprints.insert(fingerprint.clone());
+        let fired = outcomes.iter().filter(|(_, o)| *o == "fired").count();
+        println!("{fired} of {} source rules fired.", outcomes.len());
+    }
+    Ok(())
+}
 
-        let existing_active: Option<i64> = conn
-            .query_row(
-                "SELECT is_active FROM software_inventory WHERE fingerprint = ?1",
-                params![&fingerprint],
-                |r| r.get::<_, i64>(0),
-            )
-            .optional()?;
+fn source_review(args: SourceReviewArgs) -> Result<(), CliError> {
+    if args.interactive {
+        return source_review_interactive(&args);
+    }
+    let Some(candidate_id) = args.candidate_id else {
+        return Err(CliError::Usage(
+            "--candidate-id is required unless --interactive is set".to_string(),
+        ));
+    };
+    if args.approve == args.reject {
+        return Err(CliError::Usage(
+            "exactly one of --approve or --reject must be set".to_string(),
+        ));
+    }
 
-        conn.execute(
-            r#"
-            INSERT INTO software_inventory
-            (name, version, publisher, install_location, discovery_source, source_confidence, first_seen_at, last_seen_at, is_active, fingerprint)
-            VALUES (?1, ?2, ?3, ?4, ?5, 80, ?6, ?6, 1, ?7)
-            ON CONFLICT(fingerprint) DO UPDATE SET
-                name=excluded.name,
-                version=excluded.version,
-                publisher=excluded.publisher,
-                install_location=excluded.install_location,
-                discovery_source=excluded.discovery_source,
-                source_confidence=excluded.source_confidence,
-                last_seen_at=excluded.last_seen_at,
-                is_active=1
-            "#,
-            params![
-                item.name,
-                item.version,
-                item.publisher,
-                item.install_location,
-                item.discovery_source,
-                now,
-                fingerprint
-            ],
-        )?;
+    let status = if args.approve { "approved" } else { "rejected" };
+    let reviewer = resolve_reviewer(args.reviewer.as_deref());
+    let note = args.note.as_deref().map(str::trim).unwrap_or_default();
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
 
-        match existing_active {
-            None => inserted += 1,
-            Some(0) => {
-                updated += 1;
-                reactivated += 1;
-            }
-            Some(_) => updated += 1,
+    let Some(review) = load_candidate_review_state(&conn, candidate_id)? else {
+        return Err(CliError::Usage(format!(
+            "candidate_id {} not found",
+            candidate_id
+        )));
+    };
+    if let Some(why) = review.note_required_reason() {
+        if note.is_empty() {
+            return Err(CliError::Usage(format!(
+                "--note is required to review candidate_id {}: {why}",
+                candidate_id
+            )));
         }
     }
 
-    let mut stmt = conn.prepare(
-        "SELECT id, fingerprint FROM software_inventory WHERE discovery_source = 'registry' AND is_active = 1",
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE source_candidate SET status = ?1 WHERE id = ?2",
+        params![status, candidate_id],
     )?;
-    let existing_rows = stmt
-        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
-        .collect::<Result<Vec<_>, _>>()?;
-    for (id, fingerprint) in existing_rows {
-        if !seen_fingerprints.contains(&fingerprint) {
-            conn.execute(
-                "UPDATE software_inventory SET is_active = 0 WHERE id = ?1",
-                params![id],
-            )?;
-            deactivated += 1;
+    record_source_review(&tx, &review, status, &reviewer, note, None)?;
+    tx.commit()?;
+
+    let payload = json!({
+        "candidate_id": candidate_id,
+        "previous_status": review.status,
+        "status": status,
+        "reviewer": reviewer,
+        "note": note,
+        "updated": true
+    });
+    print_payload(args.json, payload, "Source candidate reviewed.")
+}
+
+/// Decisions taken in `source review --interactive` are written once this many are buffered.
+const INTERACTIVE_REVIEW_BATCH_SIZE: usize = 10;
+
+#[derive(Debug, Clone)]
+struct InteractiveReviewItem {
+    review: CandidateReviewState,
+    reason: String,
+    rule_id: Option<String>,
+    score_breakdown: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+struct InteractiveDecision {
+    candidate_id: i64,
+    status: &'static str,
+    note: String,
+}
+
+/// Cursor over pending candidates plus the decisions not yet written to the database.
+struct InteractiveReviewSession {
+    items: Vec<InteractiveReviewItem>,
+    position: usize,
+    note: String,
+    buffered: Vec<InteractiveDecision>,
+    batch_ids: Vec<String>,
+    written: usize,
+}
+
+impl InteractiveReviewSession {
+    fn new(items: Vec<InteractiveReviewItem>) -> Self {
+        Self {
+            items,
+            position: 0,
+            note: String::new(),
+            buffered: Vec::new(),
+            batch_ids: Vec::new(),
+            written: 0,
         }
     }
 
-    let active_after: i64 = conn.query_row(
-        "SELECT COUNT(1) FROM software_inventory WHERE discovery_source = 'registry' AND is_active = 1",
-        [],
-        |r| r.get(0),
-    )?;
+    fn current(&self) -> Option<&InteractiveReviewItem> {
+        self.items.get(self.position)
+    }
 
-    let scan_id = next_operation_id("discover", total_seen);
+    fn decide(&mut self, status: &'static str) -> Result<(), String> {
+        let Some(item) = self.current() else {
+            return Err("no candidate selected".to_string());
+        };
+        if let Some(why) = item.review.note_required_reason() {
+            if self.note.trim().is_empty() {
+                return Err(format!("a note is required: {why}"));
+            }
+        }
+        let candidate_id = item.review.candidate_id;
+        let note = std::mem::take(&mut self.note).trim().to_string();
+        self.buffered.push(InteractiveDecision {
+            candidate_id,
+            status,
+            note,
+        });
+        self.position += 1;
+        Ok(())
+    }
 
-    conn.execute(
+    fn skip(&mut self) {
+        self.note.clear();
+        self.position += 1;
+    }
+
+    /// Takes back the most recent unwritten decision and returns to its candidate.
+    fn undo(&mut self) -> Option<InteractiveDecision> {
+        let decision = self.buffered.pop()?;
+        if let Some(index) = self
+            .items
+            .iter()
+            .position(|i| i.review.candidate_id == decision.candidate_id)
+        {
+            self.position = index;
+        }
+        self.note = decision.note.clone();
+        Some(decision)
+    }
+}
+
+fn load_interactive_review_items(
+    conn: &Connection,
+) -> Result<Vec<InteractiveReviewItem>, CliError> {
+    let mut stmt = conn.prepare(
         r#"
-        INSERT INTO software_discovery_history
-        (scan_id, ts, source, total_seen, inserted, updated, reactivated, deactivated, skipped, active_after)
-        VALUES (?1, ?2, 'registry', ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        SELECT c.id, c.software_id, c.software_name, c.url, c.domain, c.status, c.confidence, i.criticality,
+               c.reason, c.rule_id, c.score_breakdown
+        FROM source_candidate c
+        LEFT JOIN software_inventory i ON i.id = c.software_id
+        WHERE c.status = 'pending'
+        ORDER BY c.software_name ASC, c.software_id ASC, c.confidence DESC, c.id ASC
         "#,
-        params![
-            scan_id,
-            now,
-            total_seen,
-            inserted,
-            updated,
-            reactivated,
-            deactivated,
-            skipped,
-            active_after
-        ],
     )?;
-
-    let payload = json!({
-        "scan_id": scan_id,
-        "source": "registry",
-        "total_seen": total_seen,
-        "inserted": inserted,
-        "updated": updated,
-        "reactivated": reactivated,
-        "deactivated": deactivated,
-        "active_after": active_after,
-        "skipped": skipped,
-        "duration_ms": 0
-    });
-    print_payload(as_json, payload, "Discovery scan finished.")
+    let items = stmt
+        .query_map([], |row| {
+            Ok(InteractiveReviewItem {
+                review: CandidateReviewState {
+                    candidate_id: row.get(0)?,
+                    software_id: row.get(1)?,
+                    software_name: row.get(2)?,
+                    url: row.get(3)?,
+                    domain: row.get(4)?,
+                    status: row.get(5)?,
+                    confidence: row.get(6)?,
+                    criticality: row.get(7)?,
+                },
+                reason: row.get(8)?,
+                rule_id: row.get(9)?,
+                score_breakdown: parse_score_breakdown(
+                    row.get::<_, Option<String>>(10)?.as_deref(),
+                ),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
 }
 
-fn software_discover_history(args: DiscoverHistoryArgs) -> Result<(), CliError> {
-    let limit = i64::from(args.limit.unwrap_or(100));
-    let offset = i64::from(args.offset.unwrap_or(0));
-    if limit <= 0 {
-        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+/// Writes the buffered decisions as one `interactive` review batch (undoable with `source review-undo`).
+fn flush_interactive_review(
+    conn: &mut Connection,
+    session: &mut InteractiveReviewSession,
+    reviewer: &str,
+) -> Result<Option<String>, CliError> {
+    if session.buffered.is_empty() {
+        return Ok(None);
+    }
+    let batch_id = next_operation_id("review-interactive", session.buffered.len() as i64);
+    let tx = conn.transaction()?;
+    let mut updated = 0_usize;
+    for decision in &session.buffered {
+        let Some(review) = load_candidate_review_state(&tx, decision.candidate_id)? else {
+            continue;
+        };
+        // Someone else reviewed it since the session started; keep their decision.
+        if review.status != "pending" {
+            continue;
+        }
+        tx.execute(
+            "UPDATE source_candidate SET status = ?1 WHERE id = ?2",
+            params![decision.status, decision.candidate_id],
+        )?;
+        record_source_review(
+            &tx,
+            &review,
+            decision.status,
+            reviewer,
+            &decision.note,
+            Some(&batch_id),
+        )?;
+        updated += 1;
     }
+    tx.execute(
+        r#"
+        INSERT INTO source_review_batch (batch_id, kind, target_status, reviewer, note, matched, updated, ts)
+        VALUES (?1, 'interactive', 'mixed', ?2, '', ?3, ?4, ?5)
+        "#,
+        params![batch_id, reviewer, session.buffered.len() as i64, updated as i64, unix_ts()],
+    )?;
+    tx.commit()?;
+    session.written += updated;
+    session.buffered.clear();
+    session.batch_ids.push(batch_id.clone());
+    Ok(Some(batch_id))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReviewKey {
+    Approve,
+    Reject,
+    Skip,
+    Note,
+    Details,
+    Undo,
+    Quit,
+}
+
+/// Reads one keystroke in raw mode; the terminal is back in cooked mode when this returns.
+fn read_review_key() -> Result<ReviewKey, CliError> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+
+    crossterm::terminal::enable_raw_mode()?;
+    let key = loop {
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(e) => break Err(e),
+        };
+        let mapped = match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => ReviewKey::Quit,
+            KeyCode::Esc => ReviewKey::Quit,
+            KeyCode::Char(c) => match c.to_ascii_lowercase() {
+                'a' => ReviewKey::Approve,
+                'r' => ReviewKey::Reject,
+                's' => ReviewKey::Skip,
+                'n' => ReviewKey::Note,
+                'd' => ReviewKey::Details,
+                'u' => ReviewKey::Undo,
+                'q' => ReviewKey::Quit,
+                _ => continue,
+            },
+            _ => continue,
+        };
+        break Ok(mapped);
+    };
+    crossterm::terminal::disable_raw_mode()?;
+    Ok(key?)
+}
+
+fn format_score_factors(score_breakdown: &serde_json::Value) -> String {
+    score_breakdown["factors"]
+        .as_array()
+        .map(|factors| {
+            factors
+                .iter()
+                .map(|f| {
+                    format!(
+                        "{} {:+}",
+                        f["factor"].as_str().unwrap_or("?"),
+                        f["points"].as_i64().unwrap_or(0)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_else(|| "no breakdown recorded".to_string())
+}
 
-    let db_file = db_path()?;
-    init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
+fn print_interactive_registry(conn: &Connection, software_id: i64) -> Result<(), CliError> {
+    let mut stmt = conn.prepare(
+        "SELECT url, status, health FROM source_registry WHERE software_id = ?1 ORDER BY id ASC",
+    )?;
+    let entries = stmt
+        .query_map(params![software_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if entries.is_empty() {
+        println!("  registry: none");
+    }
+    for (url, status, health) in entries {
+        println!("  registry: {url} [{status}, {health}]");
+    }
+    Ok(())
+}
 
+fn print_interactive_details(
+    conn: &Connection,
+    item: &InteractiveReviewItem,
+) -> Result<(), CliError> {
+    let review = &item.review;
+    println!(
+        "    rule:        {}",
+        item.rule_id.as_deref().unwrap_or("-")
+    );
+    println!("    reason:      {}", item.reason);
+    println!(
+        "    risk:        {} ({})",
+        review.risk_level(),
+        review.criticality.as_deref().unwrap_or("unclassified")
+    );
+    if let Some(why) = review.note_required_reason() {
+        println!("    note needed: {why}");
+    }
+    for factor in item.score_breakdown["factors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        println!(
+            "    {:<24} {:+4}  {}",
+            factor["factor"].as_str().unwrap_or("?"),
+            factor["points"].as_i64().unwrap_or(0),
+            factor["detail"].as_str().unwrap_or_default()
+        );
+    }
     let mut stmt = conn.prepare(
         r#"
-        SELECT scan_id, ts, source, total_seen, inserted, updated, reactivated, deactivated, skipped, active_after
-        FROM software_discovery_history
-        ORDER BY id DESC
-        LIMIT ?1 OFFSET ?2
+        SELECT new_status, reviewer, note FROM source_review_history
+        WHERE candidate_id = ?1 ORDER BY id DESC LIMIT 3
         "#,
     )?;
-    let rows = stmt.query_map(params![limit, offset], |row| {
-        Ok(json!({
-            "scan_id": row.get::<_, String>(0)?,
-            "timestamp": row.get::<_, i64>(1)?,
-            "source": row.get::<_, String>(2)?,
-            "total_seen": row.get::<_, i64>(3)?,
-            "inserted": row.get::<_, i64>(4)?,
-            "updated": row.get::<_, i64>(5)?,
-            "reactivated": row.get::<_, i64>(6)?,
-            "deactivated": row.get::<_, i64>(7)?,
-            "skipped": row.get::<_, i64>(8)?,
-            "active_after": row.get::<_, i64>(9)?
-        }))
-    })?;
-    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
-    if payload.is_empty() {
-        return print_payload(args.json, json!([]), "No discover history entries found.");
+    let history = stmt
+        .query_map(params![review.candidate_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (status, reviewer, note) in history {
+        println!("    history:     {status} by {reviewer} {note}");
     }
-    print_payload(args.json, json!(payload), "Discover history listed.")
+    Ok(())
 }
 
-fn software_list(args: SoftwareListArgs) -> Result<(), CliError> {
-    let limit = i64::from(args.limit.unwrap_or(200));
-    let offset = i64::from(args.offset.unwrap_or(0));
-    if limit <= 0 {
-        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+fn source_review_interactive(args: &SourceReviewArgs) -> Result<(), CliError> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if args.candidate_id.is_some()
+        || args.approve
+        || args.reject
+        || args.note.is_some()
+        || args.json
+    {
+        return Err(CliError::Usage(
+            "--interactive cannot be combined with --candidate-id, --approve, --reject, --note or --json".to_string(),
+        ));
     }
+    if !std::io::stdout().is_terminal() || !std::io::stdin().is_terminal() {
+        return Err(CliError::Usage(
+            "--interactive requires a terminal (stdout is not a TTY); use --candidate-id with --approve or --reject".to_string(),
+        ));
+    }
+    let reviewer = resolve_reviewer(args.reviewer.as_deref());
 
     let db_file = db_path()?;
     init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let mut session = InteractiveReviewSession::new(load_interactive_review_items(&conn)?);
+    if session.items.is_empty() {
+        println!("No pending source candidates.");
+        return Ok(());
+    }
+
+    let total = session.items.len();
+    let mut shown_software: Option<i64> = None;
+    while let Some(item) = session.current().cloned() {
+        let review = &item.review;
+        if shown_software != Some(review.software_id) {
+            println!();
+            println!(
+                "== {} (software {}) ==",
+                review.software_name, review.software_id
+            );
+            print_interactive_registry(&conn, review.software_id)?;
+            shown_software = Some(review.software_id);
+        }
+        println!();
+        println!(
+            "[{}/{total}] candidate {}",
+            session.position + 1,
+            review.candidate_id
+        );
+        println!("  url:        {}", review.url);
+        println!("  domain:     {}", review.domain);
+        println!(
+            "  confidence: {} ({})",
+            review.confidence,
+            format_score_factors(&item.score_breakdown)
+        );
+        if !session.note.is_empty() {
+            println!("  note:       {}", session.note);
+        }
+        println!("  [a]pprove [r]eject [s]kip [n]ote [d]etails [u]ndo [q]uit");
+        std::io::stdout().flush()?;
+
+        match read_review_key()? {
+            key @ (ReviewKey::Approve | ReviewKey::Reject) => {
+                let status = if key == ReviewKey::Approve { "approved" } else { "rejected" };
+                match session.decide(status) {
+                    Ok(()) => println!("  -> {status}"),
+                    Err(why) => println!("  ! {why}; press n to add a note"),
+                }
+                if session.buffered.len() >= INTERACTIVE_REVIEW_BATCH_SIZE {
+                    if let Some(batch_id) = flush_interactive_review(&mut conn, &mut session, &reviewer)? {
+                        println!("  wrote batch {batch_id}");
+                    }
+                }
+            }
+            ReviewKey::Skip => session.skip(),
+            ReviewKey::Note => {
+                print!("  note> ");
+                std::io::stdout().flush()?;
+                let mut line = String::new();
+                std::io::stdin().lock().read_line(&mut line)?;
+                session.note = line.trim().to_string();
+            }
+            ReviewKey::Details => print_interactive_details(&conn, &item)?,
+            ReviewKey::Undo => match session.undo() {
+                Some(decision) => {
+                    println!("  undid {} of candidate {}", decision.status, decision.candidate_id);
+                    shown_software = None;
+                }
+                None => match session.batch_ids.last() {
+                    Some(batch_id) => println!(
+                        "  nothing left to undo in this batch; use `source review-undo --batch-id {batch_id}`"
+                    ),
+                    None => println!("  nothing to undo"),
+                },
+            },
+            ReviewKey::Quit => break,
+        }
+    }
 
-    let mut sql = String::from(
-        r#"
-        SELECT id, name, version, publisher, install_location, discovery_source, source_confidence, first_seen_at, last_seen_at, is_active
-        FROM software_inventory
-        "#,
+    flush_interactive_review(&mut conn, &mut session, &reviewer)?;
+    println!();
+    println!(
+        "Interactive review finished: written={}, batches={}",
+        session.written,
+        session.batch_ids.len()
     );
-
-    let mut clauses: Vec<String> = Vec::new();
-    let mut values: Vec<Value> = Vec::new();
-    if args.active_only {
-        clauses.push("is_active = 1".to_string());
+    for batch_id in &session.batch_ids {
+        println!("  undo with: source review-undo --batch-id {batch_id}");
     }
-    if let Some(contains) = args.contains.clone() {
-        clauses.push("(name LIKE ? OR publisher LIKE ? OR version LIKE ?)".to_string());
-        let like = format!("%{contains}%");
-        values.push(Value::Text(like.clone()));
-        values.push(Value::Text(like.clone()));
-        values.push(Value::Text(like));
+    Ok(())
+}
+
+/// Confidence below which a review decision must carry a note.
+const REVIEW_NOTE_MIN_CONFIDENCE: i64 = 50;
+
+#[derive(Debug, Clone)]
+struct CandidateReviewState {
+    candidate_id: i64,
+    software_id: i64,
+    software_name: String,
+    url: String,
+    domain: String,
+    status: String,
+    confidence: i64,
+    criticality: Option<String>,
+}
+
+impl CandidateReviewState {
+    fn risk_level(&self) -> &'static str {
+        let mut facts = RiskFacts::new("source.review");
+        facts.criticality = self.criticality.clone();
+        facts.source_confidence = Some(self.confidence);
+        evaluate_risk(&facts).level
     }
-    if !clauses.is_empty() {
-        sql.push_str(" WHERE ");
-        sql.push_str(&clauses.join(" AND "));
+
+    fn note_required_reason(&self) -> Option<String> {
+        if self.confidence < REVIEW_NOTE_MIN_CONFIDENCE {
+            return Some(format!(
+                "confidence {} is below {REVIEW_NOTE_MIN_CONFIDENCE}",
+                self.confidence
+            ));
+        }
+        if self.risk_level() == "high" {
+            return Some(format!(
+                "risk is high for {} software",
+                self.criticality.as_deref().unwrap_or("unclassified")
+            ));
+        }
+        None
     }
-    sql.push_str(" ORDER BY name ASC LIMIT ? OFFSET ?");
-    values.push(Value::Integer(limit));
-    values.push(Value::Integer(offset));
+}
 
-    let mut stmt = conn.prepare(&sql)?;
+fn load_candidate_review_state(
+    conn: &Connection,
+    candidate_id: i64,
+) -> Result<Option<CandidateReviewState>, CliError> {
+    Ok(conn
+        .query_row(
+            r#"
+            SELECT c.id, c.software_id, c.software_name, c.url, c.domain, c.status, c.confidence, i.criticality
+            FROM source_candidate c
+            LEFT JOIN software_inventory i ON i.id = c.software_id
+            WHERE c.id = ?1
+            "#,
+            params![candidate_id],
+            |row| {
+                Ok(CandidateReviewState {
+                    candidate_id: row.get(0)?,
+                    software_id: row.get(1)?,
+                    software_name: row.get(2)?,
+                    url: row.get(3)?,
+                    domain: row.get(4)?,
+                    status: row.get(5)?,
+                    confidence: row.get(6)?,
+                    criticality: row.get(7)?,
+                })
+            },
+        )
+        .optional()?)
+}
 
-    let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
-        Ok(json!({
-            "id": row.get::<_, i64>(0)?,
-            "name": row.get::<_, String>(1)?,
-            "version": row.get::<_, String>(2)?,
-            "publisher": row.get::<_, String>(3)?,
-            "install_location": row.get::<_, String>(4)?,
-            "discovery_source": row.get::<_, String>(5)?,
-            "source_confidence": row.get::<_, i64>(6)?,
-            "first_seen_at": row.get::<_, i64>(7)?,
-            "last_seen_at": row.get::<_, i64>(8)?,
-            "is_active": row.get::<_, i64>(9)? == 1
-        }))
-    })?;
-    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
-    if payload.is_empty() {
-        return print_payload(args.json, json!([]), "No software entries found.");
-    }
-    print_payload(args.json, json!(payload), "Software entries listed.")
+fn resolve_reviewer(explicit: Option<&str>) -> String {
+    explicit
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(current_os_user)
 }
 
-fn source_suggest(args: SourceSuggestArgs) -> Result<(), CliError> {
+fn record_source_review(
+    conn: &Connection,
+    review: &CandidateReviewState,
+    new_status: &str,
+    reviewer: &str,
+    note: &str,
+    batch_id: Option<&str>,
+) -> Result<(), CliError> {
+    conn.execute(
+        r#"
+        INSERT INTO source_review_history
+        (candidate_id, software_id, old_status, new_status, reviewer, note, confidence, risk_level, batch_id, ts)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        "#,
+        params![
+            review.candidate_id,
+            review.software_id,
+            review.status,
+            new_status,
+            reviewer,
+            note,
+            review.confidence,
+            review.risk_level(),
+            batch_id,
+            unix_ts()
+        ],
+    )?;
+    // Review outcomes feed the domain history used to score other software on this domain.
+    conn.execute(
+        r#"
+        DELETE FROM source_suggest_state
+        WHERE software_id IN (SELECT software_id FROM source_candidate WHERE domain = ?1 AND status = 'pending')
+        "#,
+        params![review.domain],
+    )?;
+    Ok(())
+}
+
+fn source_review_history(args: SourceReviewHistoryArgs) -> Result<(), CliError> {
     let limit = i64::from(args.limit.unwrap_or(50));
     if limit <= 0 {
         return Err(CliError::Usage("--limit must be >= 1".to_string()));
     }
-    let min_confidence = args.min_confidence.unwrap_or(0);
     if let Some(status) = args.status.as_deref() {
         match status {
             "pending" | "approved" | "rejected" => {}
@@ -1132,187 +6921,62 @@ fn source_suggest(args: SourceSuggestArgs) -> Result<(), CliError> {
     init_db(&db_file)?;
     let conn = Connection::open(db_file)?;
 
-    let mut stmt = conn.prepare(
-        r#"
-        SELECT id, name, publisher
-        FROM software_inventory
-        WHERE is_active = 1
-        ORDER BY id ASC
-        "#,
-    )?;
-
-    let mut rows = stmt.query([])?;
-    let now = unix_ts();
-    let mut generated = 0_i64;
-    let mut upserted = 0_i64;
-
-    while let Some(row) = rows.next()? {
-        let software_id: i64 = row.get(0)?;
-        let name: String = row.get(1)?;
-        let publisher: String = row.get(2)?;
-
-        let candidates = build_source_candidates(&name, &publisher);
-        generated += candidates.len() as i64;
-
-        for c in candidates {
-            conn.execute(
-                r#"
-                INSERT INTO source_candidate
-                (software_id, software_name, url, domain, confidence, reason, status, created_at)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending', ?7)
-                ON CONFLICT(software_id, url) DO UPDATE SET
-                    confidence=excluded.confidence,
-                    reason=excluded.reason
-                "#,
-                params![
-                    software_id,
-                    name,
-                    c.url,
-                    c.domain,
-                    c.confidence,
-                    c.reason,
-                    now
-                ],
-            )?;
-            upserted += 1;
-        }
-    }
-
-    let mut cte_clauses: Vec<String> = vec!["confidence >= ?".to_string()];
-    let mut values: Vec<Value> = vec![Value::Integer(min_confidence)];
-    if let Some(domain) = args.domain.clone() {
-        cte_clauses.push("domain = ?".to_string());
-        values.push(Value::Text(domain));
-    }
-    if let Some(contains) = args.contains.clone() {
-        cte_clauses.push("(software_name LIKE ? OR url LIKE ? OR reason LIKE ?)".to_string());
-        let like = format!("%{contains}%");
-        values.push(Value::Text(like.clone()));
-        values.push(Value::Text(like.clone()));
-        values.push(Value::Text(like));
-    }
-    if let Some(status) = args.status.clone() {
-        cte_clauses.push("status = ?".to_string());
-        values.push(Value::Text(status));
-    }
-
     let mut sql = String::from(
         r#"
-        WITH ranked AS (
-            SELECT
-                id,
-                software_id,
-                software_name,
-                url,
-                domain,
-                confidence,
-                reason,
-                status,
-                ROW_NUMBER() OVER (
-                    PARTITION BY software_id
-                    ORDER BY confidence DESC, id ASC
-                ) AS rn
-            FROM source_candidate
-        "#,
-    );
-    if !cte_clauses.is_empty() {
-        sql.push_str(" WHERE ");
-        sql.push_str(&cte_clauses.join(" AND "));
-    }
-    sql.push_str(
-        r#"
-        )
-        SELECT id, software_id, software_name, url, domain, confidence, reason, status
-        FROM ranked
-        WHERE rn = 1
-        ORDER BY confidence DESC, software_name ASC LIMIT ?
+        SELECT h.id, h.candidate_id, h.software_id, c.software_name, c.url, h.old_status, h.new_status,
+               h.reviewer, h.note, h.confidence, h.risk_level, h.batch_id, h.ts
+        FROM source_review_history h
+        LEFT JOIN source_candidate c ON c.id = h.candidate_id
         "#,
     );
-    values.push(Value::Integer(limit));
-
-    let mut out_stmt = conn.prepare(&sql)?;
-    let out_rows = out_stmt.query_map(params_from_iter(values.iter()), |row| {
-        Ok((
-            row.get::<_, i64>(0)?,
-            row.get::<_, i64>(1)?,
-            row.get::<_, String>(2)?,
-            row.get::<_, String>(3)?,
-            row.get::<_, String>(4)?,
-            row.get::<_, i64>(5)?,
-            row.get::<_, String>(6)?,
-            row.get::<_, String>(7)?,
-        ))
-    })?;
-
-    let raw_items: Vec<(i64, i64, String, String, String, i64, String, String)> =
-        out_rows.collect::<Result<Vec<_>, _>>()?;
-
-    let mut domain_counts: HashMap<String, usize> = HashMap::new();
-    let mut items: Vec<serde_json::Value> = Vec::new();
-    const MAX_PER_DOMAIN: usize = 25;
-    for (candidate_id, software_id, software_name, url, domain, confidence, reason, status) in raw_items {
-        let count = domain_counts.entry(domain.clone()).or_insert(0);
-        if *count >= MAX_PER_DOMAIN {
-            continue;
-        }
-        *count += 1;
-        items.push(json!({
-            "candidate_id": candidate_id,
-            "software_id": software_id,
-            "software_name": software_name,
-            "url": url,
-            "domain": domain,
-            "confidence": confidence,
-            "reason": reason,
-            "status": status
-        }));
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if let Some(candidate_id) = args.candidate_id {
+        clauses.push("h.candidate_id = ?".to_string());
+        values.push(Value::Integer(candidate_id));
     }
-
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&items)?);
-    } else {
-        println!(
-            "Source suggest finished: generated={generated}, upserted={upserted}, shown={}",
-            items.len()
-        );
-        println!(
-            "Filters: limit={limit}, min_confidence={min_confidence}, domain={:?}, contains={:?}, status={:?}",
-            args.domain, args.contains, args.status
-        );
+    if let Some(reviewer) = args.reviewer.clone() {
+        clauses.push("h.reviewer = ?".to_string());
+        values.push(Value::Text(reviewer));
     }
-    Ok(())
-}
-
-fn source_review(args: SourceReviewArgs) -> Result<(), CliError> {
-    if args.approve == args.reject {
-        return Err(CliError::Usage(
-            "exactly one of --approve or --reject must be set".to_string(),
-        ));
+    if let Some(batch_id) = args.batch_id.clone() {
+        clauses.push("h.batch_id = ?".to_string());
+        values.push(Value::Text(batch_id));
     }
-
-    let status = if args.approve { "approved" } else { "rejected" };
-    let db_file = db_path()?;
-    init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
-
-    let changed = conn.execute(
-        "UPDATE source_candidate SET status = ?1 WHERE id = ?2",
-        params![status, args.candidate_id],
-    )?;
-
-    if changed == 0 {
-        return Err(CliError::Usage(format!(
-            "candidate_id {} not found",
-            args.candidate_id
-        )));
+    if let Some(status) = args.status.clone() {
+        clauses.push("h.new_status = ?".to_string());
+        values.push(Value::Text(status));
     }
+    if !clauses.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&clauses.join(" AND "));
+    }
+    sql.push_str(" ORDER BY h.id DESC LIMIT ?");
+    values.push(Value::Integer(limit));
 
-    let payload = json!({
-        "candidate_id": args.candidate_id,
-        "status": status,
-        "updated": true
-    });
-    print_payload(args.json, payload, "Source candidate reviewed.")
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+        Ok(json!({
+            "id": row.get::<_, i64>(0)?,
+            "candidate_id": row.get::<_, i64>(1)?,
+            "software_id": row.get::<_, i64>(2)?,
+            "software_name": row.get::<_, Option<String>>(3)?,
+            "url": row.get::<_, Option<String>>(4)?,
+            "old_status": row.get::<_, String>(5)?,
+            "new_status": row.get::<_, String>(6)?,
+            "reviewer": row.get::<_, String>(7)?,
+            "note": row.get::<_, String>(8)?,
+            "confidence": row.get::<_, i64>(9)?,
+            "risk_level": row.get::<_, String>(10)?,
+            "batch_id": row.get::<_, Option<String>>(11)?,
+            "ts": row.get::<_, i64>(12)?
+        }))
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(args.json, json!([]), "No source review history found.");
+    }
+    print_payload(args.json, json!(payload), "Source review history listed.")
 }
 
 fn source_review_bulk(args: SourceReviewBulkArgs) -> Result<(), CliError> {
@@ -1340,7 +7004,9 @@ fn source_review_bulk(args: SourceReviewBulkArgs) -> Result<(), CliError> {
 
     let db_file = db_path()?;
     init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    // Selection, note checks and updates share one write lock so a concurrent review can't slip in between.
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
 
     let mut sql = String::from("SELECT id FROM source_candidate");
     let mut clauses: Vec<String> = Vec::new();
@@ -1368,21 +7034,100 @@ fn source_review_bulk(args: SourceReviewBulkArgs) -> Result<(), CliError> {
     sql.push_str(" ORDER BY id DESC LIMIT ?");
     values.push(Value::Integer(limit));
 
-    let mut stmt = conn.prepare(&sql)?;
+    let mut stmt = tx.prepare(&sql)?;
     let ids = stmt
         .query_map(params_from_iter(values.iter()), |row| row.get::<_, i64>(0))?
         .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
 
-    let mut changed = 0_i64;
+    let reviewer = resolve_reviewer(args.reviewer.as_deref());
+    let note = args.note.as_deref().map(str::trim).unwrap_or_default();
+    let mut reviews = Vec::with_capacity(ids.len());
     for id in &ids {
-        changed += conn.execute(
+        if let Some(review) = load_candidate_review_state(&tx, *id)? {
+            reviews.push(review);
+        }
+    }
+
+    if args.preview {
+        let items: Vec<serde_json::Value> = reviews
+            .iter()
+            .map(|r| {
+                json!({
+                    "candidate_id": r.candidate_id,
+                    "software_id": r.software_id,
+                    "software_name": r.software_name,
+                    "url": r.url,
+                    "domain": r.domain,
+                    "confidence": r.confidence,
+                    "risk_level": r.risk_level(),
+                    "current_status": r.status,
+                    "target_status": target_status,
+                    "note_required": r.note_required_reason()
+                })
+            })
+            .collect();
+        if args.json {
+            let payload = json!({
+                "preview": true,
+                "target_status": target_status,
+                "matched": items.len(),
+                "candidates": items
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        } else {
+            for r in &reviews {
+                println!(
+                    "{} {} {} [{} -> {}] confidence={}",
+                    r.candidate_id, r.software_name, r.url, r.status, target_status, r.confidence
+                );
+            }
+            println!(
+                "{} source candidates would be {target_status}.",
+                reviews.len()
+            );
+        }
+        return Ok(());
+    }
+
+    if note.is_empty() {
+        let needs_note: Vec<String> = reviews
+            .iter()
+            .filter_map(|r| {
+                r.note_required_reason()
+                    .map(|why| format!("{} ({why})", r.candidate_id))
+            })
+            .collect();
+        if !needs_note.is_empty() {
+            return Err(CliError::Usage(format!(
+                "--note is required for candidates: {}",
+                needs_note.join(", ")
+            )));
+        }
+    }
+
+    let batch_id = next_operation_id("review-batch", ids.len() as i64);
+    let mut changed = 0_i64;
+    for review in &reviews {
+        changed += tx.execute(
             "UPDATE source_candidate SET status = ?1 WHERE id = ?2",
-            params![target_status, id],
+            params![target_status, review.candidate_id],
         )? as i64;
+        record_source_review(&tx, review, target_status, &reviewer, note, Some(&batch_id))?;
     }
+    tx.execute(
+        r#"
+        INSERT INTO source_review_batch (batch_id, kind, target_status, reviewer, note, matched, updated, ts)
+        VALUES (?1, 'bulk', ?2, ?3, ?4, ?5, ?6, ?7)
+        "#,
+        params![batch_id, target_status, reviewer, note, ids.len() as i64, changed, unix_ts()],
+    )?;
+    tx.commit()?;
 
     let payload = json!({
+        "batch_id": batch_id,
         "target_status": target_status,
+        "reviewer": reviewer,
         "matched": ids.len(),
         "updated": changed,
         "limit": limit
@@ -1390,6 +7135,653 @@ fn source_review_bulk(args: SourceReviewBulkArgs) -> Result<(), CliError> {
     print_payload(args.json, payload, "Source candidates reviewed in bulk.")
 }
 
+fn source_review_undo(args: SourceReviewUndoArgs) -> Result<(), CliError> {
+    let batch_id = args.batch_id.trim();
+    if batch_id.is_empty() {
+        return Err(CliError::Usage("--batch-id must not be empty".to_string()));
+    }
+    let reviewer = resolve_reviewer(args.reviewer.as_deref());
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    // The conflict check must see the same state the restore writes over.
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+    let batch = tx
+        .query_row(
+            "SELECT kind, undone_by_batch FROM source_review_batch WHERE batch_id = ?1",
+            params![batch_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)),
+        )
+        .optional()?;
+    let Some((kind, undone_by)) = batch else {
+        return Err(CliError::Usage(format!(
+            "review batch '{batch_id}' not found"
+        )));
+    };
+    if kind == "undo" {
+        return Err(CliError::Usage(format!(
+            "review batch '{batch_id}' is an undo batch and cannot be undone"
+        )));
+    }
+    if let Some(undo_id) = undone_by {
+        return Err(CliError::Usage(format!(
+            "review batch '{batch_id}' was already undone by '{undo_id}'"
+        )));
+    }
+
+    let mut stmt = tx.prepare(
+        r#"
+        SELECT h.id, h.candidate_id, h.old_status, h.new_status,
+               (SELECT MAX(id) FROM source_review_history WHERE candidate_id = h.candidate_id),
+               EXISTS(SELECT 1 FROM source_registry r WHERE r.candidate_id = h.candidate_id)
+        FROM source_review_history h
+        WHERE h.batch_id = ?1
+        ORDER BY h.id ASC
+        "#,
+    )?;
+    let entries = stmt
+        .query_map(params![batch_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, bool>(5)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    // The batch is restored all-or-nothing so a partial undo can't mix decisions.
+    let conflicts: Vec<String> = entries
+        .iter()
+        .filter_map(
+            |(history_id, candidate_id, _, new_status, latest_id, in_registry)| {
+                if latest_id != history_id {
+                    Some(format!("{candidate_id} (reviewed again)"))
+                } else if new_status == "approved" && *in_registry {
+                    Some(format!("{candidate_id} (already applied to registry)"))
+                } else {
+                    None
+                }
+            },
+        )
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(CliError::Usage(format!(
+            "cannot undo review batch '{batch_id}': {}",
+            conflicts.join(", ")
+        )));
+    }
+
+    let undo_batch_id = next_operation_id("review-undo", entries.len() as i64);
+    let note = format!("undo of {batch_id}");
+    let mut restored = Vec::new();
+    for (_, candidate_id, old_status, _, _, _) in &entries {
+        let Some(review) = load_candidate_review_state(&tx, *candidate_id)? else {
+            continue;
+        };
+        tx.execute(
+            "UPDATE source_candidate SET status = ?1 WHERE id = ?2",
+            params![old_status, candidate_id],
+        )?;
+        record_source_review(
+            &tx,
+            &review,
+            old_status,
+            &reviewer,
+            &note,
+            Some(&undo_batch_id),
+        )?;
+        restored.push(json!({
+            "candidate_id": candidate_id,
+            "from_status": review.status,
+            "restored_status": old_status
+        }));
+    }
+    tx.execute(
+        r#"
+        INSERT INTO source_review_batch (batch_id, kind, target_status, reviewer, note, matched, updated, ts)
+        VALUES (?1, 'undo', 'restored', ?2, ?3, ?4, ?5, ?6)
+        "#,
+        params![
+            undo_batch_id,
+            reviewer,
+            note,
+            entries.len() as i64,
+            restored.len() as i64,
+            unix_ts()
+        ],
+    )?;
+    tx.execute(
+        "UPDATE source_review_batch SET undone_by_batch = ?1 WHERE batch_id = ?2",
+        params![undo_batch_id, batch_id],
+    )?;
+    tx.commit()?;
+
+    let payload = json!({
+        "batch_id": batch_id,
+        "undo_batch_id": undo_batch_id,
+        "reviewer": reviewer,
+        "restored": restored
+    });
+    print_payload(args.json, payload, "Source review batch undone.")
+}
+
+#[derive(Debug, Clone)]
+struct AutoReviewDecision {
+    candidate_id: i64,
+    software_name: String,
+    url: String,
+    domain: String,
+    confidence: i64,
+    decision: &'static str,
+    rule: String,
+    detail: String,
+}
+
+#[derive(Debug, Clone)]
+struct AutoReviewRun {
+    run_id: String,
+    applied: bool,
+    approved: usize,
+    rejected: usize,
+    pending: usize,
+    decisions: Vec<AutoReviewDecision>,
+}
+
+fn domain_in_list(domain: &str, list: &[String]) -> bool {
+    let domain = domain.trim().to_lowercase();
+    list.iter().any(|entry| {
+        let entry = entry.trim().to_lowercase();
+        match entry.strip_prefix("*.") {
+            Some(parent) => domain == parent || domain.ends_with(&format!(".{parent}")),
+            None => domain == entry,
+        }
+    })
+}
+
+/// Host of a stored source URL, falling back to its registrable domain.
+fn source_host(url: &str, domain: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_end_matches('.').to_lowercase()))
+        .unwrap_or_else(|| domain.to_lowercase())
+}
+
+/// Trust is granted per host, so `dl.google.com` does not vouch for `sites.google.com`.
+fn source_trusted(url: &str, domain: &str, trusted: &[String]) -> bool {
+    domain_in_list(&source_host(url, domain), trusted)
+}
+
+/// Distrust covers the host and the whole registrable domain it belongs to.
+fn source_untrusted(url: &str, domain: &str, untrusted: &[String]) -> bool {
+    domain_in_list(domain, untrusted) || domain_in_list(&source_host(url, domain), untrusted)
+}
+
+/// True when the domain's registrable label names the publisher (e.g. JetBrains -> jetbrains.com).
+/// The label must equal one publisher word or all of them joined (`foolabs` / `foo-labs` for
+/// Foo Labs); a label that merely contains the name, like `evilmicrosoft`, does not match.
+fn publisher_matches_domain(publisher: &str, domain: &str) -> bool {
+    let key = publisher_key(publisher);
+    let tokens: Vec<&str> = key.split_whitespace().collect();
+    if tokens.is_empty() {
+        return false;
+    }
+    let host = domain.trim().trim_end_matches('.').to_lowercase();
+    let Some(registrable) = registrable_domain(&host) else {
+        return false;
+    };
+    let label = registrable.split('.').next().unwrap_or_default();
+    label.len() >= 3
+        && (tokens.contains(&label)
+            || label == tokens.concat()
+            || label.split('-').eq(tokens.iter().copied()))
+}
+
+fn compile_deny_patterns(config: &AppConfig) -> Result<Vec<Regex>, CliError> {
+    config
+        .auto_review
+        .deny_patterns
+        .iter()
+        .map(|p| {
+            RegexBuilder::new(p)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| {
+                    CliError::Config(format!(
+                        "invalid auto_review.deny_patterns entry '{p}': {e}"
+                    ))
+                })
+        })
+        .collect()
+}
+
+fn decide_auto_review(
+    config: &AppConfig,
+    deny: &[Regex],
+    review: &CandidateReviewState,
+    publisher: &str,
+) -> (&'static str, String, String) {
+    let policy = &config.auto_review;
+    if source_untrusted(
+        &review.url,
+        &review.domain,
+        &config.download_source_policy.untrusted_domains,
+    ) {
+        return (
+            "rejected",
+            "untrusted_domain".to_string(),
+            format!(
+                "{} is untrusted by the download source policy",
+                review.domain
+            ),
+        );
+    }
+    if let Some(pattern) = deny
+        .iter()
+        .find(|re| re.is_match(&review.url) || re.is_match(&review.software_name))
+    {
+        return (
+            "rejected",
+            format!("deny_pattern:{}", pattern.as_str()),
+            "url or software name matched a deny pattern".to_string(),
+        );
+    }
+    if let Some(floor) = policy.reject_below_confidence {
+        if review.confidence < floor {
+            return (
+                "rejected",
+                "reject_below_confidence".to_string(),
+                format!("confidence {} is below {floor}", review.confidence),
+            );
+        }
+    }
+    if !source_trusted(&review.url, &review.domain, &policy.trusted_domains) {
+        return (
+            "pending",
+            "domain_not_trusted".to_string(),
+            format!(
+                "{} is not in auto_review.trusted_domains",
+                source_host(&review.url, &review.domain)
+            ),
+        );
+    }
+    if review.confidence < policy.min_confidence {
+        return (
+            "pending",
+            "below_min_confidence".to_string(),
+            format!(
+                "confidence {} is below {}",
+                review.confidence, policy.min_confidence
+            ),
+        );
+    }
+    if policy.require_publisher_match && !publisher_matches_domain(publisher, &review.domain) {
+        return (
+            "pending",
+            "publisher_mismatch".to_string(),
+            format!("publisher '{publisher}' does not match {}", review.domain),
+        );
+    }
+    if !is_auto_approvable(review.risk_level(), review.criticality.as_deref()) {
+        return (
+            "pending",
+            "risk_requires_manual_review".to_string(),
+            format!(
+                "risk {} for {} software",
+                review.risk_level(),
+                review.criticality.as_deref().unwrap_or("unclassified")
+            ),
+        );
+    }
+    (
+        "approved",
+        "trusted_domain".to_string(),
+        format!("{} is trusted and all policy checks passed", review.domain),
+    )
+}
+
+/// Evaluates pending candidates against the auto-review policy, recording decisions when applying.
+fn run_auto_review(
+    conn: &mut Connection,
+    config: &AppConfig,
+    apply: bool,
+    limit: Option<i64>,
+) -> Result<AutoReviewRun, CliError> {
+    let deny = compile_deny_patterns(config)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT c.id, COALESCE(i.publisher_canonical, i.publisher, '')
+        FROM source_candidate c
+        LEFT JOIN software_inventory i ON i.id = c.software_id
+        WHERE c.status = 'pending'
+        ORDER BY c.id ASC
+        LIMIT ?1
+        "#,
+    )?;
+    let pending = stmt
+        .query_map(params![limit.unwrap_or(-1)], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    let mut evaluated = Vec::with_capacity(pending.len());
+    for (candidate_id, publisher) in pending {
+        let Some(review) = load_candidate_review_state(conn, candidate_id)? else {
+            continue;
+        };
+        let (decision, rule, detail) = decide_auto_review(config, &deny, &review, &publisher);
+        let decision = AutoReviewDecision {
+            candidate_id,
+            software_name: review.software_name.clone(),
+            url: review.url.clone(),
+            domain: review.domain.clone(),
+            confidence: review.confidence,
+            decision,
+            rule,
+            detail,
+        };
+        evaluated.push((review, decision));
+    }
+
+    let run_id = next_operation_id("review-auto", evaluated.len() as i64);
+    let count = |d: &str| evaluated.iter().filter(|(_, e)| e.decision == d).count();
+    let (approved, rejected, pending) = (count("approved"), count("rejected"), count("pending"));
+
+    if apply {
+        let ts = unix_ts();
+        let tx = conn.transaction()?;
+        for (review, decision) in &evaluated {
+            tx.execute(
+                r#"
+                INSERT INTO source_auto_review_decision (run_id, candidate_id, decision, rule, detail, ts)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                params![run_id, decision.candidate_id, decision.decision, decision.rule, decision.detail, ts],
+            )?;
+            if decision.decision == "pending" {
+                continue;
+            }
+            tx.execute(
+                "UPDATE source_candidate SET status = ?1 WHERE id = ?2",
+                params![decision.decision, decision.candidate_id],
+            )?;
+            record_source_review(
+                &tx,
+                review,
+                decision.decision,
+                "auto-review",
+                &format!("rule: {}", decision.rule),
+                Some(&run_id),
+            )?;
+        }
+        tx.execute(
+            r#"
+            INSERT INTO source_review_batch (batch_id, kind, target_status, reviewer, note, matched, updated, ts)
+            VALUES (?1, 'auto', 'policy', 'auto-review', '', ?2, ?3, ?4)
+            "#,
+            params![run_id, evaluated.len() as i64, (approved + rejected) as i64, ts],
+        )?;
+        tx.commit()?;
+    }
+
+    Ok(AutoReviewRun {
+        run_id,
+        applied: apply,
+        approved,
+        rejected,
+        pending,
+        decisions: evaluated.into_iter().map(|(_, d)| d).collect(),
+    })
+}
+
+fn source_auto_review(args: SourceAutoReviewArgs) -> Result<(), CliError> {
+    if args.dry_run == args.apply {
+        return Err(CliError::Usage(
+            "exactly one of --dry-run or --apply must be set".to_string(),
+        ));
+    }
+    let limit = args.limit.map(i64::from);
+    if limit == Some(0) {
+        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+    }
+    let config = load_config()?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let run = run_auto_review(&mut conn, &config, args.apply, limit)?;
+
+    if args.json {
+        let payload = json!({
+            "run_id": run.run_id,
+            "mode": if run.applied { "apply" } else { "dry_run" },
+            "approved": run.approved,
+            "rejected": run.rejected,
+            "pending": run.pending,
+            "decisions": run
+                .decisions
+                .iter()
+                .map(|d| {
+                    json!({
+                        "candidate_id": d.candidate_id,
+                        "software_name": d.software_name,
+                        "url": d.url,
+                        "domain": d.domain,
+                        "confidence": d.confidence,
+                        "decision": d.decision,
+                        "rule": d.rule,
+                        "detail": d.detail
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        for d in &run.decisions {
+            println!(
+                "{} {} -> {} [{}] {}",
+                d.candidate_id, d.url, d.decision, d.rule, d.detail
+            );
+        }
+        println!(
+            "Auto-review {} ({}): approved={}, rejected={}, pending={}",
+            run.run_id,
+            if run.applied { "applied" } else { "dry-run" },
+            run.approved,
+            run.rejected,
+            run.pending
+        );
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct CalibrationStat {
+    scope: &'static str,
+    key: String,
+    approved: i64,
+    rejected: i64,
+}
+
+impl CalibrationStat {
+    fn total(&self) -> i64 {
+        self.approved + self.rejected
+    }
+
+    /// Laplace-smoothed approval rate, so a single decision never reads as 0% or 100%.
+    fn approval_rate(&self) -> f64 {
+        (self.approved as f64 + 1.0) / (self.total() as f64 + 2.0)
+    }
+
+    /// Domain outcomes already reach the score through `prior_approvals` / `rejection_history`,
+    /// so only rules get an adjustment; domain stats are reported for reference.
+    fn adjustment(&self, config: &CalibrationConfig) -> i64 {
+        if self.scope != "rule" || self.total() < i64::from(config.min_samples) {
+            return 0;
+        }
+        let max = i64::from(config.max_adjustment);
+        (((self.approval_rate() - 0.5) * 2.0 * max as f64).round() as i64).clamp(-max, max)
+    }
+}
+
+/// Keeps review history rows decided by a person; auto-review, expiry, URL merges and imported
+/// decisions come from policy or another machine, and calibrating on them would feed the rules back.
+const MANUAL_REVIEW_FILTER: &str =
+    "reviewer NOT IN ('auto-review', 'registry-expiry', 'url-canonicalize') AND reviewer NOT LIKE 'import:%'";
+
+/// Aggregates the latest manual review decision of each candidate by domain and by rule.
+fn compute_calibration_stats(conn: &Connection) -> Result<Vec<CalibrationStat>, CliError> {
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        SELECT c.domain, c.rule_id, h.new_status
+        FROM source_review_history h
+        JOIN source_candidate c ON c.id = h.candidate_id
+        WHERE h.id = (
+                SELECT MAX(id) FROM source_review_history
+                WHERE candidate_id = h.candidate_id AND {MANUAL_REVIEW_FILTER}
+            )
+          AND h.new_status IN ('approved', 'rejected')
+        "#
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+    let mut by_key: std::collections::BTreeMap<(&'static str, String), (i64, i64)> =
+        std::collections::BTreeMap::new();
+    for row in rows {
+        let (domain, rule_id, status) = row?;
+        let approved = i64::from(status == "approved");
+        let mut keys = vec![("domain", domain.to_lowercase())];
+        if let Some(rule_id) = rule_id {
+            keys.push(("rule", rule_id));
+        }
+        for key in keys {
+            let entry = by_key.entry(key).or_insert((0, 0));
+            entry.0 += approved;
+            entry.1 += 1 - approved;
+        }
+    }
+    Ok(by_key
+        .into_iter()
+        .map(|((scope, key), (approved, rejected))| CalibrationStat {
+            scope,
+            key,
+            approved,
+            rejected,
+        })
+        .collect())
+}
+
+fn source_calibrate(args: SourceCalibrateArgs) -> Result<(), CliError> {
+    let config = load_config()?;
+    let calibration = &config.calibration;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+
+    let stats = compute_calibration_stats(&conn)?;
+    let now = unix_ts();
+    if !args.dry_run {
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM source_calibration", [])?;
+        // New adjustments change every score, so the next suggest rescans everything.
+        tx.execute("DELETE FROM source_suggest_state", [])?;
+        for stat in &stats {
+            tx.execute(
+                r#"
+                INSERT INTO source_calibration (scope, key, approved, rejected, approval_rate, adjustment, computed_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+                params![
+                    stat.scope,
+                    stat.key,
+                    stat.approved,
+                    stat.rejected,
+                    stat.approval_rate(),
+                    stat.adjustment(calibration),
+                    now
+                ],
+            )?;
+        }
+        tx.commit()?;
+    }
+
+    let to_json = |stat: &CalibrationStat| {
+        json!({
+            "key": stat.key,
+            "approved": stat.approved,
+            "rejected": stat.rejected,
+            "approval_rate": (stat.approval_rate() * 1000.0).round() / 1000.0,
+            "adjustment": stat.adjustment(calibration)
+        })
+    };
+    let scoped = |scope: &str| -> Vec<serde_json::Value> {
+        stats
+            .iter()
+            .filter(|s| s.scope == scope)
+            .map(to_json)
+            .collect()
+    };
+    let rejected_rules: Vec<serde_json::Value> = stats
+        .iter()
+        .filter(|s| {
+            s.scope == "rule"
+                && s.total() >= i64::from(calibration.min_samples)
+                && s.approval_rate() <= calibration.rejected_rule_threshold
+        })
+        .map(to_json)
+        .collect();
+
+    let payload = json!({
+        "mode": if args.dry_run { "dry_run" } else { "applied" },
+        "computed_at": now,
+        "max_adjustment": calibration.max_adjustment,
+        "min_samples": calibration.min_samples,
+        "domains": scoped("domain"),
+        "rules": scoped("rule"),
+        "consistently_rejected_rules": rejected_rules
+    });
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        for stat in &stats {
+            println!(
+                "{} {} approved={} rejected={} adjustment={:+}",
+                stat.scope,
+                stat.key,
+                stat.approved,
+                stat.rejected,
+                stat.adjustment(calibration)
+            );
+        }
+        for rule in &rejected_rules {
+            println!(
+                "consistently rejected rule: {}",
+                rule["key"].as_str().unwrap_or_default()
+            );
+        }
+        println!(
+            "Source calibration {}: {} domains, {} rules.",
+            if args.dry_run { "previewed" } else { "applied" },
+            payload["domains"].as_array().map(Vec::len).unwrap_or(0),
+            payload["rules"].as_array().map(Vec::len).unwrap_or(0)
+        );
+    }
+    Ok(())
+}
+
 fn source_list(args: SourceListArgs) -> Result<(), CliError> {
     let limit = i64::from(args.limit.unwrap_or(100));
     let offset = i64::from(args.offset.unwrap_or(0));
@@ -1413,7 +7805,8 @@ fn source_list(args: SourceListArgs) -> Result<(), CliError> {
 
     let mut sql = String::from(
         r#"
-        SELECT id, software_id, software_name, url, domain, confidence, reason, status, created_at
+        SELECT id, software_id, software_name, url, domain, confidence, reason, status, created_at, rule_id,
+               score_breakdown
         FROM source_candidate
         "#,
     );
@@ -1454,7 +7847,9 @@ fn source_list(args: SourceListArgs) -> Result<(), CliError> {
             "confidence": row.get::<_, i64>(5)?,
             "reason": row.get::<_, String>(6)?,
             "status": row.get::<_, String>(7)?,
-            "created_at": row.get::<_, i64>(8)?
+            "created_at": row.get::<_, i64>(8)?,
+            "rule_id": row.get::<_, Option<String>>(9)?,
+            "score_breakdown": parse_score_breakdown(row.get::<_, Option<String>>(10)?.as_deref())
         }))
     })?;
 
@@ -1471,9 +7866,12 @@ fn source_apply_approved(args: SourceApplyApprovedArgs) -> Result<(), CliError>
         return Err(CliError::Usage("--limit must be >= 1".to_string()));
     }
 
+    let review_interval = load_config()?.source_registry.review_interval_secs()?;
+
     let db_file = db_path()?;
     init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    expire_registry_sources(&mut conn, unix_ts())?;
 
     let mut sql = String::from(
         r#"
@@ -1523,12 +7921,19 @@ fn source_apply_approved(args: SourceApplyApprovedArgs) -> Result<(), CliError>
             params![candidate_id],
             |r| r.get::<_, i64>(0).map(|v| v == 1),
         )?;
+        // Expiry runs from the latest recorded approval, so re-applying never extends it on its own.
+        let approved_at: Option<i64> = conn.query_row(
+            "SELECT MAX(ts) FROM source_review_history WHERE candidate_id = ?1 AND new_status = 'approved'",
+            params![candidate_id],
+            |r| r.get(0),
+        )?;
+        let expires_at = approved_at.unwrap_or(applied_at) + review_interval;
 
         conn.execute(
             r#"
             INSERT INTO source_registry
-            (candidate_id, software_id, software_name, url, domain, confidence, reason, status, applied_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'active', ?8)
+            (candidate_id, software_id, software_name, url, domain, confidence, reason, status, applied_at, expires_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'active', ?8, ?9)
             ON CONFLICT(candidate_id) DO UPDATE SET
                 software_id=excluded.software_id,
                 software_name=excluded.software_name,
@@ -1537,7 +7942,9 @@ fn source_apply_approved(args: SourceApplyApprovedArgs) -> Result<(), CliError>
                 confidence=excluded.confidence,
                 reason=excluded.reason,
                 status='active',
-                applied_at=excluded.applied_at
+                applied_at=excluded.applied_at,
+                expires_at=CASE WHEN ?10 THEN excluded.expires_at
+                                ELSE COALESCE(source_registry.expires_at, excluded.expires_at) END
             "#,
             params![
                 candidate_id,
@@ -1547,7 +7954,9 @@ fn source_apply_approved(args: SourceApplyApprovedArgs) -> Result<(), CliError>
                 domain,
                 confidence,
                 reason,
-                applied_at
+                applied_at,
+                expires_at,
+                approved_at.is_some()
             ],
         )?;
 
@@ -1562,88 +7971,738 @@ fn source_apply_approved(args: SourceApplyApprovedArgs) -> Result<(), CliError>
             "software_id": software_id,
             "software_name": software_name,
             "url": url,
-            "domain": domain,
-            "confidence": confidence,
-            "status": "active"
+            "domain": domain,
+            "confidence": confidence,
+            "status": "active",
+            "expires_at": expires_at
+        }));
+    }
+
+    let payload = json!({
+        "matched": items.len(),
+        "inserted": inserted,
+        "updated": updated,
+        "applied_at": applied_at,
+        "items": items
+    });
+    print_payload(args.json, payload, "Approved sources applied.")
+}
+
+fn source_registry_list(args: SourceRegistryListArgs) -> Result<(), CliError> {
+    let limit = i64::from(args.limit.unwrap_or(100));
+    let offset = i64::from(args.offset.unwrap_or(0));
+    if limit <= 0 {
+        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+    }
+    if let Some(status) = args.status.as_deref() {
+        validate_registry_status(status)?;
+    }
+    if let Some(health) = args.health.as_deref() {
+        validate_registry_health(health)?;
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    expire_registry_sources(&mut conn, unix_ts())?;
+
+    let mut sql = String::from(
+        r#"
+        SELECT id, candidate_id, software_id, software_name, url, domain, confidence, reason, status, applied_at,
+               health, last_probed_at, expires_at, imported_from
+        FROM source_registry
+        "#,
+    );
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(status) = args.status.clone() {
+        clauses.push("status = ?".to_string());
+        values.push(Value::Text(status));
+    }
+    if let Some(health) = args.health.clone() {
+        clauses.push("health = ?".to_string());
+        values.push(Value::Text(health));
+    }
+    if let Some(domain) = args.domain.clone() {
+        clauses.push("domain = ?".to_string());
+        values.push(Value::Text(domain));
+    }
+    if let Some(contains) = args.contains.clone() {
+        clauses.push("(software_name LIKE ? OR url LIKE ? OR reason LIKE ?)".to_string());
+        let like = format!("%{contains}%");
+        values.push(Value::Text(like.clone()));
+        values.push(Value::Text(like.clone()));
+        values.push(Value::Text(like));
+    }
+    if !clauses.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&clauses.join(" AND "));
+    }
+    sql.push_str(" ORDER BY id DESC LIMIT ? OFFSET ?");
+    values.push(Value::Integer(limit));
+    values.push(Value::Integer(offset));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+        Ok(json!({
+            "registry_id": row.get::<_, i64>(0)?,
+            "candidate_id": row.get::<_, i64>(1)?,
+            "software_id": row.get::<_, i64>(2)?,
+            "software_name": row.get::<_, String>(3)?,
+            "url": row.get::<_, String>(4)?,
+            "domain": row.get::<_, String>(5)?,
+            "confidence": row.get::<_, i64>(6)?,
+            "reason": row.get::<_, String>(7)?,
+            "status": row.get::<_, String>(8)?,
+            "applied_at": row.get::<_, i64>(9)?,
+            "health": row.get::<_, String>(10)?,
+            "last_probed_at": row.get::<_, Option<i64>>(11)?,
+            "expires_at": row.get::<_, Option<i64>>(12)?,
+            "imported_from": row.get::<_, Option<String>>(13)?
+        }))
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(args.json, json!([]), "No registry sources found.");
+    }
+    print_payload(args.json, json!(payload), "Registry sources listed.")
+}
+
+/// Moves active registry entries past `expires_at` into `review_required` and returns their
+/// candidates to `pending`, so they are unusable until a reviewer approves them again. Disabled
+/// entries keep their status; they need an explicit enable, which re-checks expiry.
+fn expire_registry_sources(conn: &mut Connection, now: i64) -> Result<Vec<i64>, CliError> {
+    let review_interval = load_config()?.source_registry.review_interval_secs()?;
+    let tx = conn.transaction()?;
+    let expired = expire_registry_sources_in(&tx, now, review_interval)?;
+    tx.commit()?;
+    Ok(expired)
+}
+
+/// Expiry pass inside the caller's transaction, so a dry run can roll it back.
+fn expire_registry_sources_in(
+    tx: &Connection,
+    now: i64,
+    review_interval: i64,
+) -> Result<Vec<i64>, CliError> {
+    tx.execute(
+        "UPDATE source_registry SET expires_at = applied_at + ?1 WHERE expires_at IS NULL",
+        params![review_interval],
+    )?;
+    let expired = {
+        let mut stmt = tx.prepare(
+            "SELECT candidate_id FROM source_registry WHERE status = 'active' AND expires_at <= ?1 ORDER BY id ASC",
+        )?;
+        let ids = stmt
+            .query_map(params![now], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        ids
+    };
+    for candidate_id in &expired {
+        tx.execute(
+            "UPDATE source_registry SET status = 'review_required' WHERE candidate_id = ?1 AND status = 'active'",
+            params![candidate_id],
+        )?;
+        let Some(review) = load_candidate_review_state(tx, *candidate_id)? else {
+            continue;
+        };
+        if review.status == "pending" {
+            continue;
+        }
+        tx.execute(
+            "UPDATE source_candidate SET status = 'pending' WHERE id = ?1",
+            params![candidate_id],
+        )?;
+        record_source_review(
+            tx,
+            &review,
+            "pending",
+            "registry-expiry",
+            "registry approval expired",
+            None,
+        )?;
+    }
+    Ok(expired)
+}
+
+fn source_registry_expiring(args: SourceRegistryExpiringArgs) -> Result<(), CliError> {
+    let within_days = i64::from(args.within.unwrap_or(30));
+    let now = unix_ts();
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    expire_registry_sources(&mut conn, now)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT candidate_id, software_id, software_name, url, domain, status, expires_at
+        FROM source_registry
+        WHERE expires_at <= ?1
+        ORDER BY expires_at ASC, candidate_id ASC
+        "#,
+    )?;
+    let rows = stmt.query_map(params![now + within_days * 86_400], |row| {
+        let expires_at = row.get::<_, i64>(6)?;
+        Ok(json!({
+            "candidate_id": row.get::<_, i64>(0)?,
+            "software_id": row.get::<_, i64>(1)?,
+            "software_name": row.get::<_, String>(2)?,
+            "url": row.get::<_, String>(3)?,
+            "domain": row.get::<_, String>(4)?,
+            "status": row.get::<_, String>(5)?,
+            "expires_at": expires_at,
+            "expires_in_days": (expires_at - now).div_euclid(86_400)
+        }))
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(
+            args.json,
+            json!([]),
+            &format!("No registry sources expire within {within_days} days."),
+        );
+    }
+    print_payload(
+        args.json,
+        json!(payload),
+        "Expiring registry sources listed.",
+    )
+}
+
+const REGISTRY_EXPORT_FORMAT: &str = "synora-source-registry";
+const REGISTRY_EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegistryExportDocument {
+    format: String,
+    version: u32,
+    #[serde(default)]
+    exported_at: i64,
+    #[serde(default)]
+    exported_by: String,
+    #[serde(default)]
+    entries: Vec<RegistryExportEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegistryExportEntry {
+    software: RegistryExportSoftware,
+    url: String,
+    #[serde(default)]
+    domain: String,
+    confidence: i64,
+    #[serde(default)]
+    reason: String,
+    #[serde(default)]
+    rule_id: Option<String>,
+    #[serde(default)]
+    approval: RegistryExportApproval,
+    #[serde(default)]
+    expires_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegistryExportSoftware {
+    name: String,
+    #[serde(default)]
+    publisher: String,
+    #[serde(default)]
+    publisher_canonical: Option<String>,
+    #[serde(default)]
+    fingerprint: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RegistryExportApproval {
+    #[serde(default)]
+    approved_by: String,
+    #[serde(default)]
+    approved_at: Option<i64>,
+    #[serde(default)]
+    note: String,
+    #[serde(default)]
+    applied_at: i64,
+}
+
+fn source_registry_export(args: SourceRegistryExportArgs) -> Result<(), CliError> {
+    let format = args.format.as_deref().unwrap_or("yaml").trim();
+    if !matches!(format, "yaml" | "json") {
+        return Err(CliError::Usage(
+            "--format must be one of: yaml, json".to_string(),
+        ));
+    }
+    let status = args.status.as_deref().unwrap_or("active");
+    validate_registry_status(status)?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let now = unix_ts();
+    expire_registry_sources(&mut conn, now)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT COALESCE(i.name, r.software_name), COALESCE(i.publisher, ''), i.publisher_canonical, i.fingerprint,
+               r.url, r.domain, r.confidence, r.reason, c.rule_id, r.applied_at, r.expires_at,
+               h.reviewer, h.ts, h.note
+        FROM source_registry r
+        LEFT JOIN software_inventory i ON i.id = r.software_id
+        LEFT JOIN source_candidate c ON c.id = r.candidate_id
+        LEFT JOIN source_review_history h ON h.id = (
+            SELECT MAX(id) FROM source_review_history
+            WHERE candidate_id = r.candidate_id AND new_status = 'approved'
+        )
+        WHERE r.status = ?1
+        ORDER BY r.software_name ASC, r.url ASC
+        "#,
+    )?;
+    let entries = stmt
+        .query_map(params![status], |row| {
+            Ok(RegistryExportEntry {
+                software: RegistryExportSoftware {
+                    name: row.get(0)?,
+                    publisher: row.get(1)?,
+                    publisher_canonical: row.get(2)?,
+                    fingerprint: row.get(3)?,
+                },
+                url: row.get(4)?,
+                domain: row.get(5)?,
+                confidence: row.get(6)?,
+                reason: row.get(7)?,
+                rule_id: row.get(8)?,
+                expires_at: row.get(10)?,
+                approval: RegistryExportApproval {
+                    approved_by: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
+                    approved_at: row.get(12)?,
+                    note: row.get::<_, Option<String>>(13)?.unwrap_or_default(),
+                    applied_at: row.get(9)?,
+                },
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let document = RegistryExportDocument {
+        format: REGISTRY_EXPORT_FORMAT.to_string(),
+        version: REGISTRY_EXPORT_VERSION,
+        exported_at: now,
+        exported_by: current_os_user(),
+        entries,
+    };
+    let rendered = match format {
+        "json" => serde_json::to_string_pretty(&document)?,
+        _ => serde_yaml::to_string(&document)
+            .map_err(|e| CliError::Integration(format!("failed to render registry yaml: {e}")))?,
+    };
+
+    match args.output {
+        Some(path) => {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, rendered)?;
+            println!(
+                "Exported {} registry sources as {format} to {}.",
+                document.entries.len(),
+                path.display()
+            );
+        }
+        None => println!("{}", rendered.trim_end()),
+    }
+    Ok(())
+}
+
+struct ImportSoftware {
+    id: i64,
+    name: String,
+    canonical_publisher: String,
+    fingerprint: String,
+    criticality: Option<String>,
+}
+
+/// Resolves an exported entry to local software: by fingerprint first, then by canonical name.
+fn match_import_software<'a>(
+    entry: &RegistryExportSoftware,
+    local: &'a [ImportSoftware],
+    aliases: &HashMap<String, String>,
+) -> Result<(&'a ImportSoftware, &'static str), String> {
+    if let Some(fingerprint) = entry.fingerprint.as_deref().filter(|f| !f.is_empty()) {
+        if let Some(found) = local.iter().find(|s| s.fingerprint == fingerprint) {
+            return Ok((found, "fingerprint"));
+        }
+    }
+    let publisher = entry
+        .publisher_canonical
+        .as_deref()
+        .unwrap_or(&entry.publisher);
+    let key = make_identity_key(
+        &entry.name,
+        &canonical_publisher(publisher, aliases),
+        None,
+        None,
+    );
+    let matches: Vec<&ImportSoftware> = local
+        .iter()
+        .filter(|s| make_identity_key(&s.name, &s.canonical_publisher, None, None) == key)
+        .collect();
+    match matches.as_slice() {
+        [only] => Ok((only, "canonical_name")),
+        [] => Err(format!("no local software matches '{}'", entry.name)),
+        many => Err(format!(
+            "'{}' is ambiguous: {} local software match",
+            entry.name,
+            many.len()
+        )),
+    }
+}
+
+fn source_registry_import(args: SourceRegistryImportArgs) -> Result<(), CliError> {
+    let strategy = args.on_conflict.as_deref().unwrap_or("skip").trim();
+    if !matches!(strategy, "skip" | "overwrite" | "newer") {
+        return Err(CliError::Usage(
+            "--on-conflict must be one of: skip, overwrite, newer".to_string(),
+        ));
+    }
+    let raw = fs::read_to_string(&args.file)?;
+    let document: RegistryExportDocument = serde_yaml::from_str(&raw).map_err(|e| {
+        CliError::Usage(format!(
+            "invalid registry file {}: {e}",
+            args.file.display()
+        ))
+    })?;
+    if document.format != REGISTRY_EXPORT_FORMAT || document.version != REGISTRY_EXPORT_VERSION {
+        return Err(CliError::Usage(format!(
+            "unsupported registry file: expected format '{REGISTRY_EXPORT_FORMAT}' version {REGISTRY_EXPORT_VERSION}"
+        )));
+    }
+    let config = load_config()?;
+    let review_interval = config.source_registry.review_interval_secs()?;
+    let deny = compile_deny_patterns(&config)?;
+    let source_file = args.file.display().to_string();
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    let now = unix_ts();
+
+    let aliases = load_publisher_aliases(&conn)?;
+    let local = {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, name, COALESCE(publisher_canonical, publisher), fingerprint, criticality
+            FROM software_inventory
+            WHERE merged_into IS NULL AND is_active = 1
+            ORDER BY id ASC
+            "#,
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ImportSoftware {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    canonical_publisher: row.get(2)?,
+                    fingerprint: row.get(3)?,
+                    criticality: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    let import_id = next_operation_id("registry-import", document.entries.len() as i64);
+    let reviewer = format!(
+        "import:{}",
+        if document.exported_by.is_empty() {
+            "unknown"
+        } else {
+            &document.exported_by
+        }
+    );
+    // Everything, expiry included, happens in one transaction that a dry run never commits.
+    let tx = conn.transaction()?;
+    expire_registry_sources_in(&tx, now, review_interval)?;
+    let mut results: Vec<serde_json::Value> = Vec::new();
+    for (index, entry) in document.entries.iter().enumerate() {
+        let approved_at = entry
+            .approval
+            .approved_at
+            .unwrap_or(entry.approval.applied_at);
+        let mut software_id: Option<i64> = None;
+        let mut candidate_id: Option<i64> = None;
+        let mut match_method = "";
+        let mut url = entry.url.clone();
+
+        let (action, detail) = 'entry: {
+            let canonical = match canonicalize_source_url(&entry.url, &config.source_url_policy) {
+                Ok(c) => c,
+                Err(e) => break 'entry ("invalid", e),
+            };
+            url = canonical.url.clone();
+            if !(0..=100).contains(&entry.confidence) {
+                break 'entry (
+                    "invalid",
+                    format!("confidence {} is outside 0..100", entry.confidence),
+                );
+            }
+            // The local review policy applies to imported approvals as it does to `source review`.
+            if source_untrusted(
+                &canonical.url,
+                &canonical.domain,
+                &config.download_source_policy.untrusted_domains,
+            ) {
+                break 'entry (
+                    "blocked",
+                    format!(
+                        "{} is untrusted by the download source policy",
+                        canonical.domain
+                    ),
+                );
+            }
+            if let Some(pattern) = deny
+                .iter()
+                .find(|re| re.is_match(&canonical.url) || re.is_match(&entry.software.name))
+            {
+                break 'entry (
+                    "blocked",
+                    format!(
+                        "url or software name matched deny pattern {}",
+                        pattern.as_str()
+                    ),
+                );
+            }
+            let (software, method) = match match_import_software(&entry.software, &local, &aliases)
+            {
+                Ok(found) => found,
+                Err(e) => break 'entry ("unmatched", e),
+            };
+            software_id = Some(software.id);
+            match_method = method;
+            let review = CandidateReviewState {
+                candidate_id: 0,
+                software_id: software.id,
+                software_name: software.name.clone(),
+                url: canonical.url.clone(),
+                domain: canonical.domain.clone(),
+                status: "pending".to_string(),
+                confidence: entry.confidence,
+                criticality: software.criticality.clone(),
+            };
+            if let Some(why) = review.note_required_reason() {
+                if entry.approval.note.trim().is_empty() {
+                    break 'entry (
+                        "blocked",
+                        format!("approval carries no note, which is required: {why}"),
+                    );
+                }
+            }
+
+            let local_candidate: Option<(i64, String)> = tx
+                .query_row(
+                    "SELECT id, status FROM source_candidate WHERE software_id = ?1 AND url = ?2",
+                    params![software.id, canonical.url],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            if let Some((id, status)) = local_candidate.filter(|(_, status)| status == "rejected") {
+                candidate_id = Some(id);
+                if !args.allow_rejected {
+                    break 'entry (
+                        "skipped",
+                        format!(
+                            "candidate is {status} locally; pass --allow-rejected to re-approve it"
+                        ),
+                    );
+                }
+            }
+
+            let existing: Option<(i64, i64, String)> = tx
+                .query_row(
+                    r#"
+                    SELECT r.candidate_id,
+                           COALESCE((SELECT MAX(ts) FROM source_review_history h
+                                     WHERE h.candidate_id = r.candidate_id AND h.new_status = 'approved'), r.applied_at),
+                           r.status
+                    FROM source_registry r
+                    WHERE r.software_id = ?1 AND r.url = ?2
+                    "#,
+                    params![software.id, canonical.url],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()?;
+            if let Some(&(existing_candidate, local_approved_at, _)) = existing.as_ref() {
+                candidate_id = Some(existing_candidate);
+                match strategy {
+                    "skip" => break 'entry ("skipped", "already in registry".to_string()),
+                    "newer" if approved_at <= local_approved_at => {
+                        break 'entry ("skipped", "local approval is newer or equal".to_string())
+                    }
+                    _ => {}
+                }
+            }
+
+            tx.execute(
+                r#"
+                INSERT INTO source_candidate
+                (software_id, software_name, url, domain, confidence, reason, status, created_at, rule_id)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending', ?7, ?8)
+                ON CONFLICT(software_id, url) DO UPDATE SET
+                    domain=excluded.domain,
+                    confidence=excluded.confidence,
+                    reason=excluded.reason
+                "#,
+                params![
+                    software.id,
+                    software.name,
+                    canonical.url,
+                    canonical.domain,
+                    entry.confidence,
+                    entry.reason,
+                    now,
+                    entry.rule_id.as_deref().unwrap_or("import")
+                ],
+            )?;
+            let id: i64 = tx.query_row(
+                "SELECT id FROM source_candidate WHERE software_id = ?1 AND url = ?2",
+                params![software.id, canonical.url],
+                |row| row.get(0),
+            )?;
+            candidate_id = Some(id);
+            if let Some(review) = load_candidate_review_state(&tx, id)? {
+                tx.execute(
+                    "UPDATE source_candidate SET status = 'approved' WHERE id = ?1",
+                    params![id],
+                )?;
+                let note = format!(
+                    "imported from {source_file}; approved by {} at {approved_at}",
+                    if entry.approval.approved_by.is_empty() {
+                        "unknown"
+                    } else {
+                        &entry.approval.approved_by
+                    }
+                );
+                record_source_review(&tx, &review, "approved", &reviewer, &note, Some(&import_id))?;
+            }
+            let expires_at = entry
+                .expires_at
+                .map_or(now + review_interval, |e| e.min(now + review_interval));
+            // The import is a fresh approval, so an entry waiting for re-review becomes active
+            // again; a locally disabled entry stays disabled until someone re-enables it.
+            tx.execute(
+                r#"
+                INSERT INTO source_registry
+                (candidate_id, software_id, software_name, url, domain, confidence, reason, status, applied_at, expires_at, imported_from)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'active', ?8, ?9, ?10)
+                ON CONFLICT(candidate_id) DO UPDATE SET
+                    status=CASE WHEN status = 'review_required' THEN 'active' ELSE status END,
+                    domain=excluded.domain,
+                    confidence=excluded.confidence,
+                    reason=excluded.reason,
+                    applied_at=excluded.applied_at,
+                    expires_at=excluded.expires_at,
+                    imported_from=excluded.imported_from
+                "#,
+                params![
+                    id,
+                    software.id,
+                    software.name,
+                    canonical.url,
+                    canonical.domain,
+                    entry.confidence,
+                    entry.reason,
+                    now,
+                    expires_at,
+                    import_id
+                ],
+            )?;
+            match existing.as_ref().map(|(_, _, status)| status.as_str()) {
+                Some("disabled") => (
+                    "overwritten",
+                    format!("replaced local entry ({strategy}); kept its disabled status"),
+                ),
+                Some(_) => ("overwritten", format!("replaced local entry ({strategy})")),
+                None => ("inserted", format!("matched by {match_method}")),
+            }
+        };
+
+        tx.execute(
+            r#"
+            INSERT INTO source_registry_import
+            (import_id, entry_index, software_id, candidate_id, url, action, match_method, detail,
+             source_file, exported_by, exported_at, original_approved_by, original_approved_at, ts)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            "#,
+            params![
+                import_id,
+                index as i64,
+                software_id,
+                candidate_id,
+                url,
+                action,
+                match_method,
+                detail,
+                source_file,
+                document.exported_by,
+                document.exported_at,
+                entry.approval.approved_by,
+                approved_at,
+                now
+            ],
+        )?;
+        results.push(json!({
+            "index": index,
+            "software_name": entry.software.name,
+            "url": url,
+            "software_id": software_id,
+            "candidate_id": candidate_id,
+            "match_method": if match_method.is_empty() { None } else { Some(match_method) },
+            "action": action,
+            "detail": detail
         }));
     }
+    if !args.dry_run {
+        tx.commit()?;
+    }
 
+    let count = |action: &str| results.iter().filter(|r| r["action"] == action).count();
     let payload = json!({
-        "matched": items.len(),
-        "inserted": inserted,
-        "updated": updated,
-        "applied_at": applied_at,
-        "items": items
+        "import_id": import_id,
+        "dry_run": args.dry_run,
+        "on_conflict": strategy,
+        "source_file": source_file,
+        "inserted": count("inserted"),
+        "overwritten": count("overwritten"),
+        "skipped": count("skipped"),
+        "unmatched": count("unmatched"),
+        "invalid": count("invalid"),
+        "blocked": count("blocked"),
+        "entries": results
     });
-    print_payload(args.json, payload, "Approved sources applied.")
-}
-
-fn source_registry_list(args: SourceRegistryListArgs) -> Result<(), CliError> {
-    let limit = i64::from(args.limit.unwrap_or(100));
-    let offset = i64::from(args.offset.unwrap_or(0));
-    if limit <= 0 {
-        return Err(CliError::Usage("--limit must be >= 1".to_string()));
-    }
-    if let Some(status) = args.status.as_deref() {
-        validate_registry_status(status)?;
-    }
-
-    let db_file = db_path()?;
-    init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
-
-    let mut sql = String::from(
-        r#"
-        SELECT id, candidate_id, software_id, software_name, url, domain, confidence, reason, status, applied_at
-        FROM source_registry
-        "#,
-    );
-    let mut clauses: Vec<String> = Vec::new();
-    let mut values: Vec<Value> = Vec::new();
-
-    if let Some(status) = args.status.clone() {
-        clauses.push("status = ?".to_string());
-        values.push(Value::Text(status));
-    }
-    if let Some(domain) = args.domain.clone() {
-        clauses.push("domain = ?".to_string());
-        values.push(Value::Text(domain));
-    }
-    if let Some(contains) = args.contains.clone() {
-        clauses.push("(software_name LIKE ? OR url LIKE ? OR reason LIKE ?)".to_string());
-        let like = format!("%{contains}%");
-        values.push(Value::Text(like.clone()));
-        values.push(Value::Text(like.clone()));
-        values.push(Value::Text(like));
-    }
-    if !clauses.is_empty() {
-        sql.push_str(" WHERE ");
-        sql.push_str(&clauses.join(" AND "));
-    }
-    sql.push_str(" ORDER BY id DESC LIMIT ? OFFSET ?");
-    values.push(Value::Integer(limit));
-    values.push(Value::Integer(offset));
-
-    let mut stmt = conn.prepare(&sql)?;
-    let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
-        Ok(json!({
-            "registry_id": row.get::<_, i64>(0)?,
-            "candidate_id": row.get::<_, i64>(1)?,
-            "software_id": row.get::<_, i64>(2)?,
-            "software_name": row.get::<_, String>(3)?,
-            "url": row.get::<_, String>(4)?,
-            "domain": row.get::<_, String>(5)?,
-            "confidence": row.get::<_, i64>(6)?,
-            "reason": row.get::<_, String>(7)?,
-            "status": row.get::<_, String>(8)?,
-            "applied_at": row.get::<_, i64>(9)?
-        }))
-    })?;
-    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
-    if payload.is_empty() {
-        return print_payload(args.json, json!([]), "No registry sources found.");
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        for r in payload["entries"].as_array().into_iter().flatten() {
+            println!(
+                "{} {} -> {} ({})",
+                r["software_name"].as_str().unwrap_or_default(),
+                r["url"].as_str().unwrap_or_default(),
+                r["action"].as_str().unwrap_or_default(),
+                r["detail"].as_str().unwrap_or_default()
+            );
+        }
+        println!(
+            "Registry import {} ({}): inserted={}, overwritten={}, skipped={}, unmatched={}, invalid={}, blocked={}",
+            import_id,
+            if args.dry_run { "dry-run" } else { "applied" },
+            payload["inserted"],
+            payload["overwritten"],
+            payload["skipped"],
+            payload["unmatched"],
+            payload["invalid"],
+            payload["blocked"]
+        );
     }
-    print_payload(args.json, json!(payload), "Registry sources listed.")
+    Ok(())
 }
 
 fn source_registry_disable(args: SourceRegistryDisableArgs) -> Result<(), CliError> {
@@ -1728,6 +8787,11 @@ fn source_registry_enable(args: SourceRegistryEnableArgs) -> Result<(), CliError
     }
     if let Some(status) = args.status.as_deref() {
         validate_registry_status(status)?;
+        if status == "review_required" {
+            return Err(CliError::Usage(
+                "review_required sources must be re-approved with `source review --approve` and `source apply-approved`".to_string(),
+            ));
+        }
     }
     if args.candidate_id.is_none() && args.domain.is_none() && args.contains.is_none() && args.status.is_none() {
         return Err(CliError::Usage(
@@ -1737,7 +8801,7 @@ fn source_registry_enable(args: SourceRegistryEnableArgs) -> Result<(), CliError
 
     let db_file = db_path()?;
     init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
+    let mut conn = Connection::open(db_file)?;
 
     let mut sql = String::from("SELECT id, candidate_id FROM source_registry");
     let mut clauses: Vec<String> = Vec::new();
@@ -1787,13 +8851,588 @@ fn source_registry_enable(args: SourceRegistryEnableArgs) -> Result<(), CliError
         )? as i64;
         candidate_ids.push(*candidate_id);
     }
-
-    let payload = json!({
-        "matched": targets.len(),
-        "updated": changed,
-        "candidate_ids": candidate_ids
-    });
-    print_payload(args.json, payload, "Registry sources enabled.")
+    drop(stmt);
+    // A disabled entry may have expired meanwhile; it goes straight to re-review.
+    let expired = expire_registry_sources(&mut conn, unix_ts())?;
+    let review_required = candidate_ids
+        .iter()
+        .filter(|id| expired.contains(id))
+        .count();
+
+    let payload = json!({
+        "matched": targets.len(),
+        "updated": changed,
+        "review_required": review_required,
+        "candidate_ids": candidate_ids
+    });
+    print_payload(args.json, payload, "Registry sources enabled.")
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SourceProbeRequest {
+    #[serde(default)]
+    candidate_id: Option<i64>,
+    #[serde(default)]
+    all: bool,
+    #[serde(default)]
+    limit: Option<i64>,
+    #[serde(default)]
+    disable_broken: bool,
+}
+
+impl SourceProbeRequest {
+    fn validate(&self) -> Result<(), CliError> {
+        if self.candidate_id.is_some() == self.all {
+            return Err(CliError::Usage(
+                "exactly one of --candidate-id or --all must be set".to_string(),
+            ));
+        }
+        if self.limit.is_some_and(|l| l <= 0) {
+            return Err(CliError::Usage("--limit must be >= 1".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ProbeOutcome {
+    method: &'static str,
+    http_status: Option<u16>,
+    final_url: String,
+    redirect_count: u32,
+    tls_valid: Option<bool>,
+    content_type: String,
+    error: String,
+    duration_ms: i64,
+}
+
+#[derive(Debug, Clone)]
+struct SourceProbeResult {
+    registry_id: i64,
+    candidate_id: i64,
+    software_name: String,
+    url: String,
+    outcome: ProbeOutcome,
+    health: &'static str,
+    detail: String,
+    disabled: bool,
+}
+
+impl SourceProbeResult {
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "registry_id": self.registry_id,
+            "candidate_id": self.candidate_id,
+            "software_name": self.software_name,
+            "url": self.url,
+            "method": self.outcome.method,
+            "http_status": self.outcome.http_status,
+            "final_url": self.outcome.final_url,
+            "redirect_count": self.outcome.redirect_count,
+            "tls_valid": self.outcome.tls_valid,
+            "content_type": self.outcome.content_type,
+            "duration_ms": self.outcome.duration_ms,
+            "health": self.health,
+            "detail": self.detail,
+            "disabled": self.disabled
+        })
+    }
+}
+
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut out = err.to_string();
+    let mut source = err.source();
+    while let Some(e) = source {
+        out.push_str(": ");
+        out.push_str(&e.to_string());
+        source = e.source();
+    }
+    out
+}
+
+/// Whether a transport failure came from rustls rather than DNS, TCP or a timeout; ureq wraps
+/// the handshake error in an `io::Error`, whose `source()` skips the wrapped error itself.
+fn is_tls_failure(transport: &ureq::Transport) -> bool {
+    let mut source = std::error::Error::source(transport);
+    while let Some(e) = source {
+        let wrapped = e
+            .downcast_ref::<std::io::Error>()
+            .and_then(std::io::Error::get_ref);
+        if e.is::<rustls::Error>() || wrapped.is_some_and(|inner| inner.is::<rustls::Error>()) {
+            return true;
+        }
+        source = e.source();
+    }
+    false
+}
+
+fn is_internal_host(host: &url::Host<&str>) -> bool {
+    match host {
+        url::Host::Domain(domain) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain == "localhost"
+                || [".localhost", ".local", ".internal", ".lan", ".home.arpa"]
+                    .iter()
+                    .any(|suffix| domain.ends_with(suffix))
+        }
+        url::Host::Ipv4(ip) => is_internal_ip(std::net::IpAddr::V4(*ip)),
+        url::Host::Ipv6(ip) => is_internal_ip(std::net::IpAddr::V6(*ip)),
+    }
+}
+
+/// Private, loopback, link-local, carrier-grade NAT (100.64.0.0/10) and unspecified addresses.
+fn is_internal_ip(ip: std::net::IpAddr) -> bool {
+    match ip {
+        std::net::IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || (a == 100 && (b & 0xc0) == 64)
+        }
+        std::net::IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || ip
+                    .to_ipv4_mapped()
+                    .is_some_and(|v4| is_internal_ip(std::net::IpAddr::V4(v4)))
+        }
+    }
+}
+
+/// Resolver for probe requests. ureq connects to exactly the addresses returned here, so a name
+/// that passed the URL check cannot rebind to an internal address for the connection itself;
+/// it runs for the first request and for every redirect hop.
+fn resolve_public_only(netloc: &str) -> std::io::Result<Vec<std::net::SocketAddr>> {
+    let addrs: Vec<std::net::SocketAddr> =
+        std::net::ToSocketAddrs::to_socket_addrs(netloc)?.collect();
+    match addrs.iter().find(|addr| is_internal_ip(addr.ip())) {
+        Some(internal) => Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{netloc} resolves to internal address {}", internal.ip()),
+        )),
+        None => Ok(addrs),
+    }
+}
+
+/// Checks a URL the probe is about to request, `from` being the page that redirected to it.
+///
+/// Every hop gets the source URL policy, so a redirect cannot reach an internal host or an IP
+/// literal the registry would have refused, nor drop from HTTPS to plain HTTP.
+fn check_probe_target(
+    from: Option<&Url>,
+    to: &Url,
+    config: &SourceProbeConfig,
+    policy: &SourceUrlPolicyConfig,
+) -> Result<(), String> {
+    if from.is_some_and(|from| from.scheme() == "https") && to.scheme() != "https" {
+        return Err("downgrades HTTPS to plain HTTP".to_string());
+    }
+    match to.host() {
+        Some(host) if is_internal_host(&host) => {
+            if !config.allow_private_hosts {
+                return Err(format!("'{host}' is an internal host"));
+            }
+            if !matches!(to.scheme(), "http" | "https") {
+                return Err(format!("scheme '{}' is not allowed", to.scheme()));
+            }
+            Ok(())
+        }
+        _ => canonicalize_source_url(to.as_str(), policy).map(|_| ()),
+    }
+}
+
+/// Sends HEAD (GET when HEAD is refused) without reading bodies and follows redirects by hand
+/// so every hop is counted, bounded by `max_redirects` and checked before it is requested.
+fn probe_source_url(
+    url: &str,
+    config: &SourceProbeConfig,
+    policy: &SourceUrlPolicyConfig,
+) -> ProbeOutcome {
+    let mut agent = ureq::AgentBuilder::new()
+        .timeout(std::time::Duration::from_secs(config.timeout_secs.max(1)))
+        .redirects(0);
+    if !config.allow_private_hosts {
+        agent = agent.resolver(resolve_public_only);
+    }
+    let agent = agent.build();
+    let started = std::time::Instant::now();
+    let mut outcome = ProbeOutcome {
+        method: "HEAD",
+        final_url: url.to_string(),
+        ..ProbeOutcome::default()
+    };
+    let blocked = match Url::parse(url) {
+        Ok(target) => check_probe_target(None, &target, config, policy).err(),
+        Err(e) => Some(format!("'{url}' is not a valid URL: {e}")),
+    };
+    if let Some(why) = blocked {
+        outcome.error = format!("probe of {url} blocked: {why}");
+        return outcome;
+    }
+    loop {
+        let is_https = outcome.final_url.starts_with("https://");
+        let response = match agent.request(outcome.method, &outcome.final_url).call() {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(transport)) => {
+                if is_https && is_tls_failure(&transport) {
+                    outcome.tls_valid = Some(false);
+                }
+                outcome.error = error_chain(&transport);
+                break;
+            }
+        };
+        if is_https {
+            outcome.tls_valid.get_or_insert(true);
+        }
+        let status = response.status();
+        if outcome.method == "HEAD" && matches!(status, 405 | 501) {
+            outcome.method = "GET";
+            continue;
+        }
+        if let Some(location) = response
+            .header("location")
+            .filter(|_| (300..400).contains(&status))
+        {
+            if outcome.redirect_count >= config.max_redirects {
+                outcome.http_status = Some(status);
+                outcome.error = format!("more than {} redirects", config.max_redirects);
+                break;
+            }
+            let hop =
+                Url::parse(&outcome.final_url).and_then(|base| Ok((base.join(location)?, base)));
+            match hop {
+                Ok((next, base)) => {
+                    if let Err(why) = check_probe_target(Some(&base), &next, config, policy) {
+                        outcome.http_status = Some(status);
+                        outcome.error = format!("redirect to {next} blocked: {why}");
+                        break;
+                    }
+                    outcome.redirect_count += 1;
+                    outcome.final_url = next.to_string();
+                    continue;
+                }
+                Err(e) => {
+                    outcome.http_status = Some(status);
+                    outcome.error = format!("invalid redirect location '{location}': {e}");
+                    break;
+                }
+            }
+        }
+        outcome.http_status = Some(status);
+        outcome.content_type = response
+            .header("content-type")
+            .unwrap_or_default()
+            .to_string();
+        break;
+    }
+    outcome.duration_ms = started.elapsed().as_millis() as i64;
+    outcome
+}
+
+fn probe_url_domain(url: &str) -> String {
+    let relaxed = SourceUrlPolicyConfig {
+        allow_http: true,
+        allow_ip_literals: true,
+        allow_credentials: true,
+    };
+    match canonicalize_source_url(url, &relaxed) {
+        Ok(canonical) => canonical.domain,
+        Err(_) => Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_lowercase))
+            .unwrap_or_default(),
+    }
+}
+
+/// `broken` when the source is unreachable, fails TLS or answers with an error; `degraded` when
+/// it now lands on another domain (typical of parked domains) or drops to plain HTTP.
+fn classify_probe(outcome: &ProbeOutcome, url: &str, domain: &str) -> (&'static str, String) {
+    if outcome.tls_valid == Some(false) {
+        return (
+            "broken",
+            format!("TLS validation failed: {}", outcome.error),
+        );
+    }
+    if !outcome.error.is_empty() {
+        return ("broken", outcome.error.clone());
+    }
+    let Some(status) = outcome.http_status else {
+        return ("broken", "no response".to_string());
+    };
+    if status >= 400 {
+        return ("broken", format!("HTTP {status}"));
+    }
+    if url.starts_with("https://") && !outcome.final_url.starts_with("https://") {
+        return (
+            "degraded",
+            format!("redirected to plain HTTP {}", outcome.final_url),
+        );
+    }
+    let final_domain = probe_url_domain(&outcome.final_url);
+    if !final_domain.eq_ignore_ascii_case(domain) {
+        return (
+            "degraded",
+            format!("redirected off {domain} to {final_domain}"),
+        );
+    }
+    if status >= 300 {
+        return (
+            "degraded",
+            format!("HTTP {status} without a usable redirect"),
+        );
+    }
+    (
+        "healthy",
+        format!("HTTP {status} after {} redirects", outcome.redirect_count),
+    )
+}
+
+/// Probes registry entries, records each probe and updates the entry's health.
+fn run_source_probe(
+    conn: &Connection,
+    config: &SourceProbeConfig,
+    policy: &SourceUrlPolicyConfig,
+    request: &SourceProbeRequest,
+) -> Result<Vec<SourceProbeResult>, CliError> {
+    let (sql, value) = match request.candidate_id {
+        Some(candidate_id) => (
+            "SELECT id, candidate_id, software_name, url, domain, status FROM source_registry WHERE candidate_id = ?1",
+            candidate_id,
+        ),
+        None => (
+            "SELECT id, candidate_id, software_name, url, domain, status FROM source_registry WHERE status = 'active' ORDER BY id ASC LIMIT ?1",
+            request.limit.unwrap_or(-1),
+        ),
+    };
+    let mut stmt = conn.prepare(sql)?;
+    let targets = stmt
+        .query_map(params![value], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if let (Some(candidate_id), true) = (request.candidate_id, targets.is_empty()) {
+        return Err(CliError::Usage(format!(
+            "candidate_id {candidate_id} is not in the source registry"
+        )));
+    }
+
+    let disable_broken = request.disable_broken || config.auto_disable_broken;
+    let mut results = Vec::with_capacity(targets.len());
+    for (registry_id, candidate_id, software_name, url, domain, status) in targets {
+        let outcome = probe_source_url(&url, config, policy);
+        let (health, detail) = classify_probe(&outcome, &url, &domain);
+        // Only live entries are disabled; one awaiting re-review keeps that status.
+        let disabled = disable_broken && health == "broken" && status == "active";
+        let ts = unix_ts();
+        conn.execute(
+            r#"
+            INSERT INTO source_probe_history
+            (registry_id, candidate_id, url, method, http_status, final_url, redirect_count, tls_valid,
+             content_type, health, detail, disabled, duration_ms, ts)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            "#,
+            params![
+                registry_id,
+                candidate_id,
+                url,
+                outcome.method,
+                outcome.http_status,
+                outcome.final_url,
+                outcome.redirect_count,
+                outcome.tls_valid,
+                outcome.content_type,
+                health,
+                detail,
+                disabled,
+                outcome.duration_ms,
+                ts
+            ],
+        )?;
+        conn.execute(
+            r#"
+            UPDATE source_registry
+            SET health = ?1,
+                last_probed_at = ?2,
+                status = CASE WHEN ?3 THEN 'disabled' ELSE status END
+            WHERE id = ?4
+            "#,
+            params![health, ts, disabled, registry_id],
+        )?;
+        results.push(SourceProbeResult {
+            registry_id,
+            candidate_id,
+            software_name,
+            url,
+            outcome,
+            health,
+            detail,
+            disabled,
+        });
+    }
+    Ok(results)
+}
+
+fn probe_summary(results: &[SourceProbeResult]) -> serde_json::Value {
+    let count = |health: &str| results.iter().filter(|r| r.health == health).count();
+    json!({
+        "probed": results.len(),
+        "healthy": count("healthy"),
+        "degraded": count("degraded"),
+        "broken": count("broken"),
+        "disabled": results.iter().filter(|r| r.disabled).count()
+    })
+}
+
+/// Worker entry point for `source.probe` jobs; the payload mirrors the `source probe` flags.
+fn run_source_probe_job(payload_json: &str) -> Result<String, CliError> {
+    let request: SourceProbeRequest = serde_json::from_str(payload_json)?;
+    request.validate()?;
+    let config = load_config()?;
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+    let results = run_source_probe(
+        &conn,
+        &config.source_probe,
+        &config.source_url_policy,
+        &request,
+    )?;
+    let summary = probe_summary(&results);
+    Ok(format!(
+        "probed {} sources: healthy={}, degraded={}, broken={}, disabled={}",
+        summary["probed"],
+        summary["healthy"],
+        summary["degraded"],
+        summary["broken"],
+        summary["disabled"]
+    ))
+}
+
+fn source_probe(args: SourceProbeArgs) -> Result<(), CliError> {
+    let request = SourceProbeRequest {
+        candidate_id: args.candidate_id,
+        all: args.all,
+        limit: args.limit.map(i64::from),
+        disable_broken: args.disable_broken,
+    };
+    request.validate()?;
+    let config = load_config()?;
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+    let results = run_source_probe(
+        &conn,
+        &config.source_probe,
+        &config.source_url_policy,
+        &request,
+    )?;
+
+    let mut payload = probe_summary(&results);
+    if args.json {
+        payload["results"] = json!(results
+            .iter()
+            .map(SourceProbeResult::to_json)
+            .collect::<Vec<_>>());
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        for r in &results {
+            println!(
+                "{} {} -> {} ({}){}",
+                r.candidate_id,
+                r.url,
+                r.health,
+                r.detail,
+                if r.disabled { " [disabled]" } else { "" }
+            );
+        }
+        println!(
+            "Source probe finished: probed={}, healthy={}, degraded={}, broken={}, disabled={}",
+            payload["probed"],
+            payload["healthy"],
+            payload["degraded"],
+            payload["broken"],
+            payload["disabled"]
+        );
+    }
+    Ok(())
+}
+
+fn source_probe_history(args: SourceProbeHistoryArgs) -> Result<(), CliError> {
+    let limit = i64::from(args.limit.unwrap_or(50));
+    if limit <= 0 {
+        return Err(CliError::Usage("--limit must be >= 1".to_string()));
+    }
+    if let Some(health) = args.health.as_deref() {
+        validate_registry_health(health)?;
+    }
+
+    let db_file = db_path()?;
+    init_db(&db_file)?;
+    let conn = Connection::open(db_file)?;
+
+    let mut sql = String::from(
+        r#"
+        SELECT id, registry_id, candidate_id, url, method, http_status, final_url, redirect_count, tls_valid,
+               content_type, health, detail, disabled, duration_ms, ts
+        FROM source_probe_history
+        "#,
+    );
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if let Some(candidate_id) = args.candidate_id {
+        clauses.push("candidate_id = ?".to_string());
+        values.push(Value::Integer(candidate_id));
+    }
+    if let Some(health) = args.health.clone() {
+        clauses.push("health = ?".to_string());
+        values.push(Value::Text(health));
+    }
+    if !clauses.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&clauses.join(" AND "));
+    }
+    sql.push_str(" ORDER BY id DESC LIMIT ?");
+    values.push(Value::Integer(limit));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+        Ok(json!({
+            "id": row.get::<_, i64>(0)?,
+            "registry_id": row.get::<_, i64>(1)?,
+            "candidate_id": row.get::<_, i64>(2)?,
+            "url": row.get::<_, String>(3)?,
+            "method": row.get::<_, String>(4)?,
+            "http_status": row.get::<_, Option<i64>>(5)?,
+            "final_url": row.get::<_, String>(6)?,
+            "redirect_count": row.get::<_, i64>(7)?,
+            "tls_valid": row.get::<_, Option<bool>>(8)?,
+            "content_type": row.get::<_, String>(9)?,
+            "health": row.get::<_, String>(10)?,
+            "detail": row.get::<_, String>(11)?,
+            "disabled": row.get::<_, bool>(12)?,
+            "duration_ms": row.get::<_, i64>(13)?,
+            "ts": row.get::<_, i64>(14)?
+        }))
+    })?;
+    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    if payload.is_empty() {
+        return print_payload(args.json, json!([]), "No source probe history found.");
+    }
+    print_payload(args.json, json!(payload), "Source probe history listed.")
 }
 
 fn update_check(args: UpdateCheckArgs) -> Result<(), CliError> {
@@ -1805,13 +9444,16 @@ fn update_check(args: UpdateCheckArgs) -> Result<(), CliError> {
 
     let db_file = db_path()?;
     init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    expire_registry_sources(&mut conn, unix_ts())?;
 
     let mut sql = String::from(
         r#"
-        SELECT candidate_id, software_id, software_name, url, domain, confidence, reason, applied_at
-        FROM source_registry
-        WHERE status = 'active'
+        SELECT r.candidate_id, r.software_id, r.software_name, r.url, r.domain, r.confidence, r.reason, r.applied_at,
+               i.criticality, (SELECT score_breakdown FROM source_candidate WHERE id = r.candidate_id), r.expires_at
+        FROM source_registry r
+        LEFT JOIN software_inventory i ON i.id = r.software_id
+        WHERE r.status = 'active'
         "#,
     );
     let mut values: Vec<Value> = Vec::new();
@@ -1831,30 +9473,86 @@ fn update_check(args: UpdateCheckArgs) -> Result<(), CliError> {
     values.push(Value::Integer(limit));
     values.push(Value::Integer(offset));
 
+    let vulns = match_vulnerabilities(&conn, None)?;
+    let pins = load_active_pins(&conn)?;
     let mut stmt = conn.prepare(&sql)?;
     let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
-        let confidence = row.get::<_, i64>(5)?;
-        let risk_level = if confidence >= 70 { "low" } else { "medium" };
-        let recommendation = if confidence >= 70 {
+        let software_id = row.get::<_, i64>(1)?;
+        let matched: Vec<&VulnMatch> = vulns.iter().filter(|m| m.software_id == software_id).collect();
+        let worst = matched
+            .iter()
+            .map(|m| m.severity.as_str())
+            .max_by_key(|s| severity_rank(s));
+        // Known vulnerabilities make the update more pressing and the recommendation more certain.
+        let mut confidence = row.get::<_, i64>(5)?;
+        let criticality = row.get::<_, Option<String>>(8)?;
+        let mut score_breakdown = parse_score_breakdown(row.get::<_, Option<String>>(9)?.as_deref());
+        if let Some(severity) = worst {
+            match score_breakdown["factors"].as_array_mut() {
+                Some(factors) => {
+                    factors.push(json!({
+                        "factor": "known_vulnerabilities",
+                        "points": SCORE_KNOWN_VULNERABILITIES,
+                        "detail": format!("{} matched advisories, max severity {severity}", matched.len())
+                    }));
+                    // Same rule as candidate scoring: the factors add up to the total, clamped once.
+                    confidence = factors
+                        .iter()
+                        .filter_map(|f| f["points"].as_i64())
+                        .sum::<i64>()
+                        .clamp(0, 100);
+                    score_breakdown["total"] = json!(confidence);
+                }
+                None => confidence = (confidence + SCORE_KNOWN_VULNERABILITIES).clamp(0, 100),
+            }
+        }
+        let pin = pins.get(&software_id);
+        let recommendation = if pin.is_some() {
+            "held_by_pin"
+        } else if criticality.as_deref() == Some("critical") {
+            "manual_approval_required"
+        } else if worst.is_some() {
+            "apply_security_update"
+        } else if confidence >= 70 {
             "review_and_apply"
         } else {
             "review_source_first"
         };
         Ok(json!({
             "candidate_id": row.get::<_, i64>(0)?,
-            "software_id": row.get::<_, i64>(1)?,
+            "software_id": software_id,
             "software_name": row.get::<_, String>(2)?,
             "source_url": row.get::<_, String>(3)?,
             "source_domain": row.get::<_, String>(4)?,
             "confidence": confidence,
+            "score_breakdown": score_breakdown,
             "reason": row.get::<_, String>(6)?,
             "applied_at": row.get::<_, i64>(7)?,
+            "expires_at": row.get::<_, Option<i64>>(10)?,
             "update_available": true,
-            "risk_level": risk_level,
+            "held": pin.is_some(),
+            "hold_reason": pin.map(|p| p.reason.clone()),
+            "pin": pin.map(SoftwarePin::to_json),
+            "criticality": criticality,
+            "vulnerabilities": {
+                "count": matched.len(),
+                "max_severity": worst,
+                "advisory_ids": matched.iter().map(|m| m.advisory_id.clone()).collect::<Vec<_>>()
+            },
             "recommendation": recommendation
         }))
     })?;
-    let payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    let mut payload: Vec<serde_json::Value> = rows.collect::<Result<Vec<_>, _>>()?;
+    let config = load_config()?;
+    for item in &mut payload {
+        let candidate_id = item["candidate_id"].as_i64().unwrap_or_default();
+        let risk = action_risk(&conn, &config, &format!("update.candidate:{candidate_id}"))?;
+        let held = item["held"].as_bool().unwrap_or_default();
+        item["auto_approvable"] =
+            json!(!held && is_auto_approvable(risk.level, item["criticality"].as_str()));
+        item["risk_level"] = json!(risk.level);
+        item["risk_score"] = json!(risk.score);
+    }
     if payload.is_empty() {
         return print_payload(args.json, json!([]), "No active update sources found.");
     }
@@ -1866,30 +9564,46 @@ fn update_apply(args: UpdateApplyArgs) -> Result<(), CliError> {
 
     let db_file = db_path()?;
     init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    expire_registry_sources(&mut conn, unix_ts())?;
 
     let selected = conn.query_row(
         r#"
-        SELECT candidate_id, software_id, software_name, url, domain, confidence, reason
-        FROM source_registry
-        WHERE candidate_id = ?1 AND status = 'active'
+        SELECT r.candidate_id, r.software_id, r.software_name, r.url, r.domain, r.confidence, r.reason, i.criticality,
+               r.status, r.expires_at
+        FROM source_registry r
+        LEFT JOIN software_inventory i ON i.id = r.software_id
+        WHERE r.candidate_id = ?1 AND r.status IN ('active', 'review_required')
         "#,
         params![args.candidate_id],
         |row| {
-            Ok(json!({
-                "candidate_id": row.get::<_, i64>(0)?,
-                "software_id": row.get::<_, i64>(1)?,
-                "software_name": row.get::<_, String>(2)?,
-                "source_url": row.get::<_, String>(3)?,
-                "source_domain": row.get::<_, String>(4)?,
-                "confidence": row.get::<_, i64>(5)?,
-                "reason": row.get::<_, String>(6)?
-            }))
+            let confidence = row.get::<_, i64>(5)?;
+            let criticality = row.get::<_, Option<String>>(7)?;
+            Ok((
+                json!({
+                    "candidate_id": row.get::<_, i64>(0)?,
+                    "software_id": row.get::<_, i64>(1)?,
+                    "software_name": row.get::<_, String>(2)?,
+                    "source_url": row.get::<_, String>(3)?,
+                    "source_domain": row.get::<_, String>(4)?,
+                    "confidence": confidence,
+                    "reason": row.get::<_, String>(6)?,
+                    "criticality": criticality,
+                    "expires_at": row.get::<_, Option<i64>>(9)?
+                }),
+                row.get::<_, String>(8)?,
+            ))
         },
     );
 
     let target = match selected {
-        Ok(v) => v,
+        Ok((_, status)) if status == "review_required" => {
+            return Err(CliError::Security(format!(
+                "source for candidate {} has expired and requires re-review; re-approve it with `source review --approve` and `source apply-approved`",
+                args.candidate_id
+            )));
+        }
+        Ok((v, _)) => v,
         Err(rusqlite::Error::QueryReturnedNoRows) => {
             return Err(CliError::Usage(format!(
                 "active source not found for --candidate-id {}",
@@ -1899,20 +9613,61 @@ fn update_apply(args: UpdateApplyArgs) -> Result<(), CliError> {
         Err(e) => return Err(CliError::Db(e)),
     };
 
+    let mut target = target;
+    let risk_action_id = format!("update.apply:{}", args.candidate_id);
+    let risk_facts = load_action_risk_facts(&conn, &load_config()?, &risk_action_id)?;
+    let risk = evaluate_risk(&risk_facts);
+    record_risk_evaluation(&conn, &risk_action_id, "update apply", &risk_facts, &risk)?;
+    target["risk_level"] = json!(risk.level);
+    target["risk_score"] = json!(risk.score);
+    target["risk_reasons"] = risk.reasons_json();
+    let software_id = target["software_id"].as_i64().unwrap_or_default();
+    let target_version = args
+        .target_version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    let pin_violated = match load_active_pin(&conn, software_id)? {
+        Some(pin) => {
+            let constraint = parse_version_constraint(&pin.version_constraint)?;
+            // Without an explicit target version the update cannot be shown to respect the pin.
+            let violated = !target_version.is_some_and(|v| version_satisfies(v, &constraint));
+            let mut pin_json = pin.to_json();
+            pin_json["violated"] = json!(violated);
+            target["pin"] = pin_json;
+            if violated && !args.dry_run && !args.override_pin {
+                return Err(CliError::Security(format!(
+                    "software {software_id} is pinned to '{}' ({}); target version {} violates the pin; use --override-pin with --execution-ticket to proceed",
+                    pin.version_constraint,
+                    if pin.reason.is_empty() { "no reason recorded" } else { pin.reason.as_str() },
+                    target_version.unwrap_or("<unspecified>")
+                )));
+            }
+            violated
+        }
+        None => false,
+    };
+    target["target_version"] = json!(target_version);
+
     let operation_id = next_operation_id("update", args.candidate_id);
     let mode = if args.dry_run { "dry_run" } else { "confirmed_execution" };
     let execution_ticket = args.execution_ticket.clone().unwrap_or_default();
 
     if args.dry_run {
+        let (status, message) = if pin_violated {
+            ("held", "update apply dry-run held by version pin")
+        } else {
+            ("planned", "update apply dry-run planned successfully")
+        };
         let payload = json!({
             "operation_id": operation_id,
             "mode": mode,
-            "status": "planned",
+            "status": status,
             "mutation_boundary_reached": false,
             "rollback_attempted": false,
             "execution_ticket": "",
             "target": target,
-            "message": "update apply dry-run planned successfully"
+            "message": message
         });
         return print_payload(args.json, payload, "Update apply dry-run planned.");
     }
@@ -1920,13 +9675,18 @@ fn update_apply(args: UpdateApplyArgs) -> Result<(), CliError> {
     ensure_real_mutation_gate_enabled()?;
 
     let started_at = unix_ts();
+    let start_message = if pin_violated {
+        "real execution started (version pin overridden)"
+    } else {
+        "real execution started"
+    };
     conn.execute(
         r#"
         INSERT INTO update_operation_history
         (operation_id, ts, candidate_id, mode, status, message, rollback_attempted, rollback_status, execution_ticket)
-        VALUES (?1, ?2, ?3, ?4, 'started', 'real execution started', 0, 'not_needed', ?5)
+        VALUES (?1, ?2, ?3, ?4, 'started', ?5, 0, 'not_needed', ?6)
         "#,
-        params![operation_id, started_at, args.candidate_id, mode, execution_ticket],
+        params![operation_id, started_at, args.candidate_id, mode, start_message, execution_ticket],
     )?;
 
     let completed_at = unix_ts();
@@ -2078,23 +9838,25 @@ fn cleanup_apply(args: CleanupApplyArgs) -> Result<(), CliError> {
 
     let selected = conn.query_row(
         r#"
-        SELECT id, name, version, publisher, is_active
+        SELECT id, name, version, publisher, is_active, criticality
         FROM software_inventory
         WHERE id = ?1
         "#,
         params![args.software_id],
         |row| {
+            let criticality = row.get::<_, Option<String>>(5)?;
             Ok(json!({
                 "software_id": row.get::<_, i64>(0)?,
                 "software_name": row.get::<_, String>(1)?,
                 "software_version": row.get::<_, String>(2)?,
                 "software_publisher": row.get::<_, String>(3)?,
-                "is_active": row.get::<_, i64>(4)? == 1
+                "is_active": row.get::<_, i64>(4)? == 1,
+                "criticality": criticality
             }))
         },
     );
 
-    let target = match selected {
+    let mut target = match selected {
         Ok(v) => v,
         Err(rusqlite::Error::QueryReturnedNoRows) => {
             return Err(CliError::Usage(format!(
@@ -2104,6 +9866,13 @@ fn cleanup_apply(args: CleanupApplyArgs) -> Result<(), CliError> {
         }
         Err(e) => return Err(CliError::Db(e)),
     };
+    let risk_action_id = format!("cleanup.apply:{}", args.software_id);
+    let risk_facts = load_action_risk_facts(&conn, &load_config()?, &risk_action_id)?;
+    let risk = evaluate_risk(&risk_facts);
+    record_risk_evaluation(&conn, &risk_action_id, "cleanup apply", &risk_facts, &risk)?;
+    target["risk_level"] = json!(risk.level);
+    target["risk_score"] = json!(risk.score);
+    target["risk_reasons"] = risk.reasons_json();
 
     let operation_id = next_operation_id("cleanup", args.software_id);
     let mode = if args.dry_run {
@@ -2337,18 +10106,18 @@ fn ai_analyze(args: AiAnalyzeArgs) -> Result<(), CliError> {
         |r| r.get(0),
     )?;
     let total_publishers: i64 = conn.query_row(
-        "SELECT COUNT(DISTINCT publisher) FROM software_inventory WHERE is_active = 1",
+        "SELECT COUNT(DISTINCT COALESCE(publisher_canonical, publisher)) FROM software_inventory WHERE is_active = 1",
         [],
         |r| r.get(0),
     )?;
 
     let mut publisher_stmt = conn.prepare(
         r#"
-        SELECT publisher, COUNT(1) AS c
+        SELECT COALESCE(publisher_canonical, publisher) AS canonical, COUNT(1) AS c
         FROM software_inventory
         WHERE is_active = 1
-        GROUP BY publisher
-        ORDER BY c DESC, publisher ASC
+        GROUP BY canonical
+        ORDER BY c DESC, canonical ASC
         LIMIT 5
         "#,
     )?;
@@ -2381,12 +10150,17 @@ fn ai_analyze(args: AiAnalyzeArgs) -> Result<(), CliError> {
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
+    let plan_risk = |action: &str, signals: &[&'static str]| {
+        let mut facts = RiskFacts::new(action);
+        facts.signals = signals.to_vec();
+        evaluate_risk(&facts).level
+    };
     let mut recommendations = Vec::new();
     if !potential_redundancy.is_empty() {
         recommendations.push(json!({
             "action": "review_duplicates",
             "reason": "multiple active entries detected for same software name",
-            "risk_level": "low",
+            "risk_level": plan_risk("ai.analyze", &[]),
             "confidence": 80
         }));
     }
@@ -2394,15 +10168,44 @@ fn ai_analyze(args: AiAnalyzeArgs) -> Result<(), CliError> {
         recommendations.push(json!({
             "action": "prioritize_update_groups",
             "reason": "large active software set increases maintenance overhead",
-            "risk_level": "medium",
+            "risk_level": plan_risk("ai.analyze", &["large_inventory"]),
             "confidence": 74
         }));
     }
+    let soon_days = load_config()?.eol.soon_days;
+    let lifecycle = match_eol_cycles(&conn, i64::from(soon_days))?;
+    let eol_products: Vec<&EolMatch> = lifecycle
+        .iter()
+        .filter(|m| m.state == "eol" || m.state == "eol_soon")
+        .collect();
+    if !eol_products.is_empty() {
+        let past_eol = eol_products.iter().filter(|m| m.state == "eol").count();
+        recommendations.push(json!({
+            "action": "eol",
+            "reason": format!(
+                "{past_eol} installed products are past vendor end-of-life and {} reach it within {soon_days} days",
+                eol_products.len() - past_eol
+            ),
+            "risk_level": plan_risk("software.lifecycle", if past_eol > 0 { &["past_eol"] } else { &[] }),
+            "confidence": 85,
+            "products": eol_products
+                .iter()
+                .map(|m| json!({
+                    "software_id": m.software_id,
+                    "software_name": m.software_name,
+                    "product": m.product,
+                    "cycle": m.cycle,
+                    "eol_date": m.eol_date,
+                    "state": m.state
+                }))
+                .collect::<Vec<_>>()
+        }));
+    }
     if recommendations.is_empty() {
         recommendations.push(json!({
             "action": "maintain_current_baseline",
             "reason": "no immediate redundancy pressure detected",
-            "risk_level": "low",
+            "risk_level": plan_risk("ai.analyze", &[]),
             "confidence": 68
         }));
     }
@@ -2460,16 +10263,14 @@ fn ai_recommend(args: AiRecommendArgs) -> Result<(), CliError> {
 
     let goal = args.goal.trim();
     let goal_l = goal.to_lowercase();
-    let (recommended_software, risk_level, confidence, reason) = if goal_l.contains("video")
-        || goal_l.contains("剪辑")
-    {
+    let (recommended_software, signals, confidence, reason) = if is_media_goal(goal) {
         (
             vec![
                 json!({"name": "DaVinci Resolve", "category": "editor"}),
                 json!({"name": "OBS Studio", "category": "capture"}),
                 json!({"name": "HandBrake", "category": "transcode"})
             ],
-            "medium",
+            vec!["system_components"],
             79,
             "goal matched media production workflow template",
         )
@@ -2480,7 +10281,7 @@ fn ai_recommend(args: AiRecommendArgs) -> Result<(), CliError> {
                 json!({"name": "Visual Studio Code", "category": "editor"}),
                 json!({"name": "Docker Desktop", "category": "runtime"})
             ],
-            "low",
+            Vec::new(),
             77,
             "goal matched software development workflow template",
         )
@@ -2491,12 +10292,16 @@ fn ai_recommend(args: AiRecommendArgs) -> Result<(), CliError> {
                 json!({"name": "7-Zip", "category": "utility"}),
                 json!({"name": "PowerToys", "category": "productivity"})
             ],
-            "low",
+            Vec::new(),
             66,
             "goal matched generic productivity fallback template",
         )
     };
 
+    let mut risk_facts = RiskFacts::new("ai.recommend");
+    risk_facts.signals = signals;
+    let risk_level = evaluate_risk(&risk_facts).level;
+
     let recommendation_id = next_operation_id("ai-recommend", unix_ts());
     let created_at = unix_ts();
     conn.execute(
@@ -2562,15 +10367,9 @@ fn ai_repair_plan(args: AiRepairPlanArgs) -> Result<(), CliError> {
         )
         .optional()?;
 
-    let issue_l = args.issue.to_lowercase();
-    let risk_level = if issue_l.contains("crash")
-        || issue_l.contains("data")
-        || issue_l.contains("corrupt")
-    {
-        "high"
-    } else {
-        "medium"
-    };
+    let mut risk_facts = RiskFacts::new("ai.repair-plan");
+    risk_facts.signals = repair_issue_signals(&args.issue);
+    let risk_level = evaluate_risk(&risk_facts).level;
     let confidence = if target.is_some() { 78 } else { 64 };
     let reason = if target.is_some() {
         "plan generated from issue text and matched local software inventory"
@@ -2633,28 +10432,44 @@ fn ui_search(args: UiSearchArgs) -> Result<(), CliError> {
     let limit = i64::from(args.limit.unwrap_or(8));
     let query = validate_ui_search_args(&args.q, limit)?;
 
+    let config = load_config()?;
+
     let db_file = db_path()?;
     init_db(&db_file)?;
-    let conn = Connection::open(db_file)?;
+    let mut conn = Connection::open(db_file)?;
+    expire_registry_sources(&mut conn, unix_ts())?;
     let like = format!("%{query}%");
 
     let mut groups: Vec<serde_json::Value> = Vec::new();
 
     let mut software_stmt = conn.prepare(
         r#"
-        SELECT id, name, version, publisher
+        SELECT id, name, version, COALESCE(publisher_canonical, publisher), criticality
         FROM software_inventory
-        WHERE is_active = 1 AND (name LIKE ?1 OR publisher LIKE ?1)
+        WHERE is_active = 1
+          AND (name LIKE ?1 OR publisher LIKE ?1 OR publisher_canonical LIKE ?1
+               OR owner LIKE ?1 OR team LIKE ?1
+               OR id IN (SELECT software_id FROM software_tag WHERE tag LIKE ?1))
+          AND (?3 IS NULL OR id IN (SELECT software_id FROM software_tag WHERE tag = ?3))
+          AND (?4 IS NULL OR criticality = ?4)
         ORDER BY last_seen_at DESC, id DESC
         LIMIT ?2
         "#,
     )?;
+    let tag = args.tag.as_deref().map(normalize_tag).transpose()?;
+    if let Some(criticality) = args.criticality.as_deref() {
+        validate_criticality(criticality)?;
+    }
     let software_items: Vec<serde_json::Value> = software_stmt
-        .query_map(params![&like, limit], |row| {
+        .query_map(params![&like, limit, tag, args.criticality], |row| {
+            let mut facts = RiskFacts::new("software.show");
+            facts.criticality = row.get::<_, Option<String>>(4)?;
+            let risk_level = evaluate_risk(&facts).level;
             Ok(json!({
                 "title": row.get::<_, String>(1)?,
                 "subtitle": format!("{} | {} | {}", row.get::<_, String>(2)?, row.get::<_, String>(3)?, "active"),
-                "risk_level": "low",
+                "criticality": facts.criticality,
+                "risk_level": risk_level,
                 "confidence": 80,
                 "action_id": format!("software.show:{}", row.get::<_, i64>(0)?)
             }))
@@ -2664,6 +10479,41 @@ fn ui_search(args: UiSearchArgs) -> Result<(), CliError> {
         groups.push(json!({"type": "software", "items": software_items}));
     }
 
+    let mut drift_stmt = conn.prepare(
+        r#"
+        SELECT d.check_id, d.baseline, d.kind, d.name, d.detail
+        FROM baseline_drift_item d
+        WHERE d.kind != 'extra'
+          AND d.check_id IN (
+              SELECT h.check_id FROM baseline_check_history h
+              WHERE h.id = (SELECT MAX(id) FROM baseline_check_history WHERE baseline = h.baseline)
+          )
+          AND (d.name LIKE ?1 OR d.baseline LIKE ?1 OR d.kind LIKE ?1)
+        ORDER BY d.id DESC
+        LIMIT ?2
+        "#,
+    )?;
+    let drift_items: Vec<serde_json::Value> = drift_stmt
+        .query_map(params![&like, limit], |row| {
+            let kind = row.get::<_, String>(2)?;
+            let mut facts = RiskFacts::new("baseline.drift");
+            if kind == "forbidden_present" {
+                facts.signals.push("forbidden_present");
+            }
+            let risk_level = evaluate_risk(&facts).level;
+            Ok(json!({
+                "title": format!("{} ({})", row.get::<_, String>(3)?, kind),
+                "subtitle": format!("{} | {}", row.get::<_, String>(1)?, row.get::<_, String>(4)?),
+                "risk_level": risk_level,
+                "confidence": 90,
+                "action_id": format!("baseline.drift:{}", row.get::<_, String>(0)?)
+            }))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if !drift_items.is_empty() {
+        groups.push(json!({"type": "drift", "items": drift_items}));
+    }
+
     let mut source_stmt = conn.prepare(
         r#"
         SELECT candidate_id, software_name, domain, confidence, url
@@ -2673,17 +10523,17 @@ fn ui_search(args: UiSearchArgs) -> Result<(), CliError> {
         LIMIT ?2
         "#,
     )?;
-    let source_items: Vec<serde_json::Value> = source_stmt
+    let mut source_items: Vec<serde_json::Value> = source_stmt
         .query_map(params![&like, limit], |row| {
             Ok(json!({
                 "title": row.get::<_, String>(1)?,
                 "subtitle": format!("{} | {}", row.get::<_, String>(2)?, row.get::<_, String>(4)?),
-                "risk_level": "low",
                 "confidence": row.get::<_, i64>(3)?,
                 "action_id": format!("source.registry:{}", row.get::<_, i64>(0)?)
             }))
         })?
         .collect::<Result<Vec<_>, _>>()?;
+    annotate_action_risk(&conn, &config, &mut source_items)?;
     if !source_items.is_empty() {
         groups.push(json!({"type": "source", "items": source_items}));
     }
@@ -2697,23 +10547,23 @@ fn ui_search(args: UiSearchArgs) -> Result<(), CliError> {
         LIMIT ?2
         "#,
     )?;
-    let update_items: Vec<serde_json::Value> = update_stmt
+    let mut update_items: Vec<serde_json::Value> = update_stmt
         .query_map(params![&like, limit], |row| {
             let status = row.get::<_, String>(1)?;
-            let (risk_level, confidence) = match status.as_str() {
-                "failed" => ("high", 72),
-                "succeeded" => ("low", 76),
-                _ => ("medium", 60),
+            let confidence = match status.as_str() {
+                "failed" => 72,
+                "succeeded" => 76,
+                _ => 60,
             };
             Ok(json!({
                 "title": format!("Update {}", status),
                 "subtitle": row.get::<_, String>(2)?,
-                "risk_level": risk_level,
                 "confidence": confidence,
                 "action_id": format!("update.history:{}", row.get::<_, String>(0)?)
             }))
         })?
         .collect::<Result<Vec<_>, _>>()?;
+    annotate_action_risk(&conn, &config, &mut update_items)?;
     if !update_items.is_empty() {
         groups.push(json!({"type": "update", "items": update_items}));
     }
@@ -2727,23 +10577,23 @@ fn ui_search(args: UiSearchArgs) -> Result<(), CliError> {
         LIMIT ?2
         "#,
     )?;
-    let download_items: Vec<serde_json::Value> = download_stmt
+    let mut download_items: Vec<serde_json::Value> = download_stmt
         .query_map(params![&like, limit], |row| {
             let status = row.get::<_, String>(1)?;
-            let (risk_level, confidence) = match status.as_str() {
-                "failed" => ("high", 70),
-                "verified" => ("low", 78),
-                _ => ("low", 65),
+            let confidence = match status.as_str() {
+                "failed" => 70,
+                "verified" => 78,
+                _ => 65,
             };
             Ok(json!({
                 "title": format!("Download {}", status),
                 "subtitle": format!("{} | {}", row.get::<_, String>(2)?, row.get::<_, String>(3)?),
-                "risk_level": risk_level,
                 "confidence": confidence,
                 "action_id": format!("download.show:{}", row.get::<_, String>(0)?)
             }))
         })?
         .collect::<Result<Vec<_>, _>>()?;
+    annotate_action_risk(&conn, &config, &mut download_items)?;
     if !download_items.is_empty() {
         groups.push(json!({"type": "download", "items": download_items}));
     }
@@ -2805,22 +10655,30 @@ fn ui_search(args: UiSearchArgs) -> Result<(), CliError> {
     print_payload(args.json, payload, "UI search completed.")
 }
 
+/// Sets each item's `risk_level` from its `action_id`, so search agrees with `ui action-run`.
+fn annotate_action_risk(
+    conn: &Connection,
+    config: &AppConfig,
+    items: &mut [serde_json::Value],
+) -> Result<(), CliError> {
+    for item in items.iter_mut() {
+        let action_id = item["action_id"].as_str().unwrap_or_default().to_string();
+        item["risk_level"] = json!(action_risk(conn, config, &action_id)?.level);
+    }
+    Ok(())
+}
+
 fn ui_action_run(args: UiActionRunArgs) -> Result<(), CliError> {
     let action_id = validate_ui_action_id(&args.id)?;
-    let risk_level = if action_id.starts_with("cleanup.")
-        || action_id.starts_with("update.")
-        || action_id.starts_with("download.retry")
-    {
-        "high"
-    } else if action_id.starts_with("download.") || action_id.starts_with("source.") {
-        "medium"
-    } else {
-        "low"
-    };
+    let config = load_config()?;
 
     let db_file = db_path()?;
     init_db(&db_file)?;
     let conn = Connection::open(db_file)?;
+    let facts = load_action_risk_facts(&conn, &config, action_id)?;
+    let risk = evaluate_risk(&facts);
+    let risk_level = risk.level;
+    record_risk_evaluation(&conn, action_id, "ui action-run", &facts, &risk)?;
     let event_id = next_operation_id("ui-action", unix_ts());
     let now = unix_ts();
 
@@ -2848,14 +10706,17 @@ fn ui_action_run(args: UiActionRunArgs) -> Result<(), CliError> {
     let payload = json!({
         "event_id": event_id,
         "action_id": action_id,
+        "criticality": facts.criticality,
         "risk_level": risk_level,
+        "risk_score": risk.score,
+        "risk_reasons": risk.reasons_json(),
         "status": "executed_simulated",
         "message": "ui action executed in simulated mode"
     });
     print_payload(args.json, payload, "UI action executed in simulated mode.")
 }
 
-fn validate_ui_search_args<'a>(q: &'a str, limit: i64) -> Result<&'a str, CliError> {
+fn validate_ui_search_args(q: &str, limit: i64) -> Result<&str, CliError> {
     let query = q.trim();
     if query.is_empty() {
         return Err(CliError::Usage("--q is required".to_string()));
@@ -2876,16 +10737,9 @@ fn validate_ui_action_id(id: &str) -> Result<&str, CliError> {
 
 fn validate_job_type(job_type: &str) -> Result<(), CliError> {
     match job_type {
-        "discover.scan"
-        | "source.suggest"
-        | "update.check"
-        | "update.apply"
-        | "cleanup.apply"
-        | "download.fetch"
-        | "download.verify"
-        | "ai.analyze"
-        | "ai.recommend"
-        | "ai.repair-plan" => Ok(()),
+        "discover.scan" | "source.suggest" | "source.probe" | "update.check" | "update.apply"
+        | "cleanup.apply" | "download.fetch" | "download.verify" | "ai.analyze"
+        | "ai.recommend" | "ai.repair-plan" => Ok(()),
         _ => Err(CliError::Usage("unknown job_type".to_string())),
     }
 }
@@ -3127,8 +10981,7 @@ fn job_replay_deadletter(args: JobReplayDeadletterArgs) -> Result<(), CliError>
             "SELECT id FROM job_queue WHERE status = 'deadletter' ORDER BY id DESC LIMIT ?1",
         )?;
         let rows = stmt.query_map(params![limit], |row| row.get::<_, i64>(0))?;
-        let ids = rows.collect::<Result<Vec<_>, _>>()?;
-        ids
+        rows.collect::<Result<Vec<_>, _>>()?
     };
 
     let mut updated = 0_i64;
@@ -3277,6 +11130,10 @@ fn execute_worker_job(job: &ClaimedJob) -> (bool, String) {
                 }
             }
         }
+        "source.probe" => match run_source_probe_job(&job.payload_json) {
+            Ok(message) => (true, message),
+            Err(e) => (false, format!("source.probe failed: {e}")),
+        },
         _ => (
             false,
             format!("worker unsupported job_type: {}", job.job_type),
@@ -4207,14 +12064,37 @@ fn count_download_failures(conn: &Connection, failure_type: &str) -> Result<i64,
 
 fn validate_registry_status(status: &str) -> Result<(), CliError> {
     match status {
-        "active" | "disabled" => Ok(()),
+        "active" | "disabled" | "review_required" => Ok(()),
         _ => Err(CliError::Usage(
-            "--status must be one of: active, disabled".to_string(),
+            "--status must be one of: active, disabled, review_required".to_string(),
+        )),
+    }
+}
+
+fn validate_registry_health(health: &str) -> Result<(), CliError> {
+    match health {
+        "unknown" | "healthy" | "degraded" | "broken" => Ok(()),
+        _ => Err(CliError::Usage(
+            "--health must be one of: unknown, healthy, degraded, broken".to_string(),
+        )),
+    }
+}
+
+fn validate_install_scope(scope: &str) -> Result<(), CliError> {
+    match scope {
+        "machine" | "user" => Ok(()),
+        _ => Err(CliError::Usage(
+            "--scope must be one of: machine, user".to_string(),
         )),
     }
 }
 
 fn validate_update_apply_flags(args: &UpdateApplyArgs) -> Result<(), CliError> {
+    if args.override_pin && args.dry_run {
+        return Err(CliError::Usage(
+            "--override-pin is only valid with confirmed execution".to_string(),
+        ));
+    }
     validate_confirmed_execution_flags(
         args.dry_run,
         args.confirm,
@@ -4521,7 +12401,145 @@ fn init_db(path: &Path) -> Result<(), CliError> {
             first_seen_at INTEGER NOT NULL,
             last_seen_at INTEGER NOT NULL,
             is_active INTEGER NOT NULL DEFAULT 1,
-            fingerprint TEXT NOT NULL UNIQUE
+            fingerprint TEXT NOT NULL UNIQUE,
+            uninstall_string TEXT,
+            quiet_uninstall_string TEXT,
+            install_date TEXT,
+            estimated_size_kb INTEGER,
+            product_code TEXT,
+            upgrade_code TEXT,
+            install_scope TEXT,
+            identity_key TEXT,
+            merged_into INTEGER,
+            publisher_canonical TEXT,
+            owner TEXT,
+            team TEXT,
+            criticality TEXT,
+            notes TEXT,
+            license TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS software_tag (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            software_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            UNIQUE(software_id, tag)
+        );
+        CREATE INDEX IF NOT EXISTS idx_software_tag_tag ON software_tag(tag);
+
+        CREATE TABLE IF NOT EXISTS software_manual_audit (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts INTEGER NOT NULL,
+            software_id INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            actor TEXT NOT NULL,
+            before_json TEXT,
+            after_json TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS vuln_advisory (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            advisory_id TEXT NOT NULL UNIQUE,
+            source TEXT NOT NULL,
+            aliases_json TEXT NOT NULL DEFAULT '[]',
+            summary TEXT NOT NULL DEFAULT '',
+            severity TEXT NOT NULL,
+            cvss_score REAL,
+            published TEXT,
+            modified TEXT,
+            imported_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS vuln_affected (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            advisory_id TEXT NOT NULL,
+            package_name TEXT NOT NULL,
+            vendor TEXT NOT NULL DEFAULT '',
+            version_start TEXT,
+            start_inclusive INTEGER NOT NULL DEFAULT 1,
+            version_end TEXT,
+            end_inclusive INTEGER NOT NULL DEFAULT 0,
+            fixed_version TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_vuln_affected_package ON vuln_affected(package_name);
+
+        CREATE TABLE IF NOT EXISTS vuln_sync_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts INTEGER NOT NULL,
+            file TEXT NOT NULL,
+            format TEXT NOT NULL,
+            advisories INTEGER NOT NULL,
+            affected_ranges INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS eol_cycle (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            product TEXT NOT NULL,
+            cycle TEXT NOT NULL,
+            eol_date TEXT,
+            eol_flag INTEGER,
+            latest TEXT,
+            imported_at INTEGER NOT NULL,
+            UNIQUE(product, cycle)
+        );
+
+        CREATE TABLE IF NOT EXISTS eol_sync_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts INTEGER NOT NULL,
+            file TEXT NOT NULL,
+            products INTEGER NOT NULL,
+            cycles INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS software_pin (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            software_id INTEGER NOT NULL UNIQUE,
+            version_constraint TEXT NOT NULL,
+            reason TEXT NOT NULL DEFAULT '',
+            created_at INTEGER NOT NULL,
+            created_by TEXT NOT NULL,
+            expires_at INTEGER
+        );
+
+        CREATE TABLE IF NOT EXISTS baseline_check_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            check_id TEXT NOT NULL UNIQUE,
+            baseline TEXT NOT NULL,
+            ts INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            missing_count INTEGER NOT NULL,
+            forbidden_count INTEGER NOT NULL,
+            out_of_constraint_count INTEGER NOT NULL,
+            extra_count INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS baseline_drift_item (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            check_id TEXT NOT NULL,
+            baseline TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            software_id INTEGER,
+            name TEXT NOT NULL,
+            version TEXT,
+            expected TEXT,
+            detail TEXT NOT NULL DEFAULT ''
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_baseline_drift_item_check ON baseline_drift_item(check_id);
+
+        CREATE TABLE IF NOT EXISTS schema_migration (
+            name TEXT PRIMARY KEY,
+            applied_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS publisher_alias (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            alias_key TEXT NOT NULL UNIQUE,
+            canonical TEXT NOT NULL,
+            origin TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            updated_at INTEGER NOT NULL
         );
 
         CREATE TABLE IF NOT EXISTS software_discovery_history (
@@ -4538,6 +12556,35 @@ fn init_db(path: &Path) -> Result<(), CliError> {
             active_after INTEGER NOT NULL
         );
 
+        CREATE TABLE IF NOT EXISTS software_version_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            software_id INTEGER NOT NULL,
+            scan_id TEXT NOT NULL,
+            ts INTEGER NOT NULL,
+            change_type TEXT NOT NULL,
+            old_version TEXT,
+            new_version TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_software_version_history_software
+            ON software_version_history(software_id, ts);
+
+        CREATE TABLE IF NOT EXISTS software_scan_snapshot (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            scan_id TEXT NOT NULL,
+            software_id INTEGER NOT NULL,
+            fingerprint TEXT NOT NULL,
+            version TEXT NOT NULL,
+            UNIQUE(scan_id, fingerprint)
+        );
+
+        CREATE TABLE IF NOT EXISTS software_merge_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts INTEGER NOT NULL,
+            into_software_id INTEGER NOT NULL,
+            from_software_id INTEGER NOT NULL,
+            moved_json TEXT NOT NULL
+        );
+
         CREATE TABLE IF NOT EXISTS repo_registry (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             repo_key TEXT NOT NULL UNIQUE,
@@ -4622,6 +12669,17 @@ fn init_db(path: &Path) -> Result<(), CliError> {
             recommendation_json TEXT NOT NULL
         );
 
+        CREATE TABLE IF NOT EXISTS risk_evaluation_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action_id TEXT NOT NULL,
+            command TEXT NOT NULL,
+            level TEXT NOT NULL,
+            score INTEGER NOT NULL,
+            reasons_json TEXT NOT NULL,
+            facts_json TEXT NOT NULL,
+            ts INTEGER NOT NULL
+        );
+
         CREATE TABLE IF NOT EXISTS ui_action_history (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             event_id TEXT NOT NULL UNIQUE,
@@ -4660,6 +12718,57 @@ fn init_db(path: &Path) -> Result<(), CliError> {
             UNIQUE(software_id, url)
         );
 
+        CREATE TABLE IF NOT EXISTS source_review_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            candidate_id INTEGER NOT NULL,
+            software_id INTEGER NOT NULL,
+            old_status TEXT NOT NULL,
+            new_status TEXT NOT NULL,
+            reviewer TEXT NOT NULL,
+            note TEXT NOT NULL DEFAULT '',
+            confidence INTEGER NOT NULL,
+            risk_level TEXT NOT NULL,
+            batch_id TEXT,
+            ts INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_source_review_history_candidate ON source_review_history(candidate_id);
+
+        CREATE TABLE IF NOT EXISTS source_auto_review_decision (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            run_id TEXT NOT NULL,
+            candidate_id INTEGER NOT NULL,
+            decision TEXT NOT NULL,
+            rule TEXT NOT NULL,
+            detail TEXT NOT NULL,
+            ts INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS source_calibration (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            scope TEXT NOT NULL,
+            key TEXT NOT NULL,
+            approved INTEGER NOT NULL,
+            rejected INTEGER NOT NULL,
+            approval_rate REAL NOT NULL,
+            adjustment INTEGER NOT NULL,
+            computed_at INTEGER NOT NULL,
+            UNIQUE(scope, key)
+        );
+
+        CREATE TABLE IF NOT EXISTS source_review_batch (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            batch_id TEXT NOT NULL UNIQUE,
+            kind TEXT NOT NULL,
+            target_status TEXT NOT NULL,
+            reviewer TEXT NOT NULL,
+            note TEXT NOT NULL DEFAULT '',
+            matched INTEGER NOT NULL,
+            updated INTEGER NOT NULL,
+            ts INTEGER NOT NULL,
+            undone_by_batch TEXT
+        );
+
         CREATE TABLE IF NOT EXISTS source_registry (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             candidate_id INTEGER NOT NULL UNIQUE,
@@ -4674,6 +12783,51 @@ fn init_db(path: &Path) -> Result<(), CliError> {
             UNIQUE(software_id, url)
         );
 
+        CREATE TABLE IF NOT EXISTS source_probe_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            registry_id INTEGER NOT NULL,
+            candidate_id INTEGER NOT NULL,
+            url TEXT NOT NULL,
+            method TEXT NOT NULL,
+            http_status INTEGER,
+            final_url TEXT NOT NULL,
+            redirect_count INTEGER NOT NULL,
+            tls_valid INTEGER,
+            content_type TEXT NOT NULL DEFAULT '',
+            health TEXT NOT NULL,
+            detail TEXT NOT NULL,
+            disabled INTEGER NOT NULL DEFAULT 0,
+            duration_ms INTEGER NOT NULL,
+            ts INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_source_probe_history_candidate ON source_probe_history(candidate_id);
+
+        CREATE TABLE IF NOT EXISTS source_suggest_state (
+            software_id INTEGER PRIMARY KEY,
+            input_hash TEXT NOT NULL,
+            candidates INTEGER NOT NULL,
+            processed_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS source_registry_import (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            import_id TEXT NOT NULL,
+            entry_index INTEGER NOT NULL,
+            software_id INTEGER,
+            candidate_id INTEGER,
+            url TEXT NOT NULL,
+            action TEXT NOT NULL,
+            match_method TEXT NOT NULL DEFAULT '',
+            detail TEXT NOT NULL,
+            source_file TEXT NOT NULL,
+            exported_by TEXT NOT NULL,
+            exported_at INTEGER NOT NULL,
+            original_approved_by TEXT NOT NULL,
+            original_approved_at INTEGER NOT NULL,
+            ts INTEGER NOT NULL
+        );
+
         CREATE TABLE IF NOT EXISTS update_operation_history (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             operation_id TEXT NOT NULL UNIQUE,
@@ -4710,13 +12864,146 @@ fn init_db(path: &Path) -> Result<(), CliError> {
         );
         "#,
     )?;
+    ensure_software_inventory_columns(&conn)?;
     ensure_update_history_columns(&conn)?;
+    ensure_source_candidate_columns(&conn)?;
+    ensure_source_registry_columns(&conn)?;
     ensure_cleanup_history_columns(&conn)?;
     ensure_download_history_columns(&conn)?;
     ensure_default_repositories(&conn)?;
     Ok(())
 }
 
+fn ensure_software_inventory_columns(conn: &Connection) -> Result<(), CliError> {
+    let mut stmt = conn.prepare("PRAGMA table_info(software_inventory)")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    let columns: Vec<String> = rows.collect::<Result<Vec<_>, _>>()?;
+
+    for (column, ddl) in [
+        ("uninstall_string", "TEXT"),
+        ("quiet_uninstall_string", "TEXT"),
+        ("install_date", "TEXT"),
+        ("estimated_size_kb", "INTEGER"),
+        ("product_code", "TEXT"),
+        ("upgrade_code", "TEXT"),
+        ("install_scope", "TEXT"),
+        ("identity_key", "TEXT"),
+        ("merged_into", "INTEGER"),
+        ("publisher_canonical", "TEXT"),
+        ("owner", "TEXT"),
+        ("team", "TEXT"),
+        ("criticality", "TEXT"),
+        ("notes", "TEXT"),
+        ("license", "TEXT"),
+    ] {
+        if !columns.iter().any(|c| c == column) {
+            conn.execute(
+                &format!("ALTER TABLE software_inventory ADD COLUMN {column} {ddl}"),
+                [],
+            )?;
+        }
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_software_inventory_identity ON software_inventory(identity_key)",
+        [],
+    )?;
+
+    run_migration_once(
+        conn,
+        "0001_seed_publisher_aliases",
+        seed_default_publisher_aliases,
+    )?;
+    // Rows written before identity keys existed get one derived from their stored fields.
+    apply_publisher_aliases(conn, "identity_key IS NULL OR publisher_canonical IS NULL")?;
+    Ok(())
+}
+
+/// Runs a data migration the first time a database sees it; later `init_db` calls skip it, so
+/// users can change or remove what it wrote.
+fn run_migration_once(
+    conn: &Connection,
+    name: &str,
+    migrate: impl FnOnce(&Connection) -> Result<(), CliError>,
+) -> Result<bool, CliError> {
+    let tx = conn.unchecked_transaction()?;
+    let applied: bool = tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM schema_migration WHERE name = ?1)",
+        params![name],
+        |r| r.get(0),
+    )?;
+    if applied {
+        return Ok(false);
+    }
+    migrate(&tx)?;
+    tx.execute(
+        "INSERT INTO schema_migration (name, applied_at) VALUES (?1, ?2)",
+        params![name, unix_ts()],
+    )?;
+    tx.commit()?;
+    Ok(true)
+}
+
+fn seed_default_publisher_aliases(conn: &Connection) -> Result<(), CliError> {
+    let now = unix_ts();
+    for (alias_key, canonical) in BUILTIN_PUBLISHER_ALIASES {
+        conn.execute(
+            r#"
+            INSERT OR IGNORE INTO publisher_alias (alias_key, canonical, origin, enabled, updated_at)
+            VALUES (?1, ?2, 'builtin', 1, ?3)
+            "#,
+            params![alias_key, canonical, now],
+        )?;
+    }
+    Ok(())
+}
+
+fn load_publisher_aliases(conn: &Connection) -> Result<HashMap<String, String>, CliError> {
+    let mut stmt =
+        conn.prepare("SELECT alias_key, canonical FROM publisher_alias WHERE enabled = 1")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    Ok(rows.collect::<Result<HashMap<_, _>, _>>()?)
+}
+
+/// Recomputes canonical publisher and identity key for inventory rows matching `filter`.
+fn apply_publisher_aliases(conn: &Connection, filter: &str) -> Result<usize, CliError> {
+    let aliases = load_publisher_aliases(conn)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, name, publisher, product_code, upgrade_code, publisher_canonical FROM software_inventory WHERE {filter}"
+    ))?;
+    let pending = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut changed = 0_usize;
+    for (id, name, publisher, product_code, upgrade_code, previous) in pending {
+        let canonical = canonical_publisher(&publisher, &aliases);
+        let identity_key = make_identity_key(
+            &name,
+            &canonical,
+            product_code.as_deref(),
+            upgrade_code.as_deref(),
+        );
+        conn.execute(
+            "UPDATE software_inventory SET publisher_canonical = ?1, identity_key = ?2 WHERE id = ?3",
+            params![canonical, identity_key, id],
+        )?;
+        if previous.as_deref() != Some(canonical.as_str()) {
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
 fn ensure_update_history_columns(conn: &Connection) -> Result<(), CliError> {
     let mut stmt = conn.prepare("PRAGMA table_info(update_operation_history)")?;
     let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
@@ -4743,6 +13030,55 @@ fn ensure_update_history_columns(conn: &Connection) -> Result<(), CliError> {
     Ok(())
 }
 
+fn ensure_source_candidate_columns(conn: &Connection) -> Result<(), CliError> {
+    let mut stmt = conn.prepare("PRAGMA table_info(source_candidate)")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    let columns: Vec<String> = rows.collect::<Result<Vec<_>, _>>()?;
+
+    if !columns.iter().any(|c| c == "rule_id") {
+        conn.execute("ALTER TABLE source_candidate ADD COLUMN rule_id TEXT", [])?;
+    }
+    if !columns.iter().any(|c| c == "score_breakdown") {
+        conn.execute(
+            "ALTER TABLE source_candidate ADD COLUMN score_breakdown TEXT",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn ensure_source_registry_columns(conn: &Connection) -> Result<(), CliError> {
+    let mut stmt = conn.prepare("PRAGMA table_info(source_registry)")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    let columns: Vec<String> = rows.collect::<Result<Vec<_>, _>>()?;
+
+    if !columns.iter().any(|c| c == "health") {
+        conn.execute(
+            "ALTER TABLE source_registry ADD COLUMN health TEXT NOT NULL DEFAULT 'unknown'",
+            [],
+        )?;
+    }
+    if !columns.iter().any(|c| c == "last_probed_at") {
+        conn.execute(
+            "ALTER TABLE source_registry ADD COLUMN last_probed_at INTEGER",
+            [],
+        )?;
+    }
+    if !columns.iter().any(|c| c == "expires_at") {
+        conn.execute(
+            "ALTER TABLE source_registry ADD COLUMN expires_at INTEGER",
+            [],
+        )?;
+    }
+    if !columns.iter().any(|c| c == "imported_from") {
+        conn.execute(
+            "ALTER TABLE source_registry ADD COLUMN imported_from TEXT",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
 fn ensure_default_repositories(conn: &Connection) -> Result<(), CliError> {
     let now = unix_ts();
     conn.execute(
@@ -4776,121 +13112,1065 @@ fn ensure_cleanup_history_columns(conn: &Connection) -> Result<(), CliError> {
             "ALTER TABLE cleanup_operation_history ADD COLUMN rollback_attempted INTEGER NOT NULL DEFAULT 0",
             [],
         )?;
-    }
-    if !columns.iter().any(|c| c == "rollback_status") {
-        conn.execute(
-            "ALTER TABLE cleanup_operation_history ADD COLUMN rollback_status TEXT NOT NULL DEFAULT 'not_recorded'",
-            [],
+    }
+    if !columns.iter().any(|c| c == "rollback_status") {
+        conn.execute(
+            "ALTER TABLE cleanup_operation_history ADD COLUMN rollback_status TEXT NOT NULL DEFAULT 'not_recorded'",
+            [],
+        )?;
+    }
+    if !columns.iter().any(|c| c == "execution_ticket") {
+        conn.execute(
+            "ALTER TABLE cleanup_operation_history ADD COLUMN execution_ticket TEXT NOT NULL DEFAULT ''",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn ensure_download_history_columns(conn: &Connection) -> Result<(), CliError> {
+    let mut stmt = conn.prepare("PRAGMA table_info(download_job_history)")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    let columns: Vec<String> = rows.collect::<Result<Vec<_>, _>>()?;
+
+    if !columns.iter().any(|c| c == "hash_status") {
+        conn.execute(
+            "ALTER TABLE download_job_history ADD COLUMN hash_status TEXT NOT NULL DEFAULT 'not_started'",
+            [],
+        )?;
+    }
+    if !columns.iter().any(|c| c == "signature_status") {
+        conn.execute(
+            "ALTER TABLE download_job_history ADD COLUMN signature_status TEXT NOT NULL DEFAULT 'not_started'",
+            [],
+        )?;
+    }
+    if !columns.iter().any(|c| c == "source_policy_status") {
+        conn.execute(
+            "ALTER TABLE download_job_history ADD COLUMN source_policy_status TEXT NOT NULL DEFAULT 'not_started'",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+type SuggestedCandidateRow = (i64, i64, String, String, String, i64, String, String);
+
+#[derive(Debug, Clone)]
+struct SourceCandidateDraft {
+    url: String,
+    domain: String,
+    confidence: i64,
+    reason: String,
+    rule_id: String,
+}
+
+const DEFAULT_SOURCE_RULES: &str = include_str!("source_rules.yaml");
+
+#[derive(Debug, Clone, Deserialize)]
+struct SourceRulesFile {
+    #[serde(default)]
+    rules: Vec<SourceRuleSpec>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SourceRuleMatch {
+    #[serde(default)]
+    name_regex: Option<String>,
+    #[serde(default)]
+    publisher_regex: Option<String>,
+    #[serde(default)]
+    canonical_publisher: Vec<String>,
+    #[serde(default)]
+    discovery_source: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SourceRuleSpec {
+    id: String,
+    #[serde(default)]
+    group: Option<String>,
+    #[serde(default, rename = "match")]
+    matcher: SourceRuleMatch,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    confidence: i64,
+    #[serde(default)]
+    reason: String,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Debug, Clone)]
+struct SourceRule {
+    spec: SourceRuleSpec,
+    origin: &'static str,
+    domain: String,
+    url_policy: SourceUrlPolicyConfig,
+    name_regex: Option<Regex>,
+    publisher_regex: Option<Regex>,
+}
+
+struct SourceRuleInput<'a> {
+    name: &'a str,
+    publisher: &'a str,
+    canonical_publisher: &'a str,
+    discovery_source: &'a str,
+}
+
+impl SourceRule {
+    fn compile(
+        spec: SourceRuleSpec,
+        origin: &'static str,
+        url_policy: SourceUrlPolicyConfig,
+    ) -> Result<Self, CliError> {
+        let id = spec.id.trim();
+        if id.is_empty() {
+            return Err(CliError::Config(
+                "source rule id must not be empty".to_string(),
+            ));
+        }
+        let sample = fill_source_url_template(
+            &spec.url,
+            &SourceRuleInput {
+                name: "sample",
+                publisher: "sample",
+                canonical_publisher: "sample",
+                discovery_source: "registry",
+            },
+        );
+        let domain = canonicalize_source_url(&sample, &url_policy)
+            .map_err(|e| CliError::Config(format!("source rule '{id}' url is invalid: {e}")))?
+            .domain;
+        if !(0..=100).contains(&spec.confidence) {
+            return Err(CliError::Config(format!(
+                "source rule '{id}' confidence must be 0..100"
+            )));
+        }
+        let compile = |field: &str, pattern: Option<&String>| -> Result<Option<Regex>, CliError> {
+            pattern
+                .map(|p| {
+                    RegexBuilder::new(p)
+                        .case_insensitive(true)
+                        .build()
+                        .map_err(|e| {
+                            CliError::Config(format!("source rule '{id}' {field} is invalid: {e}"))
+                        })
+                })
+                .transpose()
+        };
+        let name_regex = compile("name_regex", spec.matcher.name_regex.as_ref())?;
+        let publisher_regex = compile("publisher_regex", spec.matcher.publisher_regex.as_ref())?;
+        Ok(Self {
+            spec,
+            origin,
+            domain,
+            url_policy,
+            name_regex,
+            publisher_regex,
+        })
+    }
+
+    fn matches(&self, input: &SourceRuleInput) -> bool {
+        let m = &self.spec.matcher;
+        self.name_regex
+            .as_ref()
+            .is_none_or(|r| r.is_match(input.name))
+            && self.publisher_regex.as_ref().is_none_or(|r| {
+                r.is_match(input.publisher) || r.is_match(input.canonical_publisher)
+            })
+            && (m.canonical_publisher.is_empty()
+                || m.canonical_publisher
+                    .iter()
+                    .any(|p| publisher_key(p) == publisher_key(input.canonical_publisher)))
+            && (m.discovery_source.is_empty()
+                || m.discovery_source
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(input.discovery_source)))
+    }
+
+    /// Fills the template and canonicalizes it; `None` when the result violates the URL policy.
+    fn render(&self, input: &SourceRuleInput) -> Option<SourceCandidateDraft> {
+        let url = fill_source_url_template(&self.spec.url, input);
+        let canonical = canonicalize_source_url(&url, &self.url_policy).ok()?;
+        Some(SourceCandidateDraft {
+            url: canonical.url,
+            domain: canonical.domain,
+            confidence: self.spec.confidence,
+            reason: self.spec.reason.clone(),
+            rule_id: self.spec.id.clone(),
+        })
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "id": self.spec.id,
+            "origin": self.origin,
+            "group": self.spec.group,
+            "match": {
+                "name_regex": self.spec.matcher.name_regex,
+                "publisher_regex": self.spec.matcher.publisher_regex,
+                "canonical_publisher": self.spec.matcher.canonical_publisher,
+                "discovery_source": self.spec.matcher.discovery_source
+            },
+            "url": self.spec.url,
+            "domain": self.domain,
+            "confidence": self.spec.confidence,
+            "reason": self.spec.reason
+        })
+    }
+}
+
+/// Domains whose downloads are published by the vendor itself.
+const OFFICIAL_VENDOR_DOMAINS: &[&str] = &[
+    "microsoft.com",
+    "visualstudio.com",
+    "google.com",
+    "mozilla.org",
+    "jetbrains.com",
+    "git-scm.com",
+    "python.org",
+    "docker.com",
+    "adobe.com",
+    "oracle.com",
+    "nvidia.com",
+    "intel.com",
+    "amd.com",
+    "zoom.us",
+    "videolan.org",
+    "7-zip.org",
+];
+
+const SCORE_PUBLISHER_MATCH: i64 = 5;
+const SCORE_OFFICIAL_VENDOR: i64 = 5;
+const SCORE_TRUSTED_DOMAIN: i64 = 5;
+const SCORE_UNTRUSTED_DOMAIN: i64 = -40;
+const SCORE_NOT_HTTPS: i64 = -25;
+const SCORE_PER_PRIOR_APPROVAL: i64 = 2;
+const SCORE_PRIOR_APPROVAL_CAP: i64 = 10;
+const SCORE_PER_REJECTION: i64 = -3;
+const SCORE_REJECTION_CAP: i64 = -15;
+const SCORE_KNOWN_VULNERABILITIES: i64 = 15;
+
+#[derive(Debug, Clone)]
+struct ScoreFactor {
+    factor: &'static str,
+    points: i64,
+    detail: String,
+}
+
+#[derive(Debug, Clone)]
+struct CandidateScore {
+    factors: Vec<ScoreFactor>,
+    total: i64,
+}
+
+impl CandidateScore {
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "factors": self
+                .factors
+                .iter()
+                .map(|f| json!({"factor": f.factor, "points": f.points, "detail": f.detail}))
+                .collect::<Vec<_>>(),
+            "total": self.total
+        })
+    }
+}
+
+fn parse_score_breakdown(raw: Option<&str>) -> serde_json::Value {
+    raw.and_then(|r| serde_json::from_str(r).ok())
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// Review outcomes per domain, used to reward or penalise a domain for other software.
+struct ScoringContext {
+    untrusted_domains: Vec<String>,
+    trusted_domains: Vec<String>,
+    domain_reviews: HashMap<String, Vec<(i64, String)>>,
+    calibration: HashMap<(String, String), i64>,
+    max_calibration: i64,
+}
+
+impl ScoringContext {
+    fn load(conn: &Connection, config: &AppConfig) -> Result<Self, CliError> {
+        let mut stmt = conn.prepare(
+            "SELECT domain, software_id, status FROM source_candidate WHERE status IN ('approved', 'rejected')",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        let mut domain_reviews: HashMap<String, Vec<(i64, String)>> = HashMap::new();
+        for row in rows {
+            let (domain, software_id, status) = row?;
+            domain_reviews
+                .entry(domain.to_lowercase())
+                .or_default()
+                .push((software_id, status));
+        }
+        let mut stmt = conn.prepare("SELECT scope, key, adjustment FROM source_calibration")?;
+        let calibration = stmt
+            .query_map([], |row| {
+                Ok((
+                    (row.get::<_, String>(0)?, row.get::<_, String>(1)?),
+                    row.get::<_, i64>(2)?,
+                ))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(Self {
+            untrusted_domains: config.download_source_policy.untrusted_domains.clone(),
+            trusted_domains: config.auto_review.trusted_domains.clone(),
+            domain_reviews,
+            calibration,
+            max_calibration: i64::from(config.calibration.max_adjustment),
+        })
+    }
+
+    fn score(
+        &self,
+        software_id: i64,
+        draft: &SourceCandidateDraft,
+        publisher: &str,
+    ) -> CandidateScore {
+        let domain = draft.domain.to_lowercase();
+        let mut factors = vec![ScoreFactor {
+            factor: "rule_base",
+            points: draft.confidence,
+            detail: format!("rule '{}'", draft.rule_id),
+        }];
+
+        let (trust_points, trust_detail) =
+            if source_untrusted(&draft.url, &domain, &self.untrusted_domains) {
+                (
+                    SCORE_UNTRUSTED_DOMAIN,
+                    "untrusted by download source policy",
+                )
+            } else if source_trusted(&draft.url, &domain, &self.trusted_domains) {
+                (
+                    SCORE_TRUSTED_DOMAIN,
+                    "listed in auto_review.trusted_domains",
+                )
+            } else {
+                (0, "no trust policy entry")
+            };
+        factors.push(ScoreFactor {
+            factor: "domain_trust",
+            points: trust_points,
+            detail: trust_detail.to_string(),
+        });
+
+        let publisher_match = publisher_matches_domain(publisher, &domain);
+        factors.push(ScoreFactor {
+            factor: "publisher_domain_match",
+            points: if publisher_match {
+                SCORE_PUBLISHER_MATCH
+            } else {
+                0
+            },
+            detail: if publisher_match {
+                format!("publisher '{publisher}' matches {domain}")
+            } else {
+                format!("publisher '{publisher}' does not match {domain}")
+            },
+        });
+
+        let https = draft.url.to_lowercase().starts_with("https://");
+        factors.push(ScoreFactor {
+            factor: "https",
+            points: if https { 0 } else { SCORE_NOT_HTTPS },
+            detail: if https {
+                "served over https"
+            } else {
+                "not served over https"
+            }
+            .to_string(),
+        });
+
+        let official = OFFICIAL_VENDOR_DOMAINS
+            .iter()
+            .any(|d| domain == *d || domain.ends_with(&format!(".{d}")));
+        factors.push(ScoreFactor {
+            factor: "official_vendor",
+            points: if official { SCORE_OFFICIAL_VENDOR } else { 0 },
+            detail: if official {
+                "official vendor domain"
+            } else {
+                "not an official vendor domain"
+            }
+            .to_string(),
+        });
+
+        let reviews = self.domain_reviews.get(&domain);
+        let others = |status: &str| {
+            reviews
+                .map(|r| {
+                    r.iter()
+                        .filter(|(id, s)| *id != software_id && s == status)
+                        .count() as i64
+                })
+                .unwrap_or(0)
+        };
+        let approvals = others("approved");
+        let rejections = others("rejected");
+        factors.push(ScoreFactor {
+            factor: "prior_approvals",
+            points: (approvals * SCORE_PER_PRIOR_APPROVAL).min(SCORE_PRIOR_APPROVAL_CAP),
+            detail: format!("{approvals} approved candidates on {domain} for other software"),
+        });
+        factors.push(ScoreFactor {
+            factor: "rejection_history",
+            points: (rejections * SCORE_PER_REJECTION).max(SCORE_REJECTION_CAP),
+            detail: format!("{rejections} rejected candidates on {domain} for other software"),
+        });
+
+        // Domain outcomes are already in prior_approvals / rejection_history; only the rule is calibrated.
+        if let Some(rule_adj) = self
+            .calibration
+            .get(&("rule".to_string(), draft.rule_id.clone()))
+        {
+            factors.push(ScoreFactor {
+                factor: "calibration",
+                points: (*rule_adj).clamp(-self.max_calibration, self.max_calibration),
+                detail: format!(
+                    "rule '{}' {rule_adj:+} from source calibrate",
+                    draft.rule_id
+                ),
+            });
+        }
+
+        let total = factors.iter().map(|f| f.points).sum::<i64>().clamp(0, 100);
+        CandidateScore { factors, total }
+    }
+}
+
+fn user_source_rules_path() -> Result<PathBuf, CliError> {
+    Ok(synora_home()?.join("source_rules.yaml"))
+}
+
+fn parse_source_rules(raw: &str, label: &str) -> Result<Vec<SourceRuleSpec>, CliError> {
+    let file: SourceRulesFile = serde_yaml::from_str(raw)
+        .map_err(|e| CliError::Config(format!("invalid source rules {label}: {e}")))?;
+    Ok(file.rules)
+}
+
+/// Loads the shipped rules and applies the user's overrides by rule id.
+fn load_source_rules(url_policy: &SourceUrlPolicyConfig) -> Result<Vec<SourceRule>, CliError> {
+    let mut merged: Vec<(SourceRuleSpec, &'static str)> =
+        parse_source_rules(DEFAULT_SOURCE_RULES, "defaults")?
+            .into_iter()
+            .map(|spec| (spec, "default"))
+            .collect();
+    let user_path = user_source_rules_path()?;
+    if user_path.is_file() {
+        let raw = fs::read_to_string(&user_path)?;
+        for spec in parse_source_rules(&raw, &user_path.display().to_string())? {
+            match merged
+                .iter_mut()
+                .find(|(existing, _)| existing.id == spec.id)
+            {
+                Some(slot) => *slot = (spec, "user"),
+                None => merged.push((spec, "user")),
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut rules = Vec::new();
+    for (spec, origin) in merged {
+        if !seen.insert(spec.id.clone()) {
+            return Err(CliError::Config(format!(
+                "duplicate source rule id '{}'",
+                spec.id
+            )));
+        }
+        if spec.disabled {
+            continue;
+        }
+        rules.push(SourceRule::compile(spec, origin, *url_policy)?);
+    }
+    Ok(rules)
+}
+
+/// Per-rule outcome: `fired`, `shadowed` (an earlier rule in the same group fired) or `no_match`.
+fn evaluate_source_rules<'r>(
+    rules: &'r [SourceRule],
+    input: &SourceRuleInput,
+) -> Vec<(&'r SourceRule, &'static str)> {
+    let mut fired_groups: HashSet<&str> = HashSet::new();
+    rules
+        .iter()
+        .map(|rule| {
+            if !rule.matches(input) {
+                return (rule, "no_match");
+            }
+            match rule.spec.group.as_deref() {
+                Some(group) if !fired_groups.insert(group) => (rule, "shadowed"),
+                _ => (rule, "fired"),
+            }
+        })
+        .collect()
+}
+
+fn build_source_candidates(
+    rules: &[SourceRule],
+    input: &SourceRuleInput,
+) -> Vec<SourceCandidateDraft> {
+    let mut out: Vec<SourceCandidateDraft> = Vec::new();
+    for (rule, outcome) in evaluate_source_rules(rules, input) {
+        if outcome != "fired" {
+            continue;
+        }
+        let Some(draft) = rule.render(input) else {
+            continue;
+        };
+        if !out.iter().any(|c| c.url == draft.url) {
+            out.push(draft);
+        }
+    }
+    out
+}
+
+fn encode_query(s: &str) -> String {
+    url::form_urlencoded::byte_serialize(s.as_bytes()).collect()
+}
+
+fn fill_source_url_template(template: &str, input: &SourceRuleInput) -> String {
+    template
+        .replace("{name_query}", &encode_query(input.name))
+        .replace("{name}", &encode_query(input.name))
+        .replace(
+            "{canonical_publisher}",
+            &encode_query(input.canonical_publisher),
+        )
+        .replace("{publisher}", &encode_query(input.publisher))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CanonicalUrl {
+    url: String,
+    host: String,
+    domain: String,
+}
+
+fn public_suffix_list() -> &'static publicsuffix::List {
+    static LIST: OnceLock<publicsuffix::List> = OnceLock::new();
+    LIST.get_or_init(|| {
+        include_str!("public_suffix_list.dat")
+            .parse()
+            .expect("bundled public suffix list is valid")
+    })
+}
+
+/// Registrable domain (eTLD+1) of an ASCII host, e.g. `dl.google.com` -> `google.com`.
+fn registrable_domain(host: &str) -> Option<String> {
+    use publicsuffix::Psl;
+    let domain = public_suffix_list().domain(host.as_bytes())?;
+    if !domain.suffix().is_known() {
+        return None;
+    }
+    std::str::from_utf8(domain.as_bytes())
+        .ok()
+        .map(str::to_string)
+}
+
+/// Decodes escapes of unreserved characters and upper-cases the hex digits of the rest.
+fn normalize_percent_encoding(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(b) if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') => {
+                out.push(b as char);
+                i += 3;
+            }
+            Some(b) => {
+                out.push_str(&format!("%{b:02X}"));
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i] as char);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Parses and canonicalizes a candidate source URL and derives its registrable domain.
+///
+/// Hosts are lower-cased and IDNA-encoded, default ports and fragments are dropped and
+/// percent-encoding is normalized, so cosmetic variants of one URL compare equal.
+fn canonicalize_source_url(
+    raw: &str,
+    policy: &SourceUrlPolicyConfig,
+) -> Result<CanonicalUrl, String> {
+    let mut url =
+        Url::parse(raw.trim()).map_err(|e| format!("'{}' is not a valid URL: {e}", raw.trim()))?;
+    match url.scheme() {
+        "https" => {}
+        "http" if policy.allow_http => {}
+        scheme => return Err(format!("scheme '{scheme}' is not allowed (https required)")),
+    }
+    if (!url.username().is_empty() || url.password().is_some()) && !policy.allow_credentials {
+        return Err("URLs with embedded credentials are not allowed".to_string());
+    }
+    let host = match url.host() {
+        Some(url::Host::Domain(domain)) => domain.trim_end_matches('.').to_string(),
+        Some(_) if policy.allow_ip_literals => url.host_str().unwrap_or_default().to_string(),
+        Some(_) => return Err("IP literal hosts are not allowed".to_string()),
+        None => return Err("URL has no host".to_string()),
+    };
+    if url.host_str() != Some(host.as_str()) {
+        url.set_host(Some(&host))
+            .map_err(|e| format!("invalid host '{host}': {e}"))?;
+    }
+    let domain = match url.host() {
+        Some(url::Host::Domain(_)) => registrable_domain(&host)
+            .ok_or_else(|| format!("'{host}' has no registrable domain"))?,
+        _ => host.clone(),
+    };
+    url.set_fragment(None);
+    let path = normalize_percent_encoding(url.path());
+    url.set_path(&path);
+    if let Some(query) = url.query().map(normalize_percent_encoding) {
+        url.set_query(Some(&query));
+    }
+    Ok(CanonicalUrl {
+        url: url.to_string(),
+        host,
+        domain,
+    })
+}
+
+fn review_status_rank(status: &str) -> u8 {
+    match status {
+        "pending" => 0,
+        "approved" => 1,
+        _ => 2,
+    }
+}
+
+/// Moves everything attached to candidate `drop_id` onto `keep_id` and deletes the duplicate.
+///
+/// The kept row takes the stricter decision of the two (a rejection beats an approval, any
+/// decision beats pending), and the merge is written to the review history.
+fn merge_source_candidate(conn: &Connection, keep_id: i64, drop_id: i64) -> Result<(), CliError> {
+    let (Some(keep), Some(dropped)) = (
+        load_candidate_review_state(conn, keep_id)?,
+        load_candidate_review_state(conn, drop_id)?,
+    ) else {
+        return Ok(());
+    };
+    for table in [
+        "source_review_history",
+        "source_auto_review_decision",
+        "update_operation_history",
+    ] {
+        conn.execute(
+            &format!("UPDATE {table} SET candidate_id = ?1 WHERE candidate_id = ?2"),
+            params![keep_id, drop_id],
+        )?;
+    }
+    conn.execute(
+        "UPDATE OR IGNORE source_registry SET candidate_id = ?1 WHERE candidate_id = ?2",
+        params![keep_id, drop_id],
+    )?;
+    conn.execute(
+        "DELETE FROM source_registry WHERE candidate_id = ?1",
+        params![drop_id],
+    )?;
+    conn.execute(
+        "DELETE FROM source_candidate WHERE id = ?1",
+        params![drop_id],
+    )?;
+
+    let status = if review_status_rank(&dropped.status) > review_status_rank(&keep.status) {
+        dropped.status.as_str()
+    } else {
+        keep.status.as_str()
+    };
+    if status != keep.status {
+        conn.execute(
+            "UPDATE source_candidate SET status = ?1 WHERE id = ?2",
+            params![status, keep_id],
+        )?;
+        if status == "rejected" {
+            conn.execute(
+                "UPDATE source_registry SET status = 'disabled' WHERE candidate_id = ?1 AND status = 'active'",
+                params![keep_id],
+            )?;
+        }
+    }
+    let note = format!(
+        "merged duplicate candidate {drop_id} ({}, {})",
+        dropped.url, dropped.status
+    );
+    record_source_review(conn, &keep, status, "url-canonicalize", &note, None)
+}
+
+/// Rewrites stored candidates to their canonical URL, collapsing rows that become duplicates.
+///
+/// Candidates are canonicalized when written, so this only runs once, as a migration for rows
+/// stored before that. A reviewed row wins over a pending one; otherwise the older row is kept.
+/// Returns `(canonicalized, collapsed)`.
+fn canonicalize_source_candidates(
+    tx: &Connection,
+    policy: &SourceUrlPolicyConfig,
+) -> Result<(i64, i64), CliError> {
+    let rows = {
+        let mut stmt = tx.prepare(
+            "SELECT id, software_id, url, domain, status FROM source_candidate ORDER BY id ASC",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    let (mut canonicalized, mut collapsed) = (0_i64, 0_i64);
+    for (id, software_id, url, domain, status) in rows {
+        let Ok(canonical) = canonicalize_source_url(&url, policy) else {
+            continue;
+        };
+        if canonical.url == url && canonical.domain == domain {
+            continue;
+        }
+        let existing: Option<(i64, String)> = tx
+            .query_row(
+                "SELECT id, status FROM source_candidate WHERE software_id = ?1 AND url = ?2 AND id != ?3",
+                params![software_id, canonical.url, id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        if let Some((other_id, other_status)) = existing {
+            collapsed += 1;
+            if other_status != "pending" || status == "pending" {
+                merge_source_candidate(tx, other_id, id)?;
+                continue;
+            }
+            merge_source_candidate(tx, id, other_id)?;
+        }
+        tx.execute(
+            "UPDATE source_candidate SET url = ?1, domain = ?2 WHERE id = ?3",
+            params![canonical.url, canonical.domain, id],
+        )?;
+        tx.execute(
+            "UPDATE source_registry SET url = ?1, domain = ?2 WHERE candidate_id = ?3",
+            params![canonical.url, canonical.domain, id],
         )?;
+        canonicalized += 1;
     }
-    if !columns.iter().any(|c| c == "execution_ticket") {
-        conn.execute(
-            "ALTER TABLE cleanup_operation_history ADD COLUMN execution_ticket TEXT NOT NULL DEFAULT ''",
-            [],
-        )?;
+    Ok((canonicalized, collapsed))
+}
+
+fn make_fingerprint(name: &str, publisher: &str, install_location: &str) -> String {
+    let name = normalize(name);
+    let publisher = normalize(publisher);
+    let install_location = normalize(install_location);
+    if name.is_empty() {
+        return String::new();
     }
-    Ok(())
+    format!("{name}|{publisher}|{install_location}")
 }
 
-fn ensure_download_history_columns(conn: &Connection) -> Result<(), CliError> {
-    let mut stmt = conn.prepare("PRAGMA table_info(download_job_history)")?;
-    let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
-    let columns: Vec<String> = rows.collect::<Result<Vec<_>, _>>()?;
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase()
+}
 
-    if !columns.iter().any(|c| c == "hash_status") {
-        conn.execute(
-            "ALTER TABLE download_job_history ADD COLUMN hash_status TEXT NOT NULL DEFAULT 'not_started'",
-            [],
-        )?;
-    }
-    if !columns.iter().any(|c| c == "signature_status") {
-        conn.execute(
-            "ALTER TABLE download_job_history ADD COLUMN signature_status TEXT NOT NULL DEFAULT 'not_started'",
-            [],
-        )?;
+const PUBLISHER_SUFFIXES: &[&str] = &[
+    "inc",
+    "incorporated",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "ltd",
+    "limited",
+    "llc",
+    "gmbh",
+    "ag",
+    "sa",
+    "sro",
+    "bv",
+    "plc",
+    "pty",
+];
+
+const BUILTIN_PUBLISHER_ALIASES: &[(&str, &str)] = &[
+    ("microsoft", "Microsoft"),
+    ("google", "Google"),
+    ("mozilla", "Mozilla"),
+    ("mozilla foundation", "Mozilla"),
+    ("jetbrains", "JetBrains"),
+    ("adobe", "Adobe"),
+    ("adobe systems", "Adobe"),
+    ("oracle", "Oracle"),
+    ("oracle america", "Oracle"),
+    ("the git development community", "Git"),
+    ("git development community", "Git"),
+    ("python software foundation", "Python Software Foundation"),
+    ("docker", "Docker"),
+    ("github", "GitHub"),
+    ("nvidia", "NVIDIA"),
+    ("intel", "Intel"),
+    ("advanced micro devices", "AMD"),
+    ("amd", "AMD"),
+    ("zoom video communications", "Zoom"),
+    ("videolan", "VideoLAN"),
+    ("igor pavlov", "Igor Pavlov"),
+];
+
+/// Lowercased publisher with punctuation folded and trailing legal suffixes removed.
+fn publisher_key(raw: &str) -> String {
+    let folded: String = raw
+        .to_lowercase()
+        .chars()
+        .filter(|c| *c != '.')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let mut words: Vec<&str> = folded.split_whitespace().collect();
+    while words.len() > 1 && PUBLISHER_SUFFIXES.contains(words.last().unwrap_or(&"")) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+fn canonical_publisher(raw: &str, aliases: &HashMap<String, String>) -> String {
+    if let Some(canonical) = aliases.get(&publisher_key(raw)) {
+        return canonical.clone();
+    }
+    strip_publisher_suffix(raw)
+}
+
+fn strip_publisher_suffix(raw: &str) -> String {
+    let mut words: Vec<&str> = raw.split_whitespace().collect();
+    while words.len() > 1 {
+        let last: String = words
+            .last()
+            .unwrap_or(&"")
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        if !PUBLISHER_SUFFIXES.contains(&last.as_str()) {
+            break;
+        }
+        words.pop();
     }
-    if !columns.iter().any(|c| c == "source_policy_status") {
-        conn.execute(
-            "ALTER TABLE download_job_history ADD COLUMN source_policy_status TEXT NOT NULL DEFAULT 'not_started'",
-            [],
-        )?;
+    words.join(" ").trim_end_matches(',').to_string()
+}
+
+fn is_version_token(token: &str) -> bool {
+    let bare = token.trim_start_matches(['v', 'V']);
+    if !bare.is_empty()
+        && bare.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && bare
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == '-')
+    {
+        return true;
     }
-    Ok(())
+    matches!(
+        token.to_lowercase().as_str(),
+        "x64" | "x86" | "x86_64" | "amd64" | "arm64" | "64-bit" | "32-bit" | "64bit" | "32bit"
+    )
 }
 
-#[derive(Debug, Clone)]
-struct SourceCandidateDraft {
-    url: String,
-    domain: String,
-    confidence: i64,
-    reason: String,
+/// Product name with version numbers, years and architecture markers removed.
+fn strip_version_tokens(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if matches!(c, '(' | ')' | '[' | ']' | ',') {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    let kept: Vec<String> = cleaned
+        .split_whitespace()
+        .filter(|token| !is_version_token(token))
+        .map(|token| token.trim_matches('-').to_lowercase())
+        .filter(|token| !token.is_empty())
+        .collect();
+    if kept.is_empty() {
+        return normalize(name);
+    }
+    kept.join(" ")
+}
+
+/// Stable key for one product line. The MSI UpgradeCode survives major upgrades that change
+/// the ProductCode, so it is preferred; rows without either fall back to name and publisher.
+fn make_identity_key(
+    name: &str,
+    canonical_publisher: &str,
+    product_code: Option<&str>,
+    upgrade_code: Option<&str>,
+) -> String {
+    if let Some(code) = upgrade_code.map(str::trim).filter(|c| !c.is_empty()) {
+        return format!("upgrade:{}", code.to_lowercase());
+    }
+    if let Some(code) = product_code.map(str::trim).filter(|c| !c.is_empty()) {
+        return format!("product:{}", code.to_lowercase());
+    }
+    format!(
+        "name:{}|{}",
+        strip_version_tokens(name),
+        publisher_key(canonical_publisher)
+    )
 }
 
-fn build_source_candidates(name: &str, publisher: &str) -> Vec<SourceCandidateDraft> {
-    let mut out = Vec::new();
-    let q = encode_query(name);
-    out.push(SourceCandidateDraft {
-        url: format!("https://winget.run/search?query={q}"),
-        domain: "winget.run".to_string(),
-        confidence: 55,
-        reason: "default winget search heuristic".to_string(),
-    });
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VersionSegment {
+    Num(u64),
+    Text(String),
+}
 
-    let pub_l = publisher.to_lowercase();
-    let name_l = name.to_lowercase();
-    if pub_l.contains("microsoft") || name_l.contains("visual studio") || name_l.contains(".net") {
-        out.push(SourceCandidateDraft {
-            url: "https://github.com/microsoft/winget-pkgs".to_string(),
-            domain: "github.com".to_string(),
-            confidence: 72,
-            reason: "publisher/name matched microsoft heuristic".to_string(),
-        });
-    } else if pub_l.contains("jetbrains") {
-        out.push(SourceCandidateDraft {
-            url: "https://www.jetbrains.com/toolbox-app/".to_string(),
-            domain: "jetbrains.com".to_string(),
-            confidence: 70,
-            reason: "publisher matched jetbrains heuristic".to_string(),
-        });
-    } else if name_l.contains("git") {
-        out.push(SourceCandidateDraft {
-            url: "https://git-scm.com/download/win".to_string(),
-            domain: "git-scm.com".to_string(),
-            confidence: 68,
-            reason: "name matched git heuristic".to_string(),
-        });
+fn version_segments(version: &str) -> Vec<VersionSegment> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut current_is_digit = false;
+    for c in version.trim().chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                out.push(finish_version_segment(&current, current_is_digit));
+                current.clear();
+            }
+            continue;
+        }
+        let is_digit = c.is_ascii_digit();
+        if !current.is_empty() && is_digit != current_is_digit {
+            out.push(finish_version_segment(&current, current_is_digit));
+            current.clear();
+        }
+        current_is_digit = is_digit;
+        current.push(c.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        out.push(finish_version_segment(&current, current_is_digit));
     }
-
     out
 }
 
-fn encode_query(s: &str) -> String {
-    let mut out = String::new();
-    for b in s.as_bytes() {
-        let c = *b as char;
-        if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '~' {
-            out.push(c);
-        } else if c == ' ' {
-            out.push('+');
-        } else {
-            out.push_str(&format!("%{:02X}", *b));
+fn finish_version_segment(raw: &str, is_digit: bool) -> VersionSegment {
+    if is_digit {
+        if let Ok(n) = raw.parse::<u64>() {
+            return VersionSegment::Num(n);
         }
     }
-    out
+    VersionSegment::Text(raw.to_string())
+}
+
+// Numeric segments compare numerically, missing numeric segments count as 0
+// ("1.0" == "1.0.0") and a trailing text segment marks a pre-release
+// ("1.0-beta" < "1.0").
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    let left = version_segments(a);
+    let right = version_segments(b);
+    for i in 0..left.len().max(right.len()) {
+        let ord = match (left.get(i), right.get(i)) {
+            (Some(VersionSegment::Num(x)), Some(VersionSegment::Num(y))) => x.cmp(y),
+            (Some(VersionSegment::Num(_)), Some(VersionSegment::Text(_))) => Ordering::Greater,
+            (Some(VersionSegment::Text(_)), Some(VersionSegment::Num(_))) => Ordering::Less,
+            (Some(VersionSegment::Text(x)), Some(VersionSegment::Text(y))) => x.cmp(y),
+            (Some(VersionSegment::Num(x)), None) => x.cmp(&0),
+            (None, Some(VersionSegment::Num(y))) => 0.cmp(y),
+            (Some(VersionSegment::Text(_)), None) => Ordering::Less,
+            (None, Some(VersionSegment::Text(_))) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    Ordering::Equal
 }
 
-fn make_fingerprint(name: &str, publisher: &str, install_location: &str) -> String {
-    let name = normalize(name);
-    let publisher = normalize(publisher);
-    let install_location = normalize(install_location);
-    if name.is_empty() {
-        return String::new();
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VersionComparator {
+    Exact(String),
+    Prefix(Vec<VersionSegment>),
+    AtLeast(String, bool),
+    AtMost(String, bool),
+}
+
+/// Parses comma-separated clauses such as `=1.2.3`, `1.2.*`, `>=1.2,<2`, `~1.4` or `^3`.
+fn parse_version_constraint(raw: &str) -> Result<Vec<VersionComparator>, CliError> {
+    let invalid = || CliError::Usage(format!("invalid version constraint '{raw}'"));
+    let mut out = Vec::new();
+    for clause in raw.split(',').map(str::trim) {
+        if clause.is_empty() {
+            return Err(invalid());
+        }
+        let (op, version) = ["==", ">=", "<=", "=", ">", "<", "~", "^"]
+            .iter()
+            .find_map(|op| clause.strip_prefix(op).map(|rest| (*op, rest.trim())))
+            .unwrap_or(("", clause));
+        let wildcard = version.ends_with(".*") || version.ends_with(".x") || version == "*";
+        let base = version.trim_end_matches(['*', 'x']).trim_end_matches('.');
+        let segments = version_segments(base);
+        // A bare `*` only makes sense as an exact wildcard; `~*`/`^*` have no prefix to keep.
+        if segments.is_empty() && !(version == "*" && matches!(op, "" | "=" | "==")) {
+            return Err(invalid());
+        }
+        match op {
+            "" | "=" | "==" if wildcard => out.push(VersionComparator::Prefix(segments)),
+            "" | "=" | "==" => out.push(VersionComparator::Exact(base.to_string())),
+            ">=" => out.push(VersionComparator::AtLeast(base.to_string(), true)),
+            ">" => out.push(VersionComparator::AtLeast(base.to_string(), false)),
+            "<=" => out.push(VersionComparator::AtMost(base.to_string(), true)),
+            "<" => out.push(VersionComparator::AtMost(base.to_string(), false)),
+            "~" | "^" => {
+                let keep = if op == "^" { 1 } else { 2.min(segments.len()) };
+                out.push(VersionComparator::AtLeast(base.to_string(), true));
+                out.push(VersionComparator::Prefix(segments[..keep].to_vec()));
+            }
+            _ => return Err(invalid()),
+        }
     }
-    format!("{name}|{publisher}|{install_location}")
+    Ok(out)
 }
 
-fn normalize(value: &str) -> String {
-    value.trim().to_lowercase()
+fn version_satisfies(version: &str, constraint: &[VersionComparator]) -> bool {
+    use std::cmp::Ordering;
+    constraint.iter().all(|comparator| match comparator {
+        VersionComparator::Exact(v) => compare_versions(version, v) == Ordering::Equal,
+        VersionComparator::Prefix(prefix) => {
+            let segments = version_segments(version);
+            segments.len() >= prefix.len() && segments[..prefix.len()] == prefix[..]
+        }
+        VersionComparator::AtLeast(v, inclusive) => match compare_versions(version, v) {
+            Ordering::Greater => true,
+            Ordering::Equal => *inclusive,
+            Ordering::Less => false,
+        },
+        VersionComparator::AtMost(v, inclusive) => match compare_versions(version, v) {
+            Ordering::Less => true,
+            Ordering::Equal => *inclusive,
+            Ordering::Greater => false,
+        },
+    })
 }
 
 #[cfg(target_os = "windows")]
@@ -4902,16 +14182,45 @@ $paths = @(
   'HKCU:\Software\Microsoft\Windows\CurrentVersion\Uninstall\*'
 )
 
+# Windows Installer keeps UpgradeCode -> ProductCode links under packed GUIDs, where each
+# group of the GUID is stored with its hex digits reversed (the last two byte-wise).
+function Expand-PackedGuid([string]$packed) {
+  if ($packed -notmatch '^[0-9A-Fa-f]{32}$') { return $null }
+  $flip = { param($s) -join ($s.ToCharArray()[($s.Length - 1)..0]) }
+  $tail = (16, 18, 20, 22, 24, 26, 28, 30 | ForEach-Object { & $flip $packed.Substring($_, 2) }) -join ''
+  $guid = (& $flip $packed.Substring(0, 8)) + '-' + (& $flip $packed.Substring(8, 4)) + '-' +
+    (& $flip $packed.Substring(12, 4)) + '-' + $tail.Substring(0, 4) + '-' + $tail.Substring(4)
+  '{' + $guid.ToUpper() + '}'
+}
+
+$upgradeCodes = @{}
+Get-ChildItem -Path 'HKLM:\Software\Classes\Installer\UpgradeCodes' -ErrorAction SilentlyContinue |
+  ForEach-Object {
+    $upgrade = Expand-PackedGuid $_.PSChildName
+    foreach ($packedProduct in $_.GetValueNames()) {
+      $product = Expand-PackedGuid $packedProduct
+      if ($upgrade -and $product) { $upgradeCodes[$product] = $upgrade }
+    }
+  }
+
 $items = foreach ($p in $paths) {
   Get-ItemProperty -Path $p -ErrorAction SilentlyContinue |
     Where-Object { $_.DisplayName -and $_.DisplayName.Trim() -ne '' } |
     ForEach-Object {
+      $productCode = if ($_.PSChildName -match '^\{[0-9A-Fa-f-]{36}\}$') { [string]$_.PSChildName } else { $null }
       [PSCustomObject]@{
         name = [string]$_.DisplayName
         version = [string]$_.DisplayVersion
         publisher = [string]$_.Publisher
         install_location = [string]$_.InstallLocation
         discovery_source = 'registry'
+        uninstall_string = if ($_.UninstallString) { [string]$_.UninstallString } else { $null }
+        quiet_uninstall_string = if ($_.QuietUninstallString) { [string]$_.QuietUninstallString } else { $null }
+        install_date = if ($_.InstallDate) { [string]$_.InstallDate } else { $null }
+        estimated_size_kb = if ($_.EstimatedSize) { [int64]$_.EstimatedSize } else { $null }
+        product_code = $productCode
+        upgrade_code = if ($productCode -and $upgradeCodes.ContainsKey($productCode)) { $upgradeCodes[$productCode] } else { $null }
+        install_scope = if ($_.PSPath -like '*HKEY_LOCAL_MACHINE*') { 'machine' } else { 'user' }
       }
     }
 }
@@ -4935,26 +14244,86 @@ $items | ConvertTo-Json -Compress
         )));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if stdout.is_empty() || stdout == "null" {
+    parse_discovered_software(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn discover_registry_software() -> Result<Vec<DiscoveredSoftware>, CliError> {
+    Ok(Vec::new())
+}
+
+/// Test hook: replaces registry discovery with the JSON file named by `SYNORA_DISCOVERY_FIXTURE`.
+/// Only debug builds and builds with the `discovery-fixture` feature honour it.
+#[cfg(any(debug_assertions, feature = "discovery-fixture"))]
+fn discovery_fixture_path() -> Option<PathBuf> {
+    env::var_os("SYNORA_DISCOVERY_FIXTURE").map(PathBuf::from)
+}
+
+#[cfg(not(any(debug_assertions, feature = "discovery-fixture")))]
+fn discovery_fixture_path() -> Option<PathBuf> {
+    None
+}
+
+#[cfg_attr(
+    not(any(debug_assertions, feature = "discovery-fixture")),
+    allow(dead_code)
+)]
+fn load_discovery_fixture(path: &Path) -> Result<Vec<DiscoveredSoftware>, CliError> {
+    let raw = fs::read_to_string(path).map_err(|e| {
+        CliError::Integration(format!(
+            "failed to read discovery fixture {}: {e}",
+            path.display()
+        ))
+    })?;
+    parse_discovered_software(&raw)
+}
+
+fn parse_discovered_software(raw: &str) -> Result<Vec<DiscoveredSoftware>, CliError> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw == "null" {
         return Ok(Vec::new());
     }
 
-    let value: serde_json::Value = serde_json::from_str(&stdout)
+    let value: serde_json::Value = serde_json::from_str(raw)
         .map_err(|e| CliError::Integration(format!("invalid discovery json: {e}")))?;
-    if value.is_array() {
+    let items: Vec<DiscoveredSoftware> = if value.is_array() {
         serde_json::from_value(value)
-            .map_err(|e| CliError::Integration(format!("invalid discovery shape: {e}")))
+            .map_err(|e| CliError::Integration(format!("invalid discovery shape: {e}")))?
     } else {
         let one: DiscoveredSoftware = serde_json::from_value(value)
             .map_err(|e| CliError::Integration(format!("invalid discovery row: {e}")))?;
-        Ok(vec![one])
-    }
+        vec![one]
+    };
+    Ok(items.into_iter().map(clean_discovered_software).collect())
 }
 
-#[cfg(not(target_os = "windows"))]
-fn discover_registry_software() -> Result<Vec<DiscoveredSoftware>, CliError> {
-    Ok(Vec::new())
+fn clean_discovered_software(mut item: DiscoveredSoftware) -> DiscoveredSoftware {
+    item.uninstall_string = non_empty(item.uninstall_string);
+    item.quiet_uninstall_string = non_empty(item.quiet_uninstall_string);
+    item.install_date = non_empty(item.install_date).map(|v| normalize_install_date(&v));
+    item.estimated_size_kb = item.estimated_size_kb.filter(|v| *v > 0);
+    item.product_code = non_empty(item.product_code).map(|v| v.to_uppercase());
+    item.upgrade_code = non_empty(item.upgrade_code).map(|v| v.to_uppercase());
+    item.install_scope = non_empty(item.install_scope)
+        .map(|v| v.to_lowercase())
+        .filter(|v| v == "machine" || v == "user");
+    item.license = non_empty(item.license);
+    item
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn normalize_install_date(raw: &str) -> String {
+    // Uninstall keys store InstallDate as YYYYMMDD; keep anything else verbatim.
+    if raw.len() == 8 && raw.chars().all(|c| c.is_ascii_digit()) {
+        format!("{}-{}-{}", &raw[0..4], &raw[4..6], &raw[6..8])
+    } else {
+        raw.to_string()
+    }
 }
 
 fn synora_home() -> Result<PathBuf, CliError> {
@@ -5026,6 +14395,8 @@ mod tests {
     fn validate_update_apply_flags_rejects_invalid_combinations() {
         let err = validate_update_apply_flags(&UpdateApplyArgs {
             candidate_id: 1,
+            target_version: None,
+            override_pin: false,
             dry_run: true,
             confirm: true,
             execution_ticket: None,
@@ -5038,6 +14409,8 @@ mod tests {
 
         let err = validate_update_apply_flags(&UpdateApplyArgs {
             candidate_id: 1,
+            target_version: None,
+            override_pin: false,
             dry_run: false,
             confirm: true,
             execution_ticket: None,
@@ -5050,6 +14423,8 @@ mod tests {
 
         let err = validate_update_apply_flags(&UpdateApplyArgs {
             candidate_id: 1,
+            target_version: None,
+            override_pin: false,
             dry_run: false,
             confirm: true,
             execution_ticket: None,
@@ -5140,6 +14515,530 @@ mod tests {
         assert!(matches!(err, CliError::Usage(_)));
     }
 
+    #[test]
+    fn parse_discovered_software_keeps_missing_fields_null() {
+        let items = parse_discovered_software(
+            r#"{"name":"Foo","version":"1.0","publisher":"Acme","install_location":"","discovery_source":"registry","uninstall_string":" ","install_date":"20240115","product_code":"{ab12cd34-0000-0000-0000-000000000000}","install_scope":"machine"}"#,
+        )
+        .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].uninstall_string, None);
+        assert_eq!(items[0].estimated_size_kb, None);
+        assert_eq!(items[0].install_date.as_deref(), Some("2024-01-15"));
+        assert_eq!(
+            items[0].product_code.as_deref(),
+            Some("{AB12CD34-0000-0000-0000-000000000000}")
+        );
+        assert_eq!(items[0].install_scope.as_deref(), Some("machine"));
+    }
+
+    #[test]
+    fn compare_versions_orders_numeric_and_prerelease_segments() {
+        use std::cmp::Ordering;
+        assert_eq!(
+            compare_versions("120.0.6099.71", "121.0.6167.85"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2.0-beta1", "2.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.0b2", "2.0b10"), Ordering::Less);
+    }
+
+    #[test]
+    fn identity_key_ignores_version_tokens_and_publisher_suffixes() {
+        assert_eq!(strip_version_tokens("Foo Studio 2023 (x64)"), "foo studio");
+        assert_eq!(strip_version_tokens("Python 3.11.4 (64-bit)"), "python");
+        let aliases = HashMap::new();
+        assert_eq!(
+            make_identity_key(
+                "Foo 2023",
+                &canonical_publisher("Acme, Inc.", &aliases),
+                None,
+                None
+            ),
+            make_identity_key(
+                "Foo 2024",
+                &canonical_publisher("Acme Inc", &aliases),
+                None,
+                None
+            )
+        );
+        assert_eq!(
+            make_identity_key("Foo", "Acme", Some("{AB12}"), None),
+            "product:{ab12}"
+        );
+        assert_eq!(
+            make_identity_key("Foo", "Acme", Some("{AB12}"), Some("{CD34}")),
+            "upgrade:{cd34}"
+        );
+    }
+
+    #[test]
+    fn canonical_publisher_folds_legal_suffixes_onto_aliases() {
+        let aliases: HashMap<String, String> = BUILTIN_PUBLISHER_ALIASES
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        for raw in ["Microsoft Corporation", "Microsoft Corp.", "microsoft"] {
+            assert_eq!(canonical_publisher(raw, &aliases), "Microsoft");
+        }
+        assert_eq!(canonical_publisher("Foo Labs GmbH", &aliases), "Foo Labs");
+        assert_eq!(publisher_key("JetBrains s.r.o."), "jetbrains");
+    }
+
+    #[test]
+    fn validate_manual_field_rejects_empty_required_and_control_chars() {
+        assert!(matches!(
+            validate_manual_field("--name", Some("  "), true),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            validate_manual_field("--name", Some("Tool\u{7}"), true),
+            Err(CliError::Usage(_))
+        ));
+        assert_eq!(validate_manual_field("--version", None, false).unwrap(), "");
+    }
+
+    #[test]
+    fn critical_software_is_never_low_risk_or_auto_approvable() {
+        let level = |criticality: Option<&str>, confidence: i64| {
+            let mut facts = RiskFacts::new("source.review");
+            facts.criticality = criticality.map(str::to_string);
+            facts.source_confidence = Some(confidence);
+            evaluate_risk(&facts).level
+        };
+        assert_eq!(level(Some("critical"), 90), "high");
+        assert_eq!(level(Some("high"), 90), "medium");
+        assert_eq!(level(Some("low"), 50), "medium");
+        assert_eq!(level(None, 90), "low");
+        assert!(!is_auto_approvable("low", Some("critical")));
+        assert!(is_auto_approvable("low", None));
+        assert!(matches!(normalize_tag("bad tag"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn export_helpers_format_timestamps_purls_and_uuids() {
+        assert_eq!(format_rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_rfc3339(1_709_210_096), "2024-02-29T12:34:56Z");
+        assert_eq!(
+            derive_purl("deb", "LibSSL3", "3.0.2-0ubuntu1.15").as_deref(),
+            Some("pkg:deb/debian/libssl3@3.0.2-0ubuntu1.15")
+        );
+        assert_eq!(
+            derive_purl("winget", "Git.Git", "2.44.0").as_deref(),
+            Some("pkg:generic/Git.Git@2.44.0?package_manager=winget")
+        );
+        assert_eq!(derive_purl("registry", "Git", "2.44.0"), None);
+        let uuid = random_uuid_v4();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!(is_spdx_license_expression("MIT OR Apache-2.0"));
+        assert!(!is_spdx_license_expression("Proprietary, see EULA"));
+        assert!(
+            !is_spdx_license_expression("Freeware"),
+            "well-formed but not on the SPDX list"
+        );
+        assert_eq!(
+            license_ref_id("Proprietary, see EULA").as_deref(),
+            Some("LicenseRef-Proprietary-see-EULA")
+        );
+        assert_eq!(
+            license_ref_id("(c) 2024"),
+            Some("LicenseRef-c-2024".to_string())
+        );
+        assert_eq!(license_ref_id("© ™"), None);
+    }
+
+    #[test]
+    fn cvss3_base_score_matches_reference_vectors() {
+        assert_eq!(
+            cvss3_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            cvss3_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"),
+            Some(6.1)
+        );
+        assert_eq!(
+            cvss3_base_score("CVSS:3.0/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:N/A:N"),
+            Some(5.5)
+        );
+        assert_eq!(cvss3_base_score("CVSS:3.1/AV:X"), None);
+    }
+
+    #[test]
+    fn osv_ranges_match_versions_between_introduced_and_fixed() {
+        let entry = json!({
+            "id": "OSV-2024-1",
+            "affected": [{
+                "package": {"name": "Foo Tool"},
+                "ranges": [{"type": "ECOSYSTEM", "events": [{"introduced": "0"}, {"fixed": "2.4.1"}]}]
+            }]
+        });
+        let advisory = parse_osv_entry(&entry).unwrap();
+        assert_eq!(advisory.severity, "unknown");
+        let range = &advisory.affected[0];
+        assert!(version_in_range("2.4.0", range));
+        assert!(!version_in_range("2.4.1", range));
+        assert!(vuln_package_matches("foo tool", "", range));
+
+        let entry = json!({
+            "id": "OSV-2024-2",
+            "affected": [{
+                "package": {"name": "Foo Tool"},
+                "ranges": [
+                    {"type": "GIT", "repo": "https://example.com/foo.git", "events": [{"introduced": "0"}, {"fixed": "9f1c2ab"}]},
+                    {"type": "SEMVER", "events": [{"introduced": "1.0.0"}, {"fixed": "1.2.0"}]}
+                ]
+            }]
+        });
+        let advisory = parse_osv_entry(&entry).unwrap();
+        assert_eq!(advisory.affected.len(), 1, "the GIT range is skipped");
+        assert_eq!(advisory.affected[0].fixed_version.as_deref(), Some("1.2.0"));
+        assert!(!version_in_range("3.0", &advisory.affected[0]));
+    }
+
+    #[test]
+    fn eol_helpers_parse_dates_and_match_cycles() {
+        assert_eq!(parse_iso_date("1970-01-01"), Some(0));
+        assert_eq!(parse_iso_date("2024-02-29"), Some(19_782));
+        assert_eq!(parse_iso_date("2024-13-01"), None);
+        assert!(version_in_cycle("3.11.4", "3.11"));
+        assert!(!version_in_cycle("3.1.4", "3.11"));
+        let entry = json!({"cycle": "3.8", "eol": "2024-10-07", "latest": "3.8.20"});
+        let cycle = parse_eol_entry("Python", &entry).unwrap();
+        assert_eq!(cycle.product, "python");
+        assert_eq!(cycle.eol_date.as_deref(), Some("2024-10-07"));
+    }
+
+    #[test]
+    fn auto_review_domain_matching_is_exact_unless_wildcarded() {
+        let list = vec!["jetbrains.com".to_string(), "*.google.com".to_string()];
+        assert!(domain_in_list("JetBrains.com", &list));
+        assert!(!domain_in_list("download.jetbrains.com", &list));
+        assert!(domain_in_list("dl.google.com", &list));
+        assert!(!domain_in_list("google.com.evil.net", &list));
+        assert!(publisher_matches_domain(
+            "JetBrains s.r.o.",
+            "www.jetbrains.com"
+        ));
+        assert!(!publisher_matches_domain(
+            "Microsoft Corporation",
+            "github.com"
+        ));
+        assert!(!publisher_matches_domain(
+            "Microsoft Corporation",
+            "evilmicrosoft.net"
+        ));
+        assert!(!publisher_matches_domain(
+            "Microsoft Corporation",
+            "micro.com"
+        ));
+        assert!(publisher_matches_domain("Foo Labs GmbH", "dl.foo-labs.io"));
+        assert!(publisher_matches_domain("Foo Labs GmbH", "foolabs.com"));
+        assert!(
+            publisher_matches_domain("Foo Labs GmbH", "downloads.foolabs.co.uk"),
+            "label before the public suffix"
+        );
+        assert!(!publisher_matches_domain("Co Uk", "foo.co.uk"));
+        assert!(source_trusted(
+            "https://dl.google.com/chrome.msi",
+            "google.com",
+            &["dl.google.com".to_string()]
+        ));
+        assert!(!source_trusted(
+            "https://sites.google.com/x",
+            "google.com",
+            &["dl.google.com".to_string()]
+        ));
+        assert!(source_untrusted(
+            "https://sites.google.com/x",
+            "google.com",
+            &["sites.google.com".to_string()]
+        ));
+        assert!(source_untrusted(
+            "https://a.evil.net/x",
+            "evil.net",
+            &["evil.net".to_string()]
+        ));
+    }
+
+    #[test]
+    fn default_source_rules_keep_vendor_heuristics_exclusive() {
+        let rules: Vec<SourceRule> = parse_source_rules(DEFAULT_SOURCE_RULES, "defaults")
+            .unwrap()
+            .into_iter()
+            .map(|spec| {
+                SourceRule::compile(spec, "default", SourceUrlPolicyConfig::default()).unwrap()
+            })
+            .collect();
+        let input = SourceRuleInput {
+            name: "Git for Visual Studio",
+            publisher: "Microsoft Corporation",
+            canonical_publisher: "Microsoft",
+            discovery_source: "registry",
+        };
+        let drafts = build_source_candidates(&rules, &input);
+        let ids: Vec<&str> = drafts.iter().map(|d| d.rule_id.as_str()).collect();
+        assert_eq!(ids, vec!["winget-search", "microsoft-winget-pkgs"]);
+        assert_eq!(
+            drafts[0].url,
+            "https://winget.run/search?query=Git+for+Visual+Studio"
+        );
+
+        let outcomes = evaluate_source_rules(&rules, &input);
+        let git = outcomes
+            .iter()
+            .find(|(r, _)| r.spec.id == "git-scm")
+            .unwrap();
+        assert_eq!(git.1, "shadowed");
+    }
+
+    #[test]
+    fn interactive_review_session_requires_notes_and_undoes_buffered_decisions() {
+        let item = |candidate_id: i64, confidence: i64| InteractiveReviewItem {
+            review: CandidateReviewState {
+                candidate_id,
+                software_id: 1,
+                software_name: "Git".to_string(),
+                url: format!("https://git-scm.com/{candidate_id}"),
+                domain: "git-scm.com".to_string(),
+                status: "pending".to_string(),
+                confidence,
+                criticality: None,
+            },
+            reason: "test".to_string(),
+            rule_id: None,
+            score_breakdown: serde_json::Value::Null,
+        };
+        let mut session =
+            InteractiveReviewSession::new(vec![item(1, 80), item(2, 20), item(3, 80)]);
+
+        session.decide("approved").unwrap();
+        assert!(session
+            .decide("rejected")
+            .unwrap_err()
+            .contains("note is required"));
+        assert_eq!(session.current().unwrap().review.candidate_id, 2);
+        session.note = "mirror".to_string();
+        session.decide("rejected").unwrap();
+        session.skip();
+        assert!(session.current().is_none());
+        assert_eq!(session.buffered.len(), 2);
+
+        let undone = session.undo().unwrap();
+        assert_eq!((undone.candidate_id, undone.status), (2, "rejected"));
+        assert_eq!(session.current().unwrap().review.candidate_id, 2);
+        assert_eq!(session.note, "mirror");
+        session.undo().unwrap();
+        assert_eq!(session.position, 0);
+        assert!(session.undo().is_none());
+    }
+
+    #[test]
+    fn source_urls_canonicalize_and_derive_registrable_domains() {
+        let strict = SourceUrlPolicyConfig::default();
+        let canonical = canonicalize_source_url(
+            "HTTPS://DL.Google.COM:443/%7eChrome/%2fsetup%3f?q=a%2cb#top",
+            &strict,
+        )
+        .unwrap();
+        assert_eq!(
+            canonical.url,
+            "https://dl.google.com/~Chrome/%2Fsetup%3F?q=a%2Cb"
+        );
+        assert_eq!(canonical.host, "dl.google.com");
+        assert_eq!(canonical.domain, "google.com");
+        assert_eq!(
+            canonicalize_source_url("https://www.example.co.uk./download", &strict)
+                .unwrap()
+                .domain,
+            "example.co.uk"
+        );
+        let idn = canonicalize_source_url("https://Bücher.example.de/", &strict).unwrap();
+        assert_eq!(idn.host, "xn--bcher-kva.example.de");
+        assert_eq!(idn.domain, "example.de");
+        assert_eq!(
+            canonicalize_source_url("https://example.com:8443/a", &strict)
+                .unwrap()
+                .url,
+            "https://example.com:8443/a"
+        );
+        assert_eq!(encode_query("Zürich 7-Zip"), "Z%C3%BCrich+7-Zip");
+
+        assert!(canonicalize_source_url("http://example.com/", &strict).is_err());
+        assert!(canonicalize_source_url("ftp://example.com/", &strict).is_err());
+        assert!(canonicalize_source_url("https://10.0.0.1/setup.exe", &strict).is_err());
+        assert!(canonicalize_source_url("https://[::1]/setup.exe", &strict).is_err());
+        assert!(canonicalize_source_url("https://user:pw@example.com/", &strict).is_err());
+        assert!(canonicalize_source_url("https://co.uk/", &strict).is_err());
+        assert!(canonicalize_source_url("https://localhost/", &strict).is_err());
+
+        let relaxed = SourceUrlPolicyConfig {
+            allow_http: true,
+            allow_ip_literals: true,
+            allow_credentials: true,
+        };
+        assert_eq!(
+            canonicalize_source_url("http://example.com:80/", &relaxed)
+                .unwrap()
+                .url,
+            "http://example.com/"
+        );
+        assert_eq!(
+            canonicalize_source_url("https://10.0.0.1/", &relaxed)
+                .unwrap()
+                .domain,
+            "10.0.0.1"
+        );
+        assert!(canonicalize_source_url("https://user@example.com/", &relaxed).is_ok());
+    }
+
+    #[test]
+    fn probe_redirect_hops_follow_the_source_url_policy() {
+        let url = |s: &str| Url::parse(s).unwrap();
+        let probe = SourceProbeConfig::default();
+        let strict = SourceUrlPolicyConfig::default();
+        let from = url("https://dl.example.com/setup.exe");
+        assert!(check_probe_target(
+            Some(&from),
+            &url("https://cdn.example.org/setup.exe"),
+            &probe,
+            &strict
+        )
+        .is_ok());
+        let relaxed = SourceUrlPolicyConfig {
+            allow_http: true,
+            allow_ip_literals: true,
+            allow_credentials: false,
+        };
+        let downgrade = check_probe_target(
+            Some(&from),
+            &url("http://dl.example.com/setup.exe"),
+            &probe,
+            &relaxed,
+        );
+        assert!(downgrade.unwrap_err().contains("plain HTTP"));
+        assert!(check_probe_target(
+            Some(&from),
+            &url("https://203.0.113.7/setup.exe"),
+            &probe,
+            &strict
+        )
+        .is_err());
+        for internal in [
+            "https://127.0.0.1/",
+            "https://10.1.2.3/",
+            "https://169.254.169.254/latest/meta-data/",
+            "https://100.64.0.1/",
+            "https://[::1]/",
+            "https://[fd00::1]/",
+            "https://[::ffff:192.168.1.1]/",
+            "https://localhost/",
+            "https://nas.local/",
+            "https://metadata.google.internal/",
+        ] {
+            let err =
+                check_probe_target(Some(&from), &url(internal), &probe, &relaxed).unwrap_err();
+            assert!(err.contains("internal host"), "{internal}: {err}");
+        }
+        let private = SourceProbeConfig {
+            allow_private_hosts: true,
+            ..SourceProbeConfig::default()
+        };
+        assert!(
+            check_probe_target(None, &url("http://localhost:8080/ok"), &private, &strict).is_ok()
+        );
+        assert!(check_probe_target(
+            Some(&from),
+            &url("http://localhost:8080/ok"),
+            &private,
+            &strict
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn probe_resolver_refuses_names_that_resolve_to_internal_addresses() {
+        for netloc in [
+            "127.0.0.1:443",
+            "[::1]:443",
+            "100.127.255.254:80",
+            "localhost:80",
+        ] {
+            let err = resolve_public_only(netloc).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied, "{netloc}");
+        }
+        let public = resolve_public_only("203.0.113.7:443").unwrap();
+        assert_eq!(public, vec!["203.0.113.7:443".parse().unwrap()]);
+        assert!(!is_internal_ip("100.128.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn baseline_evaluation_reports_missing_forbidden_constraint_and_extra() {
+        let definition: BaselineDefinition = serde_yaml::from_str(
+            "required:\n  - name: Google Chrome\n    version: \">=121\"\n  - name: 7-Zip\nforbidden:\n  - name: uTorrent*\noptional:\n  - name: VLC media player\n",
+        )
+        .unwrap();
+        let item = |id, name: &str, version: &str| BaselineSoftware {
+            id,
+            name: name.to_string(),
+            version: version.to_string(),
+            publisher: String::new(),
+        };
+        let inventory = vec![
+            item(1, "Google Chrome", "120.0.1"),
+            item(2, "uTorrent Web", "1.0"),
+            item(3, "VLC media player", "3.0.20"),
+            item(4, "Notepad++ (64-bit x64)", "8.6"),
+        ];
+        let drift = evaluate_baseline(&definition, &inventory).unwrap();
+        let kinds: Vec<(&str, &str)> = drift.iter().map(|d| (d.kind, d.name.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("out_of_constraint", "Google Chrome"),
+                ("missing", "7-Zip"),
+                ("forbidden_present", "uTorrent Web"),
+                ("extra", "Notepad++ (64-bit x64)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn version_constraints_cover_exact_wildcard_range_and_caret() {
+        let exact = parse_version_constraint("=1.2.3").unwrap();
+        assert!(version_satisfies("1.2.3", &exact));
+        assert!(!version_satisfies("1.2.4", &exact));
+        let wildcard = parse_version_constraint("1.2.*").unwrap();
+        assert!(version_satisfies("1.2.9", &wildcard));
+        assert!(!version_satisfies("1.3.0", &wildcard));
+        let range = parse_version_constraint(">=1.2, <2").unwrap();
+        assert!(version_satisfies("1.9.9", &range));
+        assert!(!version_satisfies("2.0", &range));
+        let tilde = parse_version_constraint("~1.4.2").unwrap();
+        assert!(version_satisfies("1.4.7", &tilde));
+        assert!(!version_satisfies("1.5.0", &tilde));
+        let caret = parse_version_constraint("^3.1").unwrap();
+        assert!(version_satisfies("3.9", &caret));
+        assert!(!version_satisfies("4.0", &caret));
+        assert!(parse_version_constraint(">=").is_err());
+        assert!(parse_version_constraint("1.0,,2").is_err());
+        assert!(version_satisfies(
+            "0.1",
+            &parse_version_constraint("*").unwrap()
+        ));
+        assert!(matches!(
+            parse_version_constraint("^*"),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            parse_version_constraint("~*"),
+            Err(CliError::Usage(_))
+        ));
+    }
+
     #[test]
     fn validate_ui_search_args_rejects_empty_query() {
         let err = validate_ui_search_args("  ", 5).unwrap_err();
@@ -0,0 +1,457 @@
+//! Risk evaluation shared by every command that reports or enforces a risk level.
+//!
+//! Commands describe an action as [`RiskFacts`]; [`evaluate`] scores those facts against the
+//! declarative tables below and returns the level together with every rule that fired, so the
+//! same action gets the same rating everywhere and `risk explain` can show why.
+
+use serde_json::json;
+
+/// Scores at or above these thresholds are rated medium / high.
+const MEDIUM_AT: i64 = 30;
+const HIGH_AT: i64 = 60;
+
+struct ActionBase {
+    prefix: &'static str,
+    points: i64,
+    /// Minimum level for the family, so lowering rules can never lift a confirmation gate.
+    floor: Option<&'static str>,
+    reason: &'static str,
+}
+
+/// Base score per action family; the first matching prefix wins.
+const ACTION_BASE: &[ActionBase] = &[
+    ActionBase {
+        prefix: "cleanup.",
+        points: 60,
+        floor: Some("high"),
+        reason: "removes installed software",
+    },
+    ActionBase {
+        prefix: "update.candidate",
+        points: 20,
+        floor: None,
+        reason: "a newer version is available from a reviewed source",
+    },
+    ActionBase {
+        prefix: "update.apply",
+        points: 60,
+        floor: Some("high"),
+        reason: "replaces the installed version",
+    },
+    ActionBase {
+        prefix: "update.",
+        points: 60,
+        floor: Some("high"),
+        reason: "re-runs an update outside the update apply gates",
+    },
+    ActionBase {
+        prefix: "download.retry",
+        points: 60,
+        floor: Some("high"),
+        reason: "re-runs a download",
+    },
+    ActionBase {
+        prefix: "download.",
+        points: 30,
+        floor: Some("medium"),
+        reason: "fetches an installer",
+    },
+    ActionBase {
+        prefix: "baseline.",
+        points: 30,
+        floor: None,
+        reason: "baseline drift needs remediation",
+    },
+    ActionBase {
+        prefix: "source.registry",
+        points: 30,
+        floor: Some("medium"),
+        reason: "changes where updates are downloaded from",
+    },
+    ActionBase {
+        prefix: "source.",
+        points: 15,
+        floor: None,
+        reason: "reviews where updates may be downloaded from",
+    },
+    ActionBase {
+        prefix: "software.lifecycle",
+        points: 30,
+        floor: None,
+        reason: "software is at or near vendor end-of-life",
+    },
+    ActionBase {
+        prefix: "software.",
+        points: 0,
+        floor: None,
+        reason: "read-only software view",
+    },
+    ActionBase {
+        prefix: "ai.repair-plan",
+        points: 30,
+        floor: None,
+        reason: "repair touches local software state",
+    },
+    ActionBase {
+        prefix: "ai.",
+        points: 10,
+        floor: None,
+        reason: "plan-only recommendation",
+    },
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Condition {
+    Criticality(&'static str),
+    SourceConfidenceBelow(i64),
+    SourceUntrusted,
+    SourceHealth(&'static str),
+    Verification(&'static str),
+    Vulnerability(&'static [&'static str]),
+    PriorFailures,
+    OnlyPriorSuccesses,
+    Signal(&'static str),
+}
+
+struct RiskRule {
+    id: &'static str,
+    when: Condition,
+    points: i64,
+    /// Minimum level once the rule fires, whatever the score.
+    floor: Option<&'static str>,
+    reason: &'static str,
+}
+
+const RULES: &[RiskRule] = &[
+    RiskRule {
+        id: "criticality_critical",
+        when: Condition::Criticality("critical"),
+        points: 20,
+        floor: Some("high"),
+        reason: "target software is marked critical",
+    },
+    RiskRule {
+        id: "criticality_high",
+        when: Condition::Criticality("high"),
+        points: 10,
+        floor: Some("medium"),
+        reason: "target software is marked high criticality",
+    },
+    RiskRule {
+        id: "source_low_confidence",
+        when: Condition::SourceConfidenceBelow(70),
+        points: 15,
+        floor: None,
+        reason: "source confidence is below 70",
+    },
+    RiskRule {
+        id: "source_untrusted",
+        when: Condition::SourceUntrusted,
+        points: 40,
+        floor: Some("high"),
+        reason: "source domain is untrusted by the download source policy",
+    },
+    RiskRule {
+        id: "source_broken",
+        when: Condition::SourceHealth("broken"),
+        points: 25,
+        floor: None,
+        reason: "last probe found the source broken",
+    },
+    RiskRule {
+        id: "source_degraded",
+        when: Condition::SourceHealth("degraded"),
+        points: 10,
+        floor: None,
+        reason: "last probe found the source degraded",
+    },
+    RiskRule {
+        id: "verification_failed",
+        when: Condition::Verification("failed"),
+        points: 40,
+        floor: None,
+        reason: "the action or its artifact failed verification",
+    },
+    RiskRule {
+        id: "verification_passed",
+        when: Condition::Verification("verified"),
+        points: -10,
+        floor: None,
+        reason: "the action or its artifact passed verification",
+    },
+    RiskRule {
+        id: "vulnerability_severe",
+        when: Condition::Vulnerability(&["critical", "high"]),
+        points: 40,
+        floor: None,
+        reason: "known critical or high severity vulnerabilities",
+    },
+    RiskRule {
+        id: "vulnerability_other",
+        when: Condition::Vulnerability(&["medium", "low", "unknown"]),
+        points: 15,
+        floor: None,
+        reason: "known vulnerabilities",
+    },
+    RiskRule {
+        id: "prior_failures",
+        when: Condition::PriorFailures,
+        points: 20,
+        floor: None,
+        reason: "earlier attempts for this target failed",
+    },
+    RiskRule {
+        id: "prior_successes",
+        when: Condition::OnlyPriorSuccesses,
+        points: -5,
+        floor: None,
+        reason: "earlier attempts for this target succeeded",
+    },
+    RiskRule {
+        id: "forbidden_present",
+        when: Condition::Signal("forbidden_present"),
+        points: 30,
+        floor: None,
+        reason: "a package forbidden by the baseline is installed",
+    },
+    RiskRule {
+        id: "data_loss_reported",
+        when: Condition::Signal("data_loss_reported"),
+        points: 30,
+        floor: None,
+        reason: "the issue mentions crashes, data loss or corruption",
+    },
+    RiskRule {
+        id: "past_eol",
+        when: Condition::Signal("past_eol"),
+        points: 30,
+        floor: None,
+        reason: "software is past vendor end-of-life",
+    },
+    RiskRule {
+        id: "large_inventory",
+        when: Condition::Signal("large_inventory"),
+        points: 20,
+        floor: None,
+        reason: "the active software set is large",
+    },
+    RiskRule {
+        id: "system_components",
+        when: Condition::Signal("system_components"),
+        points: 20,
+        floor: None,
+        reason: "recommended software installs drivers or system components",
+    },
+];
+
+/// What is known about an action when its risk is evaluated; unknown facts stay empty.
+#[derive(Debug, Clone, Default)]
+pub(super) struct RiskFacts {
+    /// Action family, i.e. the part of an action id before `:` such as `update.apply`.
+    pub(super) action: String,
+    pub(super) criticality: Option<String>,
+    pub(super) source_confidence: Option<i64>,
+    pub(super) source_untrusted: bool,
+    pub(super) source_health: Option<String>,
+    /// `verified` or `failed` once the action or its artifact has been checked.
+    pub(super) verification: Option<String>,
+    pub(super) vulnerability_severity: Option<String>,
+    pub(super) prior_failures: i64,
+    pub(super) prior_successes: i64,
+    /// Command-specific observations such as `forbidden_present`.
+    pub(super) signals: Vec<&'static str>,
+}
+
+impl RiskFacts {
+    pub(super) fn new(action: &str) -> Self {
+        Self {
+            action: action.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub(super) fn to_json(&self) -> serde_json::Value {
+        json!({
+            "action": self.action,
+            "criticality": self.criticality,
+            "source_confidence": self.source_confidence,
+            "source_untrusted": self.source_untrusted,
+            "source_health": self.source_health,
+            "verification": self.verification,
+            "vulnerability_severity": self.vulnerability_severity,
+            "prior_failures": self.prior_failures,
+            "prior_successes": self.prior_successes,
+            "signals": self.signals
+        })
+    }
+}
+
+impl Condition {
+    fn holds(self, facts: &RiskFacts) -> bool {
+        match self {
+            Self::Criticality(level) => facts.criticality.as_deref() == Some(level),
+            Self::SourceConfidenceBelow(min) => facts.source_confidence.is_some_and(|c| c < min),
+            Self::SourceUntrusted => facts.source_untrusted,
+            Self::SourceHealth(health) => facts.source_health.as_deref() == Some(health),
+            Self::Verification(state) => facts.verification.as_deref() == Some(state),
+            Self::Vulnerability(severities) => facts
+                .vulnerability_severity
+                .as_deref()
+                .is_some_and(|s| severities.contains(&s)),
+            Self::PriorFailures => facts.prior_failures > 0,
+            Self::OnlyPriorSuccesses => facts.prior_failures == 0 && facts.prior_successes > 0,
+            Self::Signal(signal) => facts.signals.contains(&signal),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct RiskReason {
+    pub(super) rule: &'static str,
+    pub(super) points: i64,
+    pub(super) detail: String,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct RiskAssessment {
+    pub(super) level: &'static str,
+    pub(super) score: i64,
+    pub(super) reasons: Vec<RiskReason>,
+}
+
+impl RiskAssessment {
+    pub(super) fn reasons_json(&self) -> serde_json::Value {
+        json!(self
+            .reasons
+            .iter()
+            .map(|r| json!({"rule": r.rule, "points": r.points, "detail": r.detail}))
+            .collect::<Vec<_>>())
+    }
+}
+
+fn level_rank(level: &str) -> u8 {
+    match level {
+        "low" => 0,
+        "medium" => 1,
+        _ => 2,
+    }
+}
+
+pub(super) fn evaluate(facts: &RiskFacts) -> RiskAssessment {
+    let (base, base_floor, base_detail) = ACTION_BASE
+        .iter()
+        .find(|base| facts.action.starts_with(base.prefix))
+        .map_or((0, None, "unclassified action"), |base| {
+            (base.points, base.floor, base.reason)
+        });
+    let mut reasons = vec![RiskReason {
+        rule: "action_base",
+        points: base,
+        detail: format!("{}: {base_detail}", facts.action),
+    }];
+    let mut floor = base_floor.unwrap_or("low");
+    for rule in RULES.iter().filter(|rule| rule.when.holds(facts)) {
+        if let Some(rule_floor) = rule.floor {
+            if level_rank(rule_floor) > level_rank(floor) {
+                floor = rule_floor;
+            }
+        }
+        reasons.push(RiskReason {
+            rule: rule.id,
+            points: rule.points,
+            detail: rule.reason.to_string(),
+        });
+    }
+
+    let score = reasons.iter().map(|r| r.points).sum::<i64>().clamp(0, 100);
+    let by_score = if score >= HIGH_AT {
+        "high"
+    } else if score >= MEDIUM_AT {
+        "medium"
+    } else {
+        "low"
+    };
+    let level = if level_rank(floor) > level_rank(by_score) {
+        floor
+    } else {
+        by_score
+    };
+    RiskAssessment {
+        level,
+        score,
+        reasons,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_add_up_and_floors_raise_the_level() {
+        let mut facts = RiskFacts::new("update.candidate");
+        facts.source_confidence = Some(90);
+        let quiet = evaluate(&facts);
+        assert_eq!((quiet.level, quiet.score), ("low", 20));
+        assert_eq!(quiet.reasons.len(), 1);
+
+        facts.vulnerability_severity = Some("high".to_string());
+        facts.source_confidence = Some(55);
+        let vulnerable = evaluate(&facts);
+        assert_eq!((vulnerable.level, vulnerable.score), ("high", 75));
+        let rules: Vec<&str> = vulnerable.reasons.iter().map(|r| r.rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                "action_base",
+                "source_low_confidence",
+                "vulnerability_severe"
+            ]
+        );
+
+        let mut view = RiskFacts::new("software.show");
+        view.criticality = Some("critical".to_string());
+        let critical = evaluate(&view);
+        assert_eq!(
+            (critical.level, critical.score),
+            ("high", 20),
+            "the floor wins over a low score"
+        );
+
+        let mut retry = RiskFacts::new("download.retry");
+        retry.verification = Some("verified".to_string());
+        retry.prior_failures = 0;
+        retry.prior_successes = 3;
+        assert_eq!(evaluate(&retry).score, 45);
+        assert_eq!(
+            evaluate(&retry).level,
+            "high",
+            "the family floor survives lowering rules"
+        );
+        assert_eq!(evaluate(&RiskFacts::new("unknown.thing")).level, "low");
+    }
+
+    #[test]
+    fn gated_action_families_stay_high_or_medium() {
+        for action in [
+            "update.apply",
+            "update.history",
+            "download.retry",
+            "cleanup.apply",
+        ] {
+            let mut facts = RiskFacts::new(action);
+            facts.source_confidence = Some(95);
+            facts.verification = Some("verified".to_string());
+            facts.prior_successes = 5;
+            assert_eq!(evaluate(&facts).level, "high", "{action}");
+        }
+        for action in ["source.registry", "download.show"] {
+            assert_eq!(
+                evaluate(&RiskFacts::new(action)).level,
+                "medium",
+                "{action}"
+            );
+        }
+        assert_eq!(evaluate(&RiskFacts::new("source.review")).level, "low");
+    }
+}
@@ -0,0 +1,3528 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection};
+use serde_json::Value;
+
+fn unique_home() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let pid = std::process::id();
+    let dir = std::env::temp_dir().join(format!("synora-e2e-inv-{pid}-{nanos}"));
+    fs::create_dir_all(&dir).expect("failed to create temp home");
+    dir
+}
+
+fn write_fixture(home: &Path, items: &Value) -> PathBuf {
+    let path = home.join("discovery-fixture.json");
+    fs::write(&path, serde_json::to_string(items).expect("fixture json")).expect("write fixture");
+    path
+}
+
+fn run_synora(home: &Path, args: &[&str]) -> Output {
+    let exe = env!("CARGO_BIN_EXE_synora");
+    Command::new(exe)
+        .args(args)
+        .env("SYNORA_HOME", home)
+        .env(
+            "SYNORA_DISCOVERY_FIXTURE",
+            home.join("discovery-fixture.json"),
+        )
+        .output()
+        .expect("failed to run synora")
+}
+
+fn stdout_json(output: &Output) -> Value {
+    let s = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str::<Value>(s.trim()).expect("stdout should be valid json")
+}
+
+/// Minimal HTTP/1.1 server for source probes; answers until the test process exits.
+fn spawn_probe_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind probe server");
+    let port = listener.local_addr().expect("local addr").port();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+            let mut request_line = String::new();
+            let _ = reader.read_line(&mut request_line);
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) <= 2 {
+                    break;
+                }
+            }
+            let mut parts = request_line.split_whitespace();
+            let (method, path) = (
+                parts.next().unwrap_or_default(),
+                parts.next().unwrap_or_default(),
+            );
+            let (status, headers) = match (method, path) {
+                (_, "/ok") => ("200 OK", "Content-Type: text/html\r\n".to_string()),
+                ("HEAD", "/head-refused") => ("405 Method Not Allowed", String::new()),
+                (_, "/head-refused") => (
+                    "200 OK",
+                    "Content-Type: application/octet-stream\r\n".to_string(),
+                ),
+                (_, "/moved") => ("301 Moved Permanently", "Location: /ok\r\n".to_string()),
+                (_, "/parked") => (
+                    "302 Found",
+                    format!("Location: http://localhost:{port}/ok\r\n"),
+                ),
+                (_, "/loop") => ("302 Found", "Location: /loop\r\n".to_string()),
+                _ => ("404 Not Found", String::new()),
+            };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {status}\r\n{headers}Content-Length: 0\r\nConnection: close\r\n\r\n"
+            );
+        }
+    });
+    port
+}
+
+/// Validates `doc` against an official schema vendored unmodified under tests/fixtures/schemas
+/// by scripts/fetch_sbom_schemas.py. `refs` maps the URLs the schema references to their files.
+fn assert_matches_vendored_schema(doc: &Value, schema: &str, refs: &[(&str, &str)]) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("schemas");
+    let load = |name: &str| {
+        fs::read_to_string(dir.join(name))
+            .ok()
+            .map(|raw| serde_json::from_str::<Value>(&raw).expect("schema json"))
+    };
+    let Some(root) = load(schema) else {
+        eprintln!("skipping {schema} validation: run scripts/fetch_sbom_schemas.py to vendor it");
+        return;
+    };
+    let mut options = jsonschema::JSONSchema::options();
+    for (id, file) in refs {
+        let document = load(file).unwrap_or_else(|| panic!("{file} is referenced by {schema}"));
+        options.with_document(id.to_string(), document);
+    }
+    let validator = options.compile(&root).expect("compile schema");
+    let errors: Vec<String> = match validator.validate(doc) {
+        Ok(()) => return,
+        Err(errors) => errors
+            .map(|e| format!("{}: {e}", e.instance_path))
+            .collect(),
+    };
+    panic!("export does not match {schema}: {errors:?}");
+}
+
+fn registry_item(name: &str, version: &str) -> Value {
+    serde_json::json!({
+        "name": name,
+        "version": version,
+        "publisher": "Google LLC",
+        "install_location": "",
+        "discovery_source": "registry",
+        "install_scope": "machine"
+    })
+}
+
+#[test]
+fn scan_records_version_changes_and_removals() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Google Chrome", "120.0.1")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #1 failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["inserted"], 1);
+
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Google Chrome", "121.0.2")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #2 failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["version_changes"], 1);
+
+    write_fixture(&home, &serde_json::json!([]));
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #3 failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["deactivated"], 1);
+
+    let out = run_synora(
+        &home,
+        &["software", "history", "--software-id", "1", "--json"],
+    );
+    assert!(out.status.success(), "history failed: {:?}", out);
+    let history = stdout_json(&out);
+    let kinds: Vec<&str> = history
+        .as_array()
+        .expect("history array")
+        .iter()
+        .filter_map(|it| it["change_type"].as_str())
+        .collect();
+    assert_eq!(kinds, vec!["removed", "version_changed", "installed"]);
+    assert_eq!(history[1]["old_version"], "120.0.1");
+    assert_eq!(history[1]["new_version"], "121.0.2");
+
+    let out = run_synora(
+        &home,
+        &["software", "list", "--changed-since", "0", "--json"],
+    );
+    assert!(out.status.success(), "list failed: {:?}", out);
+    assert_eq!(stdout_json(&out).as_array().map(|a| a.len()), Some(1));
+}
+
+#[test]
+fn history_rejects_unknown_software() {
+    let home = unique_home();
+    let out = run_synora(
+        &home,
+        &["software", "history", "--software-id", "42", "--json"],
+    );
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn discover_diff_lists_added_removed_and_upgraded() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.1"),
+            registry_item("Foo", "1.0")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #1 failed: {:?}", out);
+    let from = stdout_json(&out)["scan_id"]
+        .as_str()
+        .expect("scan_id")
+        .to_string();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "121.0.2"),
+            registry_item("Bar", "2.0")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #2 failed: {:?}", out);
+    let to = stdout_json(&out)["scan_id"]
+        .as_str()
+        .expect("scan_id")
+        .to_string();
+
+    let out = run_synora(
+        &home,
+        &[
+            "software", "discover", "diff", "--from", &from, "--to", &to, "--json",
+        ],
+    );
+    assert!(out.status.success(), "diff failed: {:?}", out);
+    let diff = stdout_json(&out);
+    assert_eq!(diff["added"][0]["name"], "Bar");
+    assert_eq!(diff["removed"][0]["name"], "Foo");
+    assert_eq!(diff["upgraded"][0]["to_version"], "121.0.2");
+    assert_eq!(diff["summary"]["downgraded"], 0);
+
+    let out = run_synora(
+        &home,
+        &[
+            "software", "discover", "diff", "--from", "missing", "--to", &to, "--json",
+        ],
+    );
+    assert_eq!(out.status.code(), Some(2));
+
+    fs::write(
+        home.join("config.json"),
+        serde_json::json!({
+            "execution": {"real_mutation_enabled": false, "gate_version": "phase3-draft-v1", "approval_record_ref": ""},
+            "retention": {"discovery_snapshots": 0}
+        })
+        .to_string(),
+    )
+    .expect("write config");
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert_eq!(
+        out.status.code(),
+        Some(4),
+        "zero retention is a config error"
+    );
+    let out = run_synora(&home, &["software", "discover", "history", "--json"]);
+    assert_eq!(
+        stdout_json(&out).as_array().map(Vec::len),
+        Some(2),
+        "the rejected scan writes nothing"
+    );
+}
+
+#[test]
+fn renamed_product_keeps_software_id_and_merge_repoints_history() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Foo Studio 2023", "23.1")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #1 failed: {:?}", out);
+
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Foo Studio 2024", "24.0")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #2 failed: {:?}", out);
+    let payload = stdout_json(&out);
+    assert_eq!(payload["inserted"], 0);
+    assert_eq!(payload["identity_matched"], 1);
+    assert_eq!(payload["version_changes"], 1);
+
+    let mut other = registry_item("Foo Studio", "24.0");
+    other["publisher"] = Value::from("Foo Labs");
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Foo Studio 2024", "24.0"), other]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #3 failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["inserted"], 1);
+
+    let out = run_synora(&home, &["software", "duplicates", "--json"]);
+    assert!(out.status.success(), "duplicates failed: {:?}", out);
+    let groups = stdout_json(&out);
+    assert_eq!(groups.as_array().map(Vec::len), Some(1));
+    assert_eq!(groups[0]["reason"], "same_name");
+
+    let conn = Connection::open(home.join("db").join("synora.db")).expect("open db");
+    for (candidate_id, software_id, url, status) in [
+        (201, 1, "https://foo.example/download", "active"),
+        (202, 2, "https://foo.example/download", "disabled"),
+        (203, 2, "https://foo.example/labs", "active"),
+    ] {
+        conn.execute(
+            r#"
+            INSERT INTO source_registry
+            (candidate_id, software_id, software_name, url, domain, confidence, reason, status, applied_at)
+            VALUES (?1, ?2, 'Foo Studio', ?3, 'foo.example', 70, 'merge fixture', ?4, CAST(strftime('%s', 'now') AS INTEGER))
+            "#,
+            params![candidate_id, software_id, url, status],
+        )
+        .expect("insert registry source");
+    }
+    for (software_id, constraint) in [(1, "24.*"), (2, "<25")] {
+        conn.execute(
+            r#"
+            INSERT INTO software_pin (software_id, version_constraint, created_at, created_by)
+            VALUES (?1, ?2, CAST(strftime('%s', 'now') AS INTEGER), 'tester')
+            "#,
+            params![software_id, constraint],
+        )
+        .expect("insert pin");
+    }
+    conn.execute_batch(
+        r#"
+        UPDATE software_inventory SET owner = 'alice', criticality = 'low' WHERE id = 1;
+        UPDATE software_inventory SET owner = 'bob', team = 'labs', criticality = 'high' WHERE id = 2;
+        INSERT INTO software_tag (software_id, tag, created_at) VALUES (1, 'server', 0), (2, 'server', 0), (2, 'lab', 0);
+        INSERT INTO baseline_drift_item (check_id, baseline, kind, software_id, name, detail)
+        VALUES ('check-1', 'dev', 'forbidden', 2, 'Foo Studio', '');
+        "#,
+    )
+    .expect("annotate fixtures");
+    for (candidate_id, software_id, url, status) in [
+        (301, 1, "https://foo.example/a", "pending"),
+        (302, 2, "https://foo.example/a", "approved"),
+        (303, 1, "https://foo.example/b", "approved"),
+        (304, 2, "https://foo.example/b", "rejected"),
+    ] {
+        conn.execute(
+            r#"
+            INSERT INTO source_candidate
+            (id, software_id, software_name, url, domain, confidence, reason, status, created_at)
+            VALUES (?1, ?2, 'Foo Studio', ?3, 'foo.example', 70, 'merge fixture', ?4, 0)
+            "#,
+            params![candidate_id, software_id, url, status],
+        )
+        .expect("insert candidate");
+    }
+
+    let out = run_synora(
+        &home,
+        &["software", "merge", "--into", "1", "--from", "2", "--json"],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "approved vs rejected should need a review: {:?}",
+        out
+    );
+    conn.execute(
+        "UPDATE source_candidate SET status = 'pending' WHERE id = 304",
+        [],
+    )
+    .expect("reset conflicting decision");
+
+    let out = run_synora(
+        &home,
+        &["software", "merge", "--into", "1", "--from", "2", "--json"],
+    );
+    assert!(out.status.success(), "merge failed: {:?}", out);
+    let merged = stdout_json(&out);
+    assert_eq!(merged["moved"]["software_version_history"], 1);
+    assert_eq!(merged["moved"]["software_pin"], 0);
+    assert_eq!(merged["moved"]["software_pin_kept_into"], 1);
+    let pins: Vec<(i64, String)> = conn
+        .prepare("SELECT software_id, version_constraint FROM software_pin")
+        .expect("prepare pin query")
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .expect("query pins")
+        .collect::<Result<_, _>>()
+        .expect("pin rows");
+    assert_eq!(pins, vec![(1, "24.*".to_string())]);
+    assert_eq!(merged["moved"]["software_tag"], 1);
+    assert_eq!(merged["moved"]["source_candidate_collapsed"], 2);
+    assert_eq!(merged["moved"]["source_candidate_decisions_taken"], 1);
+    let statuses: Vec<(i64, String)> = conn
+        .prepare("SELECT id, status FROM source_candidate ORDER BY id")
+        .expect("prepare candidate query")
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .expect("query candidates")
+        .collect::<Result<_, _>>()
+        .expect("candidate rows");
+    assert_eq!(
+        statuses,
+        vec![(301, "approved".to_string()), (303, "approved".to_string())]
+    );
+    assert_eq!(merged["moved"]["baseline_drift_item"], 1);
+    assert_eq!(
+        merged["moved"]["annotations"],
+        serde_json::json!(["team", "criticality"])
+    );
+    let annotations: (String, String, String, i64) = conn
+        .query_row(
+            r#"
+            SELECT owner, team, criticality, (SELECT COUNT(*) FROM software_tag WHERE software_id = 1)
+            FROM software_inventory WHERE id = 1
+            "#,
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        )
+        .expect("survivor annotations");
+    assert_eq!(
+        annotations,
+        (
+            "alice".to_string(),
+            "labs".to_string(),
+            "high".to_string(),
+            2
+        )
+    );
+    assert_eq!(
+        (
+            merged["moved"]["source_registry"].as_i64(),
+            merged["moved"]["source_registry_merged"].as_i64()
+        ),
+        (Some(1), Some(1))
+    );
+    let mut stmt = conn
+        .prepare("SELECT software_id, url, status FROM source_registry ORDER BY url")
+        .expect("prepare registry query");
+    let registry: Vec<(i64, String, String)> = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+        .expect("query registry")
+        .collect::<Result<_, _>>()
+        .expect("registry rows");
+    assert_eq!(
+        registry,
+        vec![
+            (
+                1,
+                "https://foo.example/download".to_string(),
+                "disabled".to_string()
+            ),
+            (
+                1,
+                "https://foo.example/labs".to_string(),
+                "active".to_string()
+            ),
+        ],
+        "registry rows follow the surviving software and keep the stricter status"
+    );
+
+    let out = run_synora(
+        &home,
+        &["software", "history", "--software-id", "1", "--json"],
+    );
+    assert!(out.status.success(), "history failed: {:?}", out);
+    assert_eq!(stdout_json(&out).as_array().map(Vec::len), Some(3));
+
+    let out = run_synora(
+        &home,
+        &["software", "merge", "--into", "1", "--from", "2", "--json"],
+    );
+    assert!(!out.status.success(), "second merge should be rejected");
+
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #4 failed: {:?}", out);
+    let payload = stdout_json(&out);
+    assert_eq!(payload["inserted"], 0);
+    assert_eq!(payload["deactivated"], 0);
+
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn side_by_side_versions_stay_separate_and_upgrade_code_links_renames() {
+    let home = unique_home();
+
+    let python = |version: &str| registry_item(&format!("Python {version} (64-bit)"), version);
+    let mut tool = registry_item("Acme Tool", "1.0");
+    tool["product_code"] = Value::from("{11111111-1111-1111-1111-111111111111}");
+    tool["upgrade_code"] = Value::from("{99999999-9999-9999-9999-999999999999}");
+    write_fixture(&home, &serde_json::json!([python("3.11.4"), tool]));
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #1 failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["inserted"], 2);
+
+    let mut renamed = registry_item("Acme Workbench", "2.0");
+    renamed["product_code"] = Value::from("{22222222-2222-2222-2222-222222222222}");
+    renamed["upgrade_code"] = Value::from("{99999999-9999-9999-9999-999999999999}");
+    write_fixture(
+        &home,
+        &serde_json::json!([python("3.12.1"), python("3.11.4"), renamed]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #2 failed: {:?}", out);
+    let payload = stdout_json(&out);
+    assert_eq!(payload["inserted"], 1, "Python 3.12 is a new row");
+    assert_eq!(
+        payload["identity_matched"], 1,
+        "the UpgradeCode links the rename"
+    );
+
+    let out = run_synora(&home, &["software", "list", "--json"]);
+    assert!(out.status.success(), "list failed: {:?}", out);
+    let mut rows: Vec<(String, String)> = stdout_json(&out)
+        .as_array()
+        .expect("list array")
+        .iter()
+        .map(|row| {
+            (
+                row["name"].as_str().unwrap_or_default().to_string(),
+                row["identity_key"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+    rows.sort();
+    assert_eq!(
+        rows.iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "Acme Workbench",
+            "Python 3.11.4 (64-bit)",
+            "Python 3.12.1 (64-bit)"
+        ]
+    );
+    assert_eq!(rows[0].1, "upgrade:{99999999-9999-9999-9999-999999999999}");
+
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn publisher_aliases_group_vendor_spellings() {
+    let home = unique_home();
+
+    let mut items = Vec::new();
+    for (name, publisher) in [
+        ("Edge", "Microsoft Corporation"),
+        ("Teams", "Microsoft Corp."),
+        ("Foo Tool", "Foo Labs GmbH"),
+        ("Bar Tool", "FooLabs Inc"),
+    ] {
+        let mut item = registry_item(name, "1.0");
+        item["publisher"] = Value::from(publisher);
+        items.push(item);
+    }
+    write_fixture(&home, &Value::Array(items));
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+
+    let out = run_synora(&home, &["ai", "analyze", "--json"]);
+    assert!(out.status.success(), "analyze failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["summary"]["total_publishers"], 3);
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "publisher",
+            "alias",
+            "add",
+            "--alias",
+            "FooLabs",
+            "--canonical",
+            "Foo Labs",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "alias add failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["software_updated"], 1);
+
+    let out = run_synora(
+        &home,
+        &["software", "list", "--publisher", "foo labs inc", "--json"],
+    );
+    assert!(out.status.success(), "list failed: {:?}", out);
+    let listed = stdout_json(&out);
+    assert_eq!(listed.as_array().map(Vec::len), Some(2));
+    assert_eq!(listed[0]["publisher"], "FooLabs Inc");
+    assert_eq!(listed[0]["publisher_canonical"], "Foo Labs");
+
+    // Overriding a built-in alias makes it a user row, which remove deletes; the seed must not return it.
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "publisher",
+            "alias",
+            "add",
+            "--alias",
+            "microsoft",
+            "--canonical",
+            "Microsoft Corp",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "alias override failed: {:?}", out);
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "publisher",
+            "alias",
+            "remove",
+            "--alias",
+            "microsoft",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "alias remove failed: {:?}", out);
+    let out = run_synora(&home, &["software", "publisher", "alias", "list", "--json"]);
+    assert!(out.status.success(), "alias list failed: {:?}", out);
+    let aliases = stdout_json(&out);
+    assert!(aliases
+        .as_array()
+        .expect("alias array")
+        .iter()
+        .all(|it| it["alias_key"] != "microsoft"));
+
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn manual_entries_survive_scans_and_are_audited() {
+    let home = unique_home();
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "add",
+            "--name",
+            "Portable Putty",
+            "--version",
+            "0.80",
+            "--publisher",
+            "Simon Tatham",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "add failed: {:?}", out);
+    let added = stdout_json(&out);
+    assert_eq!(added["discovery_source"], "manual");
+    let id = added["software_id"]
+        .as_i64()
+        .expect("software id")
+        .to_string();
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "add",
+            "--name",
+            "Portable Putty",
+            "--publisher",
+            "Simon Tatham",
+        ],
+    );
+    assert!(
+        !out.status.success(),
+        "duplicate manual entry should be rejected"
+    );
+
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Google Chrome", "121.0")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #1 failed: {:?}", out);
+    write_fixture(&home, &serde_json::json!([]));
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #2 failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["deactivated"], 1);
+
+    let out = run_synora(&home, &["software", "list", "--active-only", "--json"]);
+    assert!(out.status.success(), "list failed: {:?}", out);
+    let listed = stdout_json(&out);
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+    assert_eq!(listed[0]["name"], "Portable Putty");
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "edit",
+            "--software-id",
+            &id,
+            "--version",
+            "0.81",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "edit failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["version"], "0.81");
+
+    let out = run_synora(
+        &home,
+        &["software", "edit", "--software-id", "2", "--version", "1"],
+    );
+    assert!(
+        !out.status.success(),
+        "editing discovered software should be rejected"
+    );
+
+    let out = run_synora(
+        &home,
+        &["software", "remove", "--software-id", &id, "--json"],
+    );
+    assert!(out.status.success(), "remove failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["is_active"], false);
+
+    let out = run_synora(
+        &home,
+        &["software", "audit", "--software-id", &id, "--json"],
+    );
+    assert!(out.status.success(), "audit failed: {:?}", out);
+    let audit = stdout_json(&out);
+    let actions: Vec<&str> = audit
+        .as_array()
+        .expect("audit array")
+        .iter()
+        .filter_map(|it| it["action"].as_str())
+        .collect();
+    assert_eq!(actions, vec!["remove", "edit", "add"]);
+    assert_eq!(audit[1]["before"]["version"], "0.80");
+    assert!(audit[0]["actor"].as_str().is_some_and(|a| !a.is_empty()));
+
+    let mut putty = registry_item("Portable Putty", "0.83");
+    putty["publisher"] = Value::from("Simon Tatham");
+    write_fixture(&home, &serde_json::json!([putty]));
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan #3 failed: {:?}", out);
+    let payload = stdout_json(&out);
+    assert_eq!(
+        (
+            payload["manual_skipped"].as_i64(),
+            payload["inserted"].as_i64()
+        ),
+        (Some(1), Some(0))
+    );
+    let out = run_synora(&home, &["software", "list", "--json"]);
+    let putty_row = stdout_json(&out)
+        .as_array()
+        .and_then(|rows| {
+            rows.iter()
+                .find(|row| row["name"] == "Portable Putty")
+                .cloned()
+        })
+        .expect("manual row");
+    assert_eq!(
+        (
+            putty_row["is_active"].as_bool(),
+            putty_row["version"].as_str()
+        ),
+        (Some(false), Some("0.81")),
+        "a scan must not reactivate or rewrite a removed manual entry"
+    );
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "add",
+            "--name",
+            "Portable Putty",
+            "--version",
+            "0.82",
+            "--publisher",
+            "Simon Tatham",
+            "--json",
+        ],
+    );
+    assert!(
+        out.status.success(),
+        "re-adding a removed entry failed: {:?}",
+        out
+    );
+    let readded = stdout_json(&out);
+    assert_eq!(
+        readded["software_id"].as_i64().map(|v| v.to_string()),
+        Some(id.clone())
+    );
+    assert_eq!(
+        (readded["is_active"].as_bool(), readded["version"].as_str()),
+        (Some(true), Some("0.82"))
+    );
+    let out = run_synora(
+        &home,
+        &["software", "audit", "--software-id", &id, "--json"],
+    );
+    let audit = stdout_json(&out);
+    assert_eq!(
+        (
+            audit[0]["action"].as_str(),
+            audit[0]["before"]["is_active"].as_bool()
+        ),
+        (Some("add"), Some(false))
+    );
+
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn criticality_and_tags_drive_filters_and_risk() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "121.0"),
+            registry_item("Google Earth", "7.3")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "annotate",
+            "--software-id",
+            "1",
+            "--owner",
+            "alice",
+            "--team",
+            "desktop",
+            "--criticality",
+            "critical",
+            "--notes",
+            "browser baseline",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "annotate failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["criticality"], "critical");
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "annotate",
+            "--software-id",
+            "1",
+            "--criticality",
+            "urgent",
+        ],
+    );
+    assert!(
+        !out.status.success(),
+        "unknown criticality should be rejected"
+    );
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "tag",
+            "--software-id",
+            "1",
+            "--add",
+            "Browser",
+            "--add",
+            "pci",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "tag failed: {:?}", out);
+    assert_eq!(
+        stdout_json(&out)["tags"],
+        serde_json::json!(["browser", "pci"])
+    );
+
+    let out = run_synora(&home, &["software", "list", "--tag", "pci", "--json"]);
+    assert!(out.status.success(), "list failed: {:?}", out);
+    let listed = stdout_json(&out);
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+    assert_eq!(listed[0]["owner"], "alice");
+    assert_eq!(listed[0]["tags"], serde_json::json!(["browser", "pci"]));
+
+    let out = run_synora(
+        &home,
+        &[
+            "ui",
+            "search",
+            "--q",
+            "google",
+            "--criticality",
+            "critical",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "ui search failed: {:?}", out);
+    let items = &stdout_json(&out)["groups"][0]["items"];
+    assert_eq!(items.as_array().map(Vec::len), Some(1));
+    assert_eq!(items[0]["risk_level"], "high");
+
+    let out = run_synora(
+        &home,
+        &["ui", "action-run", "--id", "software.show:1", "--json"],
+    );
+    assert!(
+        !out.status.success(),
+        "critical software action should need confirmation"
+    );
+    let out = run_synora(
+        &home,
+        &["ui", "action-run", "--id", "software.show:2", "--json"],
+    );
+    assert!(
+        out.status.success(),
+        "non-critical action failed: {:?}",
+        out
+    );
+
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn export_emits_cyclonedx_spdx_and_csv() {
+    let home = unique_home();
+
+    let mut deb = registry_item("curl", "7.81.0-1ubuntu1.15");
+    deb["discovery_source"] = Value::from("deb");
+    deb["publisher"] = Value::from("Ubuntu Developers");
+    deb["license"] = Value::from("curl");
+    let mut tool = registry_item("Acme Tool", "2.0");
+    tool["license"] = Value::from("Proprietary, see EULA");
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Google Chrome", "121.0"), deb, tool]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let out = run_synora(
+        &home,
+        &["software", "tag", "--software-id", "2", "--add", "server"],
+    );
+    assert!(out.status.success(), "tag failed: {:?}", out);
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "export",
+            "--format",
+            "cyclonedx-json",
+            "--active-only",
+        ],
+    );
+    assert!(out.status.success(), "cyclonedx export failed: {:?}", out);
+    let bom = stdout_json(&out);
+    assert_eq!(bom["bomFormat"], "CycloneDX");
+    assert!(bom["serialNumber"]
+        .as_str()
+        .is_some_and(|s| s.starts_with("urn:uuid:")));
+    let components = bom["components"].as_array().expect("components");
+    assert_eq!(components.len(), 3);
+    let curl = components
+        .iter()
+        .find(|c| c["name"] == "curl")
+        .expect("curl component");
+    assert_eq!(curl["purl"], "pkg:deb/debian/curl@7.81.0-1ubuntu1.15");
+    assert_eq!(curl["supplier"]["name"], "Ubuntu Developers");
+    assert_eq!(curl["licenses"][0]["license"]["name"], "curl");
+    assert_matches_vendored_schema(
+        &bom,
+        "bom-1.5.schema.json",
+        &[
+            (
+                "http://cyclonedx.org/schema/spdx.schema.json",
+                "spdx.schema.json",
+            ),
+            (
+                "http://cyclonedx.org/schema/jsf-0.82.schema.json",
+                "jsf-0.82.schema.json",
+            ),
+        ],
+    );
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "export",
+            "--format",
+            "spdx-json",
+            "--tag",
+            "server",
+        ],
+    );
+    assert!(out.status.success(), "spdx export failed: {:?}", out);
+    let doc = stdout_json(&out);
+    assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+    assert_eq!(doc["packages"].as_array().map(Vec::len), Some(1));
+    assert_eq!(
+        doc["packages"][0]["externalRefs"][0]["referenceType"],
+        "purl"
+    );
+    assert_eq!(doc["relationships"][0]["relationshipType"], "DESCRIBES");
+
+    let out = run_synora(&home, &["software", "export", "--format", "spdx-json"]);
+    assert!(out.status.success(), "full spdx export failed: {:?}", out);
+    let doc = stdout_json(&out);
+    assert_matches_vendored_schema(&doc, "spdx-2.3.schema.json", &[]);
+    let declared: Vec<&str> = doc["packages"]
+        .as_array()
+        .expect("packages")
+        .iter()
+        .filter_map(|p| p["licenseDeclared"].as_str())
+        .collect();
+    assert_eq!(
+        declared,
+        vec!["LicenseRef-Proprietary-see-EULA", "NOASSERTION", "curl"]
+    );
+    assert_eq!(
+        doc["hasExtractedLicensingInfos"][0]["extractedText"],
+        "Proprietary, see EULA"
+    );
+
+    let path = home.join("out").join("inventory.csv");
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "export",
+            "--format",
+            "csv",
+            "--output",
+            path.to_str().expect("utf8 path"),
+        ],
+    );
+    assert!(out.status.success(), "csv export failed: {:?}", out);
+    let csv = fs::read_to_string(&path).expect("csv written");
+    assert_eq!(csv.lines().count(), 4);
+    assert!(csv.starts_with("software_id,name,version,publisher"));
+
+    let out = run_synora(&home, &["software", "export", "--format", "xml"]);
+    assert!(!out.status.success(), "unknown format should be rejected");
+
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn vulns_sync_matches_inventory_and_raises_update_risk() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.6099.71"),
+            registry_item("Google Earth", "7.3")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+
+    let nvd = serde_json::json!({
+        "vulnerabilities": [{
+            "cve": {
+                "id": "CVE-2024-0519",
+                "descriptions": [{"lang": "en", "value": "Out of bounds memory access in V8"}],
+                "metrics": {"cvssMetricV31": [{"cvssData": {
+                    "vectorString": "CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:U/C:H/I:H/A:H",
+                    "baseScore": 8.8,
+                    "baseSeverity": "HIGH"
+                }}]},
+                "configurations": [{"nodes": [{"cpeMatch": [{
+                    "vulnerable": true,
+                    "criteria": "cpe:2.3:a:google:chrome:*:*:*:*:*:*:*:*",
+                    "versionEndExcluding": "120.0.6099.224"
+                }]}]}]
+            }
+        }]
+    });
+    let feed = home.join("nvd.json");
+    fs::write(&feed, nvd.to_string()).expect("write feed");
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "vulns",
+            "sync",
+            "--file",
+            feed.to_str().expect("utf8 path"),
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "sync failed: {:?}", out);
+    let synced = stdout_json(&out);
+    assert_eq!(synced["format"], "nvd");
+    assert_eq!(synced["advisories"], 1);
+
+    let out = run_synora(&home, &["software", "vulns", "--json"]);
+    assert!(out.status.success(), "vulns failed: {:?}", out);
+    let matches = stdout_json(&out);
+    assert_eq!(matches.as_array().map(Vec::len), Some(1));
+    assert_eq!(matches[0]["software_name"], "Google Chrome");
+    assert_eq!(matches[0]["severity"], "high");
+    assert_eq!(
+        matches[0]["fixed_versions"],
+        serde_json::json!(["120.0.6099.224"])
+    );
+
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "1",
+            "--approve",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "review failed: {:?}", out);
+    let out = run_synora(&home, &["source", "apply-approved", "--json"]);
+    assert!(out.status.success(), "apply failed: {:?}", out);
+
+    let out = run_synora(&home, &["update", "check", "--json"]);
+    assert!(out.status.success(), "update check failed: {:?}", out);
+    let checks = stdout_json(&out);
+    assert_eq!(checks[0]["software_name"], "Google Chrome");
+    assert_eq!(checks[0]["risk_level"], "high");
+    assert_eq!(checks[0]["confidence"], 70);
+    assert_eq!(
+        checks[0]["vulnerabilities"]["advisory_ids"],
+        serde_json::json!(["CVE-2024-0519"])
+    );
+    let breakdown = &checks[0]["score_breakdown"];
+    let points: i64 = breakdown["factors"]
+        .as_array()
+        .expect("factors")
+        .iter()
+        .filter_map(|f| f["points"].as_i64())
+        .sum();
+    assert_eq!(
+        breakdown["total"].as_i64(),
+        Some(points.clamp(0, 100)),
+        "factors must add up to the total"
+    );
+    assert_eq!(breakdown["total"], checks[0]["confidence"]);
+
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn eol_sync_reports_lifecycle_states_and_ai_recommendation() {
+    let home = unique_home();
+
+    let mut python_old = registry_item("Python 3.8.10 (64-bit)", "3.8.10");
+    python_old["publisher"] = Value::from("Python Software Foundation");
+    let mut python_new = registry_item("Python 3.12.2 (64-bit)", "3.12.2");
+    python_new["publisher"] = Value::from("Python Software Foundation");
+    python_new["install_location"] = Value::from("C:\\Python312");
+    write_fixture(
+        &home,
+        &serde_json::json!([python_old, python_new, registry_item("Google Earth", "7.3")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+
+    let dataset = serde_json::json!({
+        "python": [
+            {"cycle": "3.12", "eol": "2099-10-31", "latest": "3.12.4"},
+            {"cycle": "3.8", "eol": "2024-10-07", "latest": "3.8.20"}
+        ]
+    });
+    let file = home.join("eol.json");
+    fs::write(&file, dataset.to_string()).expect("write dataset");
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "eol",
+            "sync",
+            "--file",
+            file.to_str().expect("utf8 path"),
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "eol sync failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["cycles"], 2);
+
+    let out = run_synora(&home, &["software", "eol", "--json"]);
+    assert!(out.status.success(), "eol failed: {:?}", out);
+    let states: Vec<(String, String)> = stdout_json(&out)
+        .as_array()
+        .expect("eol array")
+        .iter()
+        .map(|m| {
+            (
+                m["cycle"].as_str().unwrap_or_default().to_string(),
+                m["state"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        states,
+        vec![
+            ("3.12".to_string(), "supported".to_string()),
+            ("3.8".to_string(), "eol".to_string())
+        ]
+    );
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "eol",
+            "--soon-days",
+            "100000",
+            "--state",
+            "eol_soon",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "eol soon failed: {:?}", out);
+    assert_eq!(stdout_json(&out)[0]["cycle"], "3.12");
+
+    let out = run_synora(&home, &["ai", "analyze", "--json"]);
+    assert!(out.status.success(), "analyze failed: {:?}", out);
+    let analysis = stdout_json(&out);
+    let actions: Vec<&str> = analysis["recommendations"]
+        .as_array()
+        .expect("recommendations")
+        .iter()
+        .filter_map(|r| r["action"].as_str())
+        .collect();
+    assert_eq!(actions, vec!["eol"]);
+
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn pins_hold_updates_and_block_violating_apply() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Google Chrome", "120.0.6099.71")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "pin",
+            "--software-id",
+            "1",
+            "--version",
+            ">=",
+            "--json",
+        ],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "invalid constraint should be rejected: {:?}",
+        out
+    );
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "pin",
+            "--software-id",
+            "1",
+            "--version",
+            "120.*",
+            "--reason",
+            "validated build",
+            "--expires-in-days",
+            "30",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "pin failed: {:?}", out);
+    let pin = stdout_json(&out);
+    assert_eq!(pin["constraint"], "120.*");
+    assert_eq!(pin["expired"], false);
+
+    let out = run_synora(&home, &["software", "pins", "--json"]);
+    assert!(out.status.success(), "pins failed: {:?}", out);
+    let pins = stdout_json(&out);
+    assert_eq!(pins.as_array().map(Vec::len), Some(1));
+    assert_eq!(pins[0]["installed_satisfies"], true);
+
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "1",
+            "--approve",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "review failed: {:?}", out);
+    let out = run_synora(&home, &["source", "apply-approved", "--json"]);
+    assert!(out.status.success(), "apply failed: {:?}", out);
+
+    let out = run_synora(&home, &["update", "check", "--json"]);
+    assert!(out.status.success(), "update check failed: {:?}", out);
+    let checks = stdout_json(&out);
+    assert_eq!(checks[0]["held"], true);
+    assert_eq!(checks[0]["hold_reason"], "validated build");
+    assert_eq!(checks[0]["recommendation"], "held_by_pin");
+
+    let out = run_synora(
+        &home,
+        &[
+            "update",
+            "apply",
+            "--candidate-id",
+            "1",
+            "--target-version",
+            "121.0.1",
+            "--dry-run",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "dry-run failed: {:?}", out);
+    let planned = stdout_json(&out);
+    assert_eq!(planned["status"], "held");
+    assert_eq!(planned["target"]["pin"]["violated"], true);
+
+    let out = run_synora(
+        &home,
+        &[
+            "update",
+            "apply",
+            "--candidate-id",
+            "1",
+            "--target-version",
+            "121.0.1",
+            "--confirm",
+            "--execution-ticket",
+            "CHG-1",
+            "--json",
+        ],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(3),
+        "violating apply should be refused: {:?}",
+        out
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--override-pin"));
+
+    let out = run_synora(
+        &home,
+        &[
+            "update",
+            "apply",
+            "--candidate-id",
+            "1",
+            "--target-version",
+            "120.0.6099.200",
+            "--dry-run",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "dry-run failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["status"], "planned");
+
+    let out = run_synora(
+        &home,
+        &["software", "unpin", "--software-id", "1", "--json"],
+    );
+    assert!(out.status.success(), "unpin failed: {:?}", out);
+    let out = run_synora(&home, &["update", "check", "--json"]);
+    assert_eq!(stdout_json(&out)[0]["held"], false);
+}
+
+#[test]
+fn baseline_check_reports_drift_with_exit_code_and_history() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.6099.71"),
+            registry_item("Google Earth", "7.3")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+
+    let baselines = home.join("baselines");
+    fs::create_dir_all(&baselines).expect("create baselines dir");
+    fs::write(
+        baselines.join("dev.yaml"),
+        "description: Developer workstation\nrequired:\n  - name: Google Chrome\n    version: \">=121\"\n  - name: Git\nforbidden:\n  - name: Google Earth\n    reason: not licensed\n",
+    )
+    .expect("write baseline");
+    fs::write(
+        baselines.join("kiosk.yaml"),
+        "required:\n  - name: Google Chrome\n    publisher: Google\noptional:\n  - name: Google Earth\n",
+    )
+    .expect("write baseline");
+
+    let out = run_synora(&home, &["software", "baseline", "list", "--json"]);
+    assert!(out.status.success(), "list failed: {:?}", out);
+    let listed = stdout_json(&out);
+    assert_eq!(listed[0]["name"], "dev");
+    assert_eq!(listed[0]["required"], 2);
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "baseline",
+            "check",
+            "--baseline",
+            "dev",
+            "--json",
+        ],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(5),
+        "drift should exit with 5: {:?}",
+        out
+    );
+    let report = stdout_json(&out);
+    assert_eq!(report["status"], "drift");
+    assert_eq!(report["missing"][0]["name"], "Git");
+    assert_eq!(report["forbidden_present"][0]["detail"], "not licensed");
+    assert_eq!(report["out_of_constraint"][0]["expected"], ">=121");
+    let check_id = report["check_id"].as_str().expect("check id").to_string();
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "baseline",
+            "check",
+            "--baseline",
+            "kiosk",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "kiosk should be compliant: {:?}", out);
+    assert_eq!(stdout_json(&out)["status"], "compliant");
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "baseline",
+            "check",
+            "--baseline",
+            "missing",
+            "--json",
+        ],
+    );
+    assert_eq!(out.status.code(), Some(2));
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "baseline",
+            "history",
+            "--check-id",
+            &check_id,
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "history failed: {:?}", out);
+    let history = stdout_json(&out);
+    assert_eq!(history[0]["missing"], 1);
+    assert_eq!(history[0]["items"].as_array().map(Vec::len), Some(3));
+
+    let out = run_synora(&home, &["ui", "search", "--q", "Earth", "--json"]);
+    assert!(out.status.success(), "search failed: {:?}", out);
+    let search = stdout_json(&out);
+    let drift = search["groups"]
+        .as_array()
+        .and_then(|g| g.iter().find(|g| g["type"] == "drift").cloned())
+        .expect("drift group");
+    assert_eq!(drift["items"][0]["risk_level"], "high");
+}
+
+#[test]
+fn baseline_with_invalid_version_constraint_is_a_config_error() {
+    let home = unique_home();
+    let baselines = home.join("baselines");
+    fs::create_dir_all(&baselines).expect("create baselines dir");
+    fs::write(
+        baselines.join("bad.yaml"),
+        "required:\n  - name: Git\n    version: \"^*\"\n",
+    )
+    .expect("write baseline");
+
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "baseline",
+            "check",
+            "--baseline",
+            "bad",
+            "--json",
+        ],
+    );
+    assert_eq!(out.status.code(), Some(4), "check should fail: {:?}", out);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("invalid version constraint '^*'"));
+}
+
+#[test]
+fn source_rules_are_overridable_and_recorded_on_candidates() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Google Chrome", "120.0.6099.71")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+
+    let out = run_synora(&home, &["source", "rules", "list", "--json"]);
+    assert!(out.status.success(), "rules list failed: {:?}", out);
+    let rules = stdout_json(&out);
+    assert_eq!(rules[0]["id"], "winget-search");
+    assert_eq!(rules[0]["origin"], "default");
+
+    fs::write(
+        home.join("source_rules.yaml"),
+        r#"rules:
+  - id: winget-search
+    disabled: true
+  - id: google-chrome
+    group: vendor
+    match:
+      name_regex: '^google chrome$'
+      canonical_publisher: [Google]
+      discovery_source: [registry]
+    url: "https://www.google.com/chrome/?q={name}"
+    confidence: 80
+    reason: publisher matched google heuristic
+"#,
+    )
+    .expect("write rules");
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "rules",
+            "test",
+            "--name",
+            "Google Chrome",
+            "--publisher",
+            "Google LLC",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "rules test failed: {:?}", out);
+    let tested = stdout_json(&out);
+    assert_eq!(tested["canonical_publisher"], "Google");
+    let fired: Vec<&str> = tested["rules"]
+        .as_array()
+        .expect("rules")
+        .iter()
+        .filter(|r| r["outcome"] == "fired")
+        .filter_map(|r| r["id"].as_str())
+        .collect();
+    assert_eq!(fired, vec!["google-chrome"]);
+
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+    let out = run_synora(&home, &["source", "list", "--json"]);
+    assert!(out.status.success(), "list failed: {:?}", out);
+    let candidates = stdout_json(&out);
+    assert_eq!(candidates.as_array().map(Vec::len), Some(1));
+    assert_eq!(candidates[0]["rule_id"], "google-chrome");
+    assert_eq!(
+        candidates[0]["url"],
+        "https://www.google.com/chrome/?q=Google+Chrome"
+    );
+    assert_eq!(candidates[0]["score_breakdown"]["factors"][0]["points"], 80);
+    assert_eq!(candidates[0]["confidence"], 90);
+
+    fs::write(home.join("source_rules.yaml"), "rules:\n  - id: broken\n    match:\n      name_regex: '('\n    url: \"https://example.com\"\n").expect("write rules");
+    let out = run_synora(&home, &["source", "rules", "list", "--json"]);
+    assert_eq!(
+        out.status.code(),
+        Some(4),
+        "invalid regex should be a config error: {:?}",
+        out
+    );
+
+    fs::write(
+        home.join("source_rules.yaml"),
+        "rules:\n  - id: plain-http\n    url: \"http://example.com/{name}\"\n",
+    )
+    .expect("write rules");
+    let out = run_synora(&home, &["source", "rules", "list", "--json"]);
+    assert_eq!(
+        out.status.code(),
+        Some(4),
+        "non-https rule should be a config error: {:?}",
+        out
+    );
+}
+
+#[test]
+fn source_reviews_are_audited_and_risky_reviews_need_a_note() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.6099.71"),
+            registry_item("Google Earth", "7.3")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "annotate",
+            "--software-id",
+            "2",
+            "--criticality",
+            "critical",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "annotate failed: {:?}", out);
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "1",
+            "--approve",
+            "--reviewer",
+            "alice",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "review failed: {:?}", out);
+    let reviewed = stdout_json(&out);
+    assert_eq!(reviewed["previous_status"], "pending");
+    assert_eq!(reviewed["reviewer"], "alice");
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "2",
+            "--approve",
+            "--json",
+        ],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "critical software review should need a note: {:?}",
+        out
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--note is required"));
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-bulk",
+            "--reject",
+            "--status",
+            "pending",
+            "--reviewer",
+            "bob",
+            "--json",
+        ],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "bulk review should need a note: {:?}",
+        out
+    );
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-bulk",
+            "--reject",
+            "--status",
+            "pending",
+            "--reviewer",
+            "bob",
+            "--note",
+            "not our distribution channel",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "bulk review failed: {:?}", out);
+    let bulk = stdout_json(&out);
+    assert_eq!(bulk["updated"], 1);
+    let batch_id = bulk["batch_id"].as_str().expect("batch id").to_string();
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-history",
+            "--batch-id",
+            &batch_id,
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "history failed: {:?}", out);
+    let batch = stdout_json(&out);
+    assert_eq!(batch.as_array().map(Vec::len), Some(1));
+    assert_eq!(batch[0]["candidate_id"], 2);
+    assert_eq!(batch[0]["old_status"], "pending");
+    assert_eq!(batch[0]["new_status"], "rejected");
+    assert_eq!(batch[0]["reviewer"], "bob");
+    assert_eq!(batch[0]["risk_level"], "high");
+    assert_eq!(batch[0]["note"], "not our distribution channel");
+
+    let out = run_synora(
+        &home,
+        &["source", "review-history", "--reviewer", "alice", "--json"],
+    );
+    let alice = stdout_json(&out);
+    assert_eq!(alice.as_array().map(Vec::len), Some(1));
+    assert_eq!(alice[0]["batch_id"], serde_json::Value::Null);
+}
+
+#[test]
+fn bulk_review_previews_commits_as_batch_and_undoes() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.6099.71"),
+            registry_item("Google Earth", "7.3"),
+            registry_item("Google Drive", "85.0")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-bulk",
+            "--approve",
+            "--status",
+            "pending",
+            "--preview",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "preview failed: {:?}", out);
+    let preview = stdout_json(&out);
+    assert_eq!(preview["matched"], 3);
+    assert_eq!(preview["candidates"][0]["current_status"], "pending");
+    let out = run_synora(&home, &["source", "list", "--status", "approved", "--json"]);
+    assert_eq!(
+        stdout_json(&out),
+        serde_json::json!([]),
+        "preview must not change statuses"
+    );
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-bulk",
+            "--approve",
+            "--status",
+            "pending",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "bulk failed: {:?}", out);
+    let first = stdout_json(&out);
+    assert_eq!(first["updated"], 3);
+    let first_batch = first["batch_id"].as_str().expect("batch id").to_string();
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "1",
+            "--reject",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "single review failed: {:?}", out);
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-undo",
+            "--batch-id",
+            &first_batch,
+            "--json",
+        ],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "undo should refuse re-reviewed candidates: {:?}",
+        out
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("1 (reviewed again)"));
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-bulk",
+            "--reject",
+            "--status",
+            "approved",
+            "--reviewer",
+            "carol",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "second bulk failed: {:?}", out);
+    let second_batch = stdout_json(&out)["batch_id"]
+        .as_str()
+        .expect("batch id")
+        .to_string();
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-undo",
+            "--batch-id",
+            &second_batch,
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "undo failed: {:?}", out);
+    let undone = stdout_json(&out);
+    assert_eq!(undone["restored"].as_array().map(Vec::len), Some(2));
+    assert_eq!(undone["restored"][0]["restored_status"], "approved");
+
+    let out = run_synora(&home, &["source", "list", "--status", "approved", "--json"]);
+    assert_eq!(stdout_json(&out).as_array().map(Vec::len), Some(2));
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-undo",
+            "--batch-id",
+            &second_batch,
+            "--json",
+        ],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "double undo should be refused: {:?}",
+        out
+    );
+}
+
+#[test]
+fn auto_review_policy_approves_rejects_and_respects_untrusted_domains() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.6099.71"),
+            registry_item("Google Earth", "7.3"),
+            registry_item("Google Drive", "85.0")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let out = run_synora(
+        &home,
+        &[
+            "software",
+            "annotate",
+            "--software-id",
+            "3",
+            "--criticality",
+            "critical",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "annotate failed: {:?}", out);
+
+    fs::write(
+        home.join("source_rules.yaml"),
+        r#"rules:
+  - id: google-official
+    match:
+      canonical_publisher: [Google]
+      name_regex: 'chrome|drive'
+    url: "https://dl.google.com/{name}"
+    domain: dl.google.com
+    confidence: 80
+    reason: google download heuristic
+  - id: earth-mirror
+    match:
+      name_regex: earth
+    url: "https://mirror.example.net/{name}"
+    domain: mirror.example.net
+    confidence: 75
+    reason: mirror heuristic
+"#,
+    )
+    .expect("write rules");
+    fs::write(
+        home.join("config.json"),
+        serde_json::json!({
+            "execution": {"real_mutation_enabled": false, "gate_version": "phase3-draft-v1", "approval_record_ref": ""},
+            "download_source_policy": {"untrusted_domains": ["*.example.net"]},
+            "auto_review": {
+                "trusted_domains": ["*.google.com", "*.example.net"],
+                "min_confidence": 70,
+                "require_publisher_match": true,
+                "deny_patterns": ["winget\\.run"]
+            }
+        })
+        .to_string(),
+    )
+    .expect("write config");
+
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+
+    let out = run_synora(&home, &["source", "auto-review", "--dry-run", "--json"]);
+    assert!(out.status.success(), "dry-run failed: {:?}", out);
+    let dry = stdout_json(&out);
+    let decision_for = |run: &serde_json::Value, url_part: &str| -> (String, String) {
+        let d = run["decisions"]
+            .as_array()
+            .expect("decisions")
+            .iter()
+            .find(|d| d["url"].as_str().is_some_and(|u| u.contains(url_part)))
+            .expect("decision")
+            .clone();
+        (
+            d["decision"].as_str().unwrap_or_default().to_string(),
+            d["rule"].as_str().unwrap_or_default().to_string(),
+        )
+    };
+    assert_eq!(
+        decision_for(&dry, "dl.google.com/Google+Chrome"),
+        ("approved".to_string(), "trusted_domain".to_string())
+    );
+    assert_eq!(
+        decision_for(&dry, "mirror"),
+        ("rejected".to_string(), "untrusted_domain".to_string())
+    );
+    assert_eq!(
+        decision_for(&dry, "dl.google.com/Google+Drive"),
+        (
+            "pending".to_string(),
+            "risk_requires_manual_review".to_string()
+        )
+    );
+    assert_eq!(
+        decision_for(&dry, "winget.run/search?query=Google+Chrome").0,
+        "rejected"
+    );
+    let out = run_synora(&home, &["source", "list", "--status", "pending", "--json"]);
+    assert_eq!(
+        stdout_json(&out).as_array().map(Vec::len),
+        Some(6),
+        "dry-run must not change statuses"
+    );
+
+    let out = run_synora(&home, &["source", "auto-review", "--apply", "--json"]);
+    assert!(out.status.success(), "apply failed: {:?}", out);
+    let applied = stdout_json(&out);
+    assert_eq!(applied["approved"], 1);
+    assert_eq!(applied["rejected"], 4);
+    assert_eq!(applied["pending"], 1);
+    let run_id = applied["run_id"].as_str().expect("run id").to_string();
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-history",
+            "--batch-id",
+            &run_id,
+            "--status",
+            "approved",
+            "--json",
+        ],
+    );
+    let history = stdout_json(&out);
+    assert_eq!(history[0]["reviewer"], "auto-review");
+    assert_eq!(history[0]["note"], "rule: trusted_domain");
+
+    let out = run_synora(
+        &home,
+        &["source", "review-undo", "--batch-id", &run_id, "--json"],
+    );
+    assert!(
+        out.status.success(),
+        "undo of auto-review failed: {:?}",
+        out
+    );
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "suggest",
+            "--auto-review",
+            "--status",
+            "approved",
+            "--json",
+        ],
+    );
+    assert!(
+        out.status.success(),
+        "suggest with auto-review failed: {:?}",
+        out
+    );
+    let suggested = stdout_json(&out);
+    assert_eq!(suggested["items"][0]["domain"], "google.com");
+    assert!(
+        suggested["auto_review"]["approved"].as_i64().is_some(),
+        "{suggested}"
+    );
+}
+
+#[test]
+fn candidate_confidence_is_explained_by_named_factors() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.6099.71"),
+            registry_item("Google Earth", "7.3")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+
+    let out = run_synora(&home, &["source", "list", "--json"]);
+    let candidates = stdout_json(&out);
+    let breakdown = &candidates[0]["score_breakdown"];
+    let factors: Vec<&str> = breakdown["factors"]
+        .as_array()
+        .expect("factors")
+        .iter()
+        .filter_map(|f| f["factor"].as_str())
+        .collect();
+    assert_eq!(
+        factors,
+        vec![
+            "rule_base",
+            "domain_trust",
+            "publisher_domain_match",
+            "https",
+            "official_vendor",
+            "prior_approvals",
+            "rejection_history"
+        ]
+    );
+    assert_eq!(breakdown["total"], candidates[0]["confidence"]);
+    assert_eq!(candidates[0]["confidence"], 55);
+
+    // Chrome is candidate 1; rejecting it should penalise winget.run for Earth on the next run.
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "1",
+            "--reject",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "review failed: {:?}", out);
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+    let out = run_synora(&home, &["source", "list", "--contains", "Earth", "--json"]);
+    let earth = stdout_json(&out);
+    assert_eq!(earth[0]["confidence"], 52);
+    assert_eq!(earth[0]["score_breakdown"]["factors"][6]["points"], -3);
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "2",
+            "--approve",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "review failed: {:?}", out);
+    let out = run_synora(&home, &["source", "apply-approved", "--json"]);
+    assert!(out.status.success(), "apply failed: {:?}", out);
+    let out = run_synora(&home, &["update", "check", "--json"]);
+    let checks = stdout_json(&out);
+    assert_eq!(checks[0]["software_name"], "Google Earth");
+    assert_eq!(checks[0]["score_breakdown"]["total"], 52);
+}
+
+#[test]
+fn calibrate_learns_from_review_decisions_within_bounds() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.6099.71"),
+            registry_item("Google Earth", "7.3"),
+            registry_item("Google Drive", "85.0"),
+            registry_item("Google Git Helper", "1.0")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    fs::write(
+        home.join("config.json"),
+        serde_json::json!({
+            "execution": {"real_mutation_enabled": false, "gate_version": "phase3-draft-v1", "approval_record_ref": ""},
+            "calibration": {"max_adjustment": 6, "min_samples": 3}
+        })
+        .to_string(),
+    )
+    .expect("write config");
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-bulk",
+            "--reject",
+            "--domain",
+            "winget.run",
+            "--status",
+            "pending",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "bulk reject failed: {:?}", out);
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-bulk",
+            "--approve",
+            "--domain",
+            "git-scm.com",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "approve failed: {:?}", out);
+
+    let out = run_synora(&home, &["source", "calibrate", "--dry-run", "--json"]);
+    assert!(out.status.success(), "dry-run failed: {:?}", out);
+    let preview = stdout_json(&out);
+    assert_eq!(preview["mode"], "dry_run");
+
+    let out = run_synora(&home, &["source", "calibrate", "--json"]);
+    assert!(out.status.success(), "calibrate failed: {:?}", out);
+    let report = stdout_json(&out);
+    let winget = report["domains"]
+        .as_array()
+        .expect("domains")
+        .iter()
+        .find(|d| d["key"] == "winget.run")
+        .expect("winget domain")
+        .clone();
+    assert_eq!(winget["rejected"], 4);
+    assert_eq!(
+        winget["adjustment"], 0,
+        "domain history is already scored by rejection_history"
+    );
+    let rule = report["rules"]
+        .as_array()
+        .expect("rules")
+        .iter()
+        .find(|r| r["key"] == "winget-search")
+        .expect("winget rule")
+        .clone();
+    assert_eq!(rule["adjustment"], -4);
+    let git = report["domains"]
+        .as_array()
+        .expect("domains")
+        .iter()
+        .find(|d| d["key"] == "git-scm.com")
+        .expect("git domain")
+        .clone();
+    assert_eq!(git["adjustment"], 0, "below min_samples must not adjust");
+    assert_eq!(
+        report["consistently_rejected_rules"][0]["key"],
+        "winget-search"
+    );
+
+    // Policy decisions (auto-review, expiry, import) are not calibration samples.
+    let conn = Connection::open(home.join("db").join("synora.db")).expect("open db");
+    conn.execute(
+        r#"
+        INSERT INTO source_review_history
+        (candidate_id, software_id, old_status, new_status, reviewer, note, confidence, risk_level, ts)
+        SELECT id, software_id, status, 'rejected', 'auto-review', 'rule: fixture', confidence, 'low', CAST(strftime('%s', 'now') AS INTEGER)
+        FROM source_candidate WHERE domain = 'git-scm.com'
+        "#,
+        [],
+    )
+    .expect("insert auto-review decision");
+    let out = run_synora(&home, &["source", "calibrate", "--dry-run", "--json"]);
+    let git = stdout_json(&out)["domains"]
+        .as_array()
+        .expect("domains")
+        .iter()
+        .find(|d| d["key"] == "git-scm.com")
+        .expect("git domain")
+        .clone();
+    assert_eq!(
+        (git["approved"].as_i64(), git["rejected"].as_i64()),
+        (Some(1), Some(0))
+    );
+
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.6099.71"),
+            registry_item("Google Earth", "7.3"),
+            registry_item("Google Drive", "85.0"),
+            registry_item("Google Git Helper", "1.0"),
+            registry_item("Google Maps", "1.0")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "list",
+            "--domain",
+            "winget.run",
+            "--status",
+            "rejected",
+            "--json",
+        ],
+    );
+    let rejected = stdout_json(&out);
+    assert!(
+        rejected
+            .as_array()
+            .expect("rejected")
+            .iter()
+            .all(|c| c["score_breakdown"]["factors"]
+                .as_array()
+                .expect("factors")
+                .iter()
+                .all(|f| f["factor"] != "calibration")),
+        "reviewed candidates must keep the score their reviewer saw"
+    );
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "list",
+            "--domain",
+            "winget.run",
+            "--status",
+            "pending",
+            "--json",
+        ],
+    );
+    let candidates = stdout_json(&out);
+    let calibration = candidates[0]["score_breakdown"]["factors"]
+        .as_array()
+        .expect("factors")
+        .iter()
+        .find(|f| f["factor"] == "calibration")
+        .expect("calibration factor")
+        .clone();
+    assert_eq!(
+        calibration["points"], -4,
+        "only the rule adjustment applies"
+    );
+}
+
+#[test]
+fn cosmetic_url_duplicates_collapse_onto_the_reviewed_candidate() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Google Chrome", "120.0.6099.71")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+    let suggested = stdout_json(&out);
+    assert_eq!(
+        suggested["items"][0]["url"],
+        "https://winget.run/search?query=Google+Chrome"
+    );
+    let software_id = suggested["items"][0]["software_id"]
+        .as_i64()
+        .expect("software id");
+
+    let conn = Connection::open(home.join("db").join("synora.db")).expect("open db");
+    for url in [
+        "HTTPS://WinGet.RUN:443/search?query=Google+Chrome#top",
+        "https://winget.run/%7eusers/%2fchrome",
+    ] {
+        conn.execute(
+            r#"
+            INSERT INTO source_candidate (software_id, software_name, url, domain, confidence, reason, status, created_at)
+            VALUES (?1, 'Google Chrome', ?2, 'WinGet.RUN', 55, 'legacy import', 'pending', 0)
+            "#,
+            params![software_id, url],
+        )
+        .expect("insert legacy candidate");
+    }
+    let duplicate_id = conn.last_insert_rowid() - 1;
+    // Canonicalization is a one-time migration; forget it so these rows look like a pre-upgrade database.
+    let reset_canonicalization = || {
+        conn.execute(
+            "DELETE FROM schema_migration WHERE name = '0002_canonicalize_source_candidates'",
+            [],
+        )
+        .expect("reset migration")
+    };
+    reset_canonicalization();
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            &duplicate_id.to_string(),
+            "--approve",
+        ],
+    );
+    assert!(out.status.success(), "review failed: {:?}", out);
+    let out = run_synora(&home, &["source", "apply-approved", "--json"]);
+    assert!(out.status.success(), "apply-approved failed: {:?}", out);
+
+    let out = run_synora(&home, &["source", "suggest"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("Canonicalized 2 candidate URLs, collapsed 1 duplicates."),
+        "{stdout}"
+    );
+    let out = run_synora(&home, &["source", "suggest"]);
+    assert!(
+        !String::from_utf8_lossy(&out.stdout).contains("Canonicalized"),
+        "canonicalization runs once"
+    );
+
+    let out = run_synora(&home, &["source", "list", "--json"]);
+    let candidates = stdout_json(&out);
+    let mut urls: Vec<(i64, &str, &str, &str)> = candidates
+        .as_array()
+        .expect("candidates")
+        .iter()
+        .map(|c| {
+            (
+                c["candidate_id"].as_i64().unwrap_or_default(),
+                c["url"].as_str().unwrap_or_default(),
+                c["domain"].as_str().unwrap_or_default(),
+                c["status"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect();
+    urls.sort();
+    assert_eq!(
+        urls,
+        vec![
+            (
+                duplicate_id,
+                "https://winget.run/search?query=Google+Chrome",
+                "winget.run",
+                "approved"
+            ),
+            (
+                duplicate_id + 1,
+                "https://winget.run/~users/%2Fchrome",
+                "winget.run",
+                "pending"
+            ),
+        ]
+    );
+
+    let registry_url: String = conn
+        .query_row(
+            "SELECT url FROM source_registry WHERE candidate_id = ?1",
+            params![duplicate_id],
+            |r| r.get(0),
+        )
+        .expect("registry row");
+    assert_eq!(
+        registry_url,
+        "https://winget.run/search?query=Google+Chrome"
+    );
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-history",
+            "--candidate-id",
+            &duplicate_id.to_string(),
+            "--json",
+        ],
+    );
+    let history = stdout_json(&out);
+    assert_eq!(
+        history.as_array().map(Vec::len),
+        Some(2),
+        "the review and the merge are both on record"
+    );
+    assert_eq!(history[0]["reviewer"], "url-canonicalize");
+
+    // A rejected duplicate outranks the approval of the row it collapses onto.
+    let kept_id = (duplicate_id + 1).to_string();
+    let out = run_synora(
+        &home,
+        &["source", "review", "--candidate-id", &kept_id, "--approve"],
+    );
+    assert!(out.status.success(), "review failed: {:?}", out);
+    conn.execute(
+        r#"
+        INSERT INTO source_candidate (software_id, software_name, url, domain, confidence, reason, status, created_at)
+        VALUES (?1, 'Google Chrome', 'https://WINGET.run/%7Eusers/%2fchrome', 'winget.run', 55, 'legacy import', 'rejected', 0)
+        "#,
+        params![software_id],
+    )
+    .expect("insert rejected legacy candidate");
+    reset_canonicalization();
+    let out = run_synora(&home, &["source", "suggest"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+    let out = run_synora(&home, &["source", "list", "--status", "rejected", "--json"]);
+    let rejected = stdout_json(&out);
+    assert_eq!(rejected.as_array().map(Vec::len), Some(1));
+    assert_eq!(rejected[0]["candidate_id"].as_i64(), Some(duplicate_id + 1));
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review-history",
+            "--candidate-id",
+            &kept_id,
+            "--json",
+        ],
+    );
+    let history = stdout_json(&out);
+    assert_eq!(
+        (
+            history[0]["reviewer"].as_str(),
+            history[0]["new_status"].as_str()
+        ),
+        (Some("url-canonicalize"), Some("rejected"))
+    );
+}
+
+#[test]
+fn probe_records_health_and_disables_broken_registry_sources() {
+    let home = unique_home();
+    let port = spawn_probe_server();
+
+    let out = run_synora(&home, &["source", "probe", "--json"]);
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "probe without a selector should be a usage error: {:?}",
+        out
+    );
+    let out = run_synora(&home, &["source", "registry-list", "--json"]);
+    assert!(out.status.success(), "registry-list failed: {:?}", out);
+
+    let conn = Connection::open(home.join("db").join("synora.db")).expect("open db");
+    let paths = [
+        "/ok",
+        "/head-refused",
+        "/moved",
+        "/parked",
+        "/gone",
+        "/loop",
+    ];
+    for (i, path) in paths.iter().enumerate() {
+        conn.execute(
+            r#"
+            INSERT INTO source_registry
+            (candidate_id, software_id, software_name, url, domain, confidence, reason, status, applied_at)
+            VALUES (?1, ?1, ?2, ?3, '127.0.0.1', 70, 'probe fixture', 'active', CAST(strftime('%s', 'now') AS INTEGER))
+            "#,
+            params![101 + i as i64, format!("Tool {i}"), format!("http://127.0.0.1:{port}{path}")],
+        )
+        .expect("insert registry source");
+    }
+
+    let out = run_synora(
+        &home,
+        &["source", "probe", "--candidate-id", "103", "--json"],
+    );
+    assert!(
+        out.status.success(),
+        "probe of an internal host failed: {:?}",
+        out
+    );
+    let blocked = &stdout_json(&out)["results"][0];
+    assert_eq!(
+        (blocked["health"].as_str(), blocked["http_status"].as_i64()),
+        (Some("broken"), None)
+    );
+    assert!(
+        blocked["detail"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("internal host"),
+        "{blocked}"
+    );
+    conn.execute("DELETE FROM source_probe_history", [])
+        .expect("reset probe history");
+
+    fs::write(
+        home.join("config.json"),
+        serde_json::json!({
+            "execution": {"real_mutation_enabled": false, "gate_version": "phase3-draft-v1", "approval_record_ref": ""},
+            "source_url_policy": {"allow_http": true, "allow_ip_literals": true},
+            "source_probe": {"allow_private_hosts": true}
+        })
+        .to_string(),
+    )
+    .expect("write config");
+    let out = run_synora(&home, &["source", "probe", "--all", "--json"]);
+    assert!(out.status.success(), "probe --all failed: {:?}", out);
+    let report = stdout_json(&out);
+    assert_eq!(
+        (report["probed"].as_i64(), report["healthy"].as_i64()),
+        (Some(6), Some(3))
+    );
+    assert_eq!(
+        (report["degraded"].as_i64(), report["broken"].as_i64()),
+        (Some(1), Some(2))
+    );
+    let result = |candidate_id: i64| {
+        report["results"]
+            .as_array()
+            .expect("results")
+            .iter()
+            .find(|r| r["candidate_id"] == candidate_id)
+            .cloned()
+            .expect("probe result")
+    };
+    let ok = result(101);
+    assert_eq!(
+        (ok["method"].as_str(), ok["http_status"].as_i64()),
+        (Some("HEAD"), Some(200))
+    );
+    assert_eq!(ok["content_type"], "text/html");
+    assert!(ok["tls_valid"].is_null(), "plain http has no TLS verdict");
+    let refused = result(102);
+    assert_eq!(
+        (refused["method"].as_str(), refused["health"].as_str()),
+        (Some("GET"), Some("healthy"))
+    );
+    assert_eq!(refused["content_type"], "application/octet-stream");
+    let moved = result(103);
+    assert_eq!(
+        (moved["redirect_count"].as_i64(), moved["health"].as_str()),
+        (Some(1), Some("healthy"))
+    );
+    assert_eq!(moved["final_url"], format!("http://127.0.0.1:{port}/ok"));
+    let parked = result(104);
+    assert_eq!(parked["health"], "degraded");
+    assert_eq!(parked["final_url"], format!("http://localhost:{port}/ok"));
+    assert_eq!(
+        (
+            result(105)["health"].as_str(),
+            result(105)["http_status"].as_i64()
+        ),
+        (Some("broken"), Some(404))
+    );
+    assert!(result(106)["detail"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("more than 5 redirects"));
+    assert_eq!(report["disabled"], 0);
+
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "probe",
+            "--candidate-id",
+            "105",
+            "--disable-broken",
+            "--json",
+        ],
+    );
+    assert!(
+        out.status.success(),
+        "probe --disable-broken failed: {:?}",
+        out
+    );
+    assert_eq!(stdout_json(&out)["results"][0]["disabled"], true);
+    let out = run_synora(
+        &home,
+        &["source", "registry-list", "--health", "broken", "--json"],
+    );
+    let broken = stdout_json(&out);
+    let statuses: Vec<(i64, &str)> = broken
+        .as_array()
+        .expect("registry")
+        .iter()
+        .map(|r| {
+            (
+                r["candidate_id"].as_i64().unwrap_or_default(),
+                r["status"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect();
+    assert_eq!(statuses, vec![(106, "active"), (105, "disabled")]);
+
+    conn.execute(
+        "UPDATE source_registry SET status = 'review_required' WHERE candidate_id = 106",
+        [],
+    )
+    .expect("require review");
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "probe",
+            "--candidate-id",
+            "106",
+            "--disable-broken",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "probe of 106 failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["results"][0]["disabled"], false);
+    let status: String = conn
+        .query_row(
+            "SELECT status FROM source_registry WHERE candidate_id = 106",
+            [],
+            |row| row.get(0),
+        )
+        .expect("registry status");
+    assert_eq!(
+        status, "review_required",
+        "--disable-broken only disables active entries"
+    );
+
+    let out = run_synora(
+        &home,
+        &[
+            "job",
+            "submit",
+            "--type",
+            "source.probe",
+            "--payload",
+            r#"{"all":true}"#,
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "job submit failed: {:?}", out);
+    let out = run_synora(&home, &["job", "worker-run", "--once", "--json"]);
+    let job = stdout_json(&out);
+    assert_eq!(job["new_status"], "success", "{job}");
+
+    let out = run_synora(
+        &home,
+        &["source", "probe-history", "--candidate-id", "101", "--json"],
+    );
+    assert_eq!(stdout_json(&out).as_array().map(Vec::len), Some(2));
+    let out = run_synora(
+        &home,
+        &["source", "probe-history", "--candidate-id", "105", "--json"],
+    );
+    assert_eq!(
+        stdout_json(&out).as_array().map(Vec::len),
+        Some(2),
+        "disabled sources are skipped by --all"
+    );
+}
+
+#[test]
+fn expired_registry_sources_require_re_review_before_use() {
+    let home = unique_home();
+
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Google Chrome", "120.0.6099.71")]),
+    );
+    for args in [
+        &["software", "discover", "scan", "--json"][..],
+        &["source", "suggest", "--json"],
+        &["source", "review", "--candidate-id", "1", "--approve"],
+        &["source", "apply-approved", "--json"],
+    ] {
+        let out = run_synora(&home, args);
+        assert!(out.status.success(), "{args:?} failed: {:?}", out);
+    }
+
+    let out = run_synora(&home, &["source", "registry-list", "--json"]);
+    let registry = stdout_json(&out);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let expires_at = registry[0]["expires_at"].as_i64().expect("expires_at");
+    assert!(
+        (now + 179 * 86_400..=now + 181 * 86_400).contains(&expires_at),
+        "expires_at {expires_at}"
+    );
+
+    let out = run_synora(
+        &home,
+        &["source", "registry-expiring", "--within", "30", "--json"],
+    );
+    assert_eq!(stdout_json(&out).as_array().map(Vec::len), Some(0));
+    let out = run_synora(
+        &home,
+        &["source", "registry-expiring", "--within", "200", "--json"],
+    );
+    let expiring = stdout_json(&out);
+    assert_eq!(expiring[0]["candidate_id"], 1);
+    assert!(
+        matches!(expiring[0]["expires_in_days"].as_i64(), Some(179 | 180)),
+        "{expiring}"
+    );
+
+    // Simulate the review interval passing.
+    let conn = Connection::open(home.join("db").join("synora.db")).expect("open db");
+    conn.execute(
+        "UPDATE source_registry SET expires_at = ?1",
+        params![now - 60],
+    )
+    .expect("age registry");
+
+    let out = run_synora(&home, &["ui", "search", "--q", "google", "--json"]);
+    assert!(out.status.success(), "search failed: {:?}", out);
+    let search = stdout_json(&out);
+    assert!(
+        search["groups"]
+            .as_array()
+            .expect("groups")
+            .iter()
+            .all(|g| g["type"] != "source"),
+        "expired sources are not offered by search: {search}"
+    );
+    let out = run_synora(&home, &["update", "check", "--json"]);
+    assert!(out.status.success(), "update check failed: {:?}", out);
+    assert_eq!(
+        stdout_json(&out).as_array().map(Vec::len),
+        Some(0),
+        "expired sources are not usable"
+    );
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "registry-list",
+            "--status",
+            "review_required",
+            "--json",
+        ],
+    );
+    assert_eq!(stdout_json(&out)[0]["candidate_id"], 1);
+    let out = run_synora(
+        &home,
+        &["source", "review-history", "--candidate-id", "1", "--json"],
+    );
+    let history = stdout_json(&out);
+    assert_eq!(
+        (
+            history[0]["reviewer"].as_str(),
+            history[0]["new_status"].as_str()
+        ),
+        (Some("registry-expiry"), Some("pending"))
+    );
+
+    let out = run_synora(
+        &home,
+        &[
+            "update",
+            "apply",
+            "--candidate-id",
+            "1",
+            "--dry-run",
+            "--json",
+        ],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(3),
+        "expired source must be refused: {:?}",
+        out
+    );
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "registry-enable",
+            "--status",
+            "review_required",
+            "--json",
+        ],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "enable must not bypass re-review: {:?}",
+        out
+    );
+    let out = run_synora(&home, &["source", "apply-approved", "--json"]);
+    assert_eq!(
+        stdout_json(&out)["matched"],
+        0,
+        "pending candidates are not re-applied"
+    );
+
+    let out = run_synora(
+        &home,
+        &["source", "review", "--candidate-id", "1", "--approve"],
+    );
+    assert!(out.status.success(), "re-approval failed: {:?}", out);
+    let out = run_synora(&home, &["source", "apply-approved", "--json"]);
+    assert!(out.status.success(), "apply-approved failed: {:?}", out);
+    let out = run_synora(&home, &["source", "registry-list", "--json"]);
+    let registry = stdout_json(&out);
+    assert_eq!(registry[0]["status"], "active");
+    assert!(registry[0]["expires_at"].as_i64().unwrap_or_default() > now + 179 * 86_400);
+    let out = run_synora(&home, &["update", "check", "--json"]);
+    assert_eq!(stdout_json(&out).as_array().map(Vec::len), Some(1));
+
+    // Disabled entries stay disabled when they expire; enabling them sends them to re-review.
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "registry-disable",
+            "--candidate-id",
+            "1",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "registry-disable failed: {:?}", out);
+    conn.execute(
+        "UPDATE source_registry SET expires_at = ?1",
+        params![now - 60],
+    )
+    .expect("age registry");
+    let out = run_synora(&home, &["source", "registry-list", "--json"]);
+    assert_eq!(stdout_json(&out)[0]["status"], "disabled");
+    let out = run_synora(
+        &home,
+        &["source", "review-history", "--candidate-id", "1", "--json"],
+    );
+    assert_eq!(
+        stdout_json(&out)[0]["new_status"],
+        "approved",
+        "disabled sources are not reset to pending"
+    );
+    let out = run_synora(
+        &home,
+        &["source", "registry-enable", "--candidate-id", "1", "--json"],
+    );
+    assert!(out.status.success(), "registry-enable failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["review_required"], 1);
+    let out = run_synora(&home, &["source", "registry-list", "--json"]);
+    assert_eq!(stdout_json(&out)[0]["status"], "review_required");
+}
+
+#[test]
+fn registry_export_imports_on_another_machine_with_conflict_strategies() {
+    let origin = unique_home();
+    write_fixture(
+        &origin,
+        &serde_json::json!([registry_item("Google Chrome", "120.0.6099.71")]),
+    );
+    for args in [
+        &["software", "discover", "scan", "--json"][..],
+        &["source", "suggest", "--json"],
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "1",
+            "--approve",
+            "--reviewer",
+            "alice",
+            "--note",
+            "vetted",
+        ],
+        &["source", "apply-approved", "--json"],
+    ] {
+        let out = run_synora(&origin, args);
+        assert!(out.status.success(), "{args:?} failed: {:?}", out);
+    }
+
+    let out = run_synora(&origin, &["source", "registry-export", "--format", "json"]);
+    assert!(out.status.success(), "json export failed: {:?}", out);
+    let exported = stdout_json(&out);
+    assert_eq!(exported["format"], "synora-source-registry");
+    assert_eq!(exported["entries"][0]["approval"]["approved_by"], "alice");
+    assert_eq!(exported["entries"][0]["approval"]["note"], "vetted");
+    let export_file = origin.join("registry.yaml");
+    let out = run_synora(
+        &origin,
+        &[
+            "source",
+            "registry-export",
+            "--format",
+            "yaml",
+            "--output",
+            export_file.to_str().expect("path"),
+        ],
+    );
+    assert!(out.status.success(), "yaml export failed: {:?}", out);
+    let mut yaml = fs::read_to_string(&export_file).expect("read export");
+    yaml.push_str(
+        r#"- software:
+    name: Unknown Tool
+    publisher: Nobody
+  url: https://tools.example.org/unknown
+  confidence: 60
+- software:
+    name: Google Chrome
+    publisher: Google LLC
+  url: http://insecure.example.org/chrome
+  confidence: 60
+- software:
+    name: Google Chrome
+    publisher: Google LLC
+  url: https://cdn.example.net/chrome
+  confidence: 80
+  approval:
+    note: mirrored
+- software:
+    name: Google Chrome
+    publisher: Google LLC
+  url: https://chrome-mirror.example.com/setup
+  confidence: 80
+  approval:
+    note: mirrored
+- software:
+    name: Google Chrome
+    publisher: Google LLC
+  url: https://dl.example.com/chrome
+  confidence: 30
+"#,
+    );
+    fs::write(&export_file, yaml).expect("extend export");
+
+    let target = unique_home();
+    fs::write(
+        target.join("config.json"),
+        serde_json::json!({
+            "execution": {"real_mutation_enabled": false, "gate_version": "phase3-draft-v1", "approval_record_ref": ""},
+            "download_source_policy": {"untrusted_domains": ["example.net"]},
+            "auto_review": {"deny_patterns": ["mirror"]}
+        })
+        .to_string(),
+    )
+    .expect("write config");
+    let mut chrome = registry_item("Google Chrome", "121.0.6167.85");
+    chrome["install_location"] = serde_json::json!("D:\\Apps\\Chrome");
+    write_fixture(&target, &serde_json::json!([chrome]));
+    let out = run_synora(&target, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "target scan failed: {:?}", out);
+
+    let file = export_file.to_str().expect("path");
+    let out = run_synora(
+        &target,
+        &[
+            "source",
+            "registry-import",
+            "--file",
+            file,
+            "--dry-run",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "dry-run import failed: {:?}", out);
+    let preview = stdout_json(&out);
+    assert_eq!(
+        (
+            preview["inserted"].as_i64(),
+            preview["unmatched"].as_i64(),
+            preview["invalid"].as_i64()
+        ),
+        (Some(1), Some(1), Some(1))
+    );
+    assert_eq!(preview["entries"][0]["match_method"], "canonical_name");
+    let blocked: Vec<&str> = preview["entries"]
+        .as_array()
+        .expect("entries")
+        .iter()
+        .filter(|e| e["action"] == "blocked")
+        .map(|e| e["detail"].as_str().unwrap_or_default())
+        .collect();
+    assert_eq!(blocked.len(), 3, "{preview}");
+    assert!(
+        blocked[0].contains("untrusted")
+            && blocked[1].contains("deny pattern")
+            && blocked[2].contains("note"),
+        "{blocked:?}"
+    );
+    let out = run_synora(&target, &["source", "registry-list", "--json"]);
+    assert_eq!(
+        stdout_json(&out).as_array().map(Vec::len),
+        Some(0),
+        "dry-run must not write"
+    );
+
+    let out = run_synora(
+        &target,
+        &["source", "registry-import", "--file", file, "--json"],
+    );
+    let imported = stdout_json(&out);
+    let import_id = imported["import_id"]
+        .as_str()
+        .expect("import id")
+        .to_string();
+    let out = run_synora(&target, &["source", "registry-list", "--json"]);
+    let registry = stdout_json(&out);
+    assert_eq!(
+        registry[0]["url"],
+        "https://winget.run/search?query=Google+Chrome"
+    );
+    assert_eq!(registry[0]["status"], "active");
+    assert_eq!(registry[0]["imported_from"], import_id.as_str());
+    let out = run_synora(
+        &target,
+        &[
+            "source",
+            "review-history",
+            "--batch-id",
+            &import_id,
+            "--json",
+        ],
+    );
+    let history = stdout_json(&out);
+    assert!(history[0]["reviewer"]
+        .as_str()
+        .unwrap_or_default()
+        .starts_with("import:"));
+    assert!(history[0]["note"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("approved by alice"));
+
+    let strategy = |strategy: &str| {
+        let out = run_synora(
+            &target,
+            &[
+                "source",
+                "registry-import",
+                "--file",
+                file,
+                "--on-conflict",
+                strategy,
+                "--json",
+            ],
+        );
+        assert!(out.status.success(), "import {strategy} failed: {:?}", out);
+        let report = stdout_json(&out);
+        (report["skipped"].as_i64(), report["overwritten"].as_i64())
+    };
+    assert_eq!(strategy("skip"), (Some(1), Some(0)));
+    assert_eq!(
+        strategy("newer"),
+        (Some(1), Some(0)),
+        "the local re-approval is newer than the export"
+    );
+    assert_eq!(strategy("overwrite"), (Some(0), Some(1)));
+
+    let out = run_synora(&target, &["update", "check", "--json"]);
+    assert_eq!(stdout_json(&out).as_array().map(Vec::len), Some(1));
+
+    // A dry run rolls back the expiry pass along with everything else.
+    let conn = Connection::open(target.join("db").join("synora.db")).expect("open db");
+    let registry_status = || -> String {
+        conn.query_row(
+            "SELECT status FROM source_registry WHERE candidate_id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .expect("registry status")
+    };
+    conn.execute("UPDATE source_registry SET expires_at = 1", [])
+        .expect("age registry");
+    let out = run_synora(
+        &target,
+        &[
+            "source",
+            "registry-import",
+            "--file",
+            file,
+            "--dry-run",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "dry-run import failed: {:?}", out);
+    assert_eq!(
+        registry_status(),
+        "active",
+        "dry-run must not expire entries"
+    );
+    conn.execute(
+        "UPDATE source_registry SET expires_at = applied_at + 86400",
+        [],
+    )
+    .expect("restore expiry");
+
+    // An overwrite is a fresh approval, so an entry waiting for re-review becomes active.
+    conn.execute(
+        "UPDATE source_registry SET status = 'review_required' WHERE candidate_id = 1",
+        [],
+    )
+    .expect("require review");
+    assert_eq!(strategy("overwrite"), (Some(0), Some(1)));
+    assert_eq!(registry_status(), "active");
+
+    // Overwrites keep a local disable and never flip a local rejection by default.
+    let out = run_synora(
+        &target,
+        &[
+            "source",
+            "registry-disable",
+            "--candidate-id",
+            "1",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "registry-disable failed: {:?}", out);
+    assert_eq!(strategy("overwrite"), (Some(0), Some(1)));
+    assert_eq!(
+        registry_status(),
+        "disabled",
+        "overwrite must not re-enable disabled sources"
+    );
+    let out = run_synora(
+        &target,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "1",
+            "--reject",
+            "--note",
+            "compromised",
+        ],
+    );
+    assert!(out.status.success(), "reject failed: {:?}", out);
+    assert_eq!(strategy("overwrite"), (Some(1), Some(0)));
+    let candidate_status = || -> String {
+        conn.query_row(
+            "SELECT status FROM source_candidate WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .expect("candidate status")
+    };
+    assert_eq!(candidate_status(), "rejected");
+    let out = run_synora(
+        &target,
+        &[
+            "source",
+            "registry-import",
+            "--file",
+            file,
+            "--on-conflict",
+            "overwrite",
+            "--allow-rejected",
+            "--json",
+        ],
+    );
+    assert_eq!(stdout_json(&out)["overwritten"], 1);
+    assert_eq!(candidate_status(), "approved");
+}
+
+#[test]
+fn interactive_review_requires_a_terminal() {
+    let home = unique_home();
+    write_fixture(&home, &serde_json::json!([registry_item("Git", "2.44.0")]));
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+
+    let out = run_synora(&home, &["source", "review", "--interactive"]);
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "piped stdout must be refused: {:?}",
+        out
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("terminal"));
+
+    let out = run_synora(
+        &home,
+        &["source", "review", "--interactive", "--candidate-id", "1"],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "mixed modes must be refused: {:?}",
+        out
+    );
+
+    let out = run_synora(&home, &["source", "review", "--approve", "--json"]);
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "candidate id is still required: {:?}",
+        out
+    );
+
+    let out = run_synora(&home, &["source", "list", "--status", "pending", "--json"]);
+    assert!(!stdout_json(&out).as_array().expect("candidates").is_empty());
+}
+
+#[test]
+fn suggest_is_incremental_and_keeps_reviewed_candidates() {
+    let home = unique_home();
+    write_fixture(
+        &home,
+        &serde_json::json!([
+            registry_item("Google Chrome", "120.0.6099.71"),
+            registry_item("Google Earth", "7.3")
+        ]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let summary = |args: &[&str]| {
+        let out = run_synora(&home, args);
+        assert!(out.status.success(), "suggest failed: {:?}", out);
+        String::from_utf8_lossy(&out.stdout).to_string()
+    };
+
+    let first = summary(&["source", "suggest"]);
+    assert!(first.contains("processed=2, unchanged=0"), "{first}");
+    assert!(first.contains("elapsed_ms="), "{first}");
+    let second = summary(&["source", "suggest"]);
+    assert!(
+        second.contains("processed=0, unchanged=2, generated=0"),
+        "{second}"
+    );
+
+    // Approving Chrome's winget.run candidate changes the domain history Earth is scored with.
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "1",
+            "--approve",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "review failed: {:?}", out);
+    let third = summary(&["source", "suggest"]);
+    assert!(third.contains("processed=1, unchanged=1"), "{third}");
+
+    fs::write(
+        home.join("config.json"),
+        serde_json::json!({
+            "execution": {"real_mutation_enabled": false, "gate_version": "phase3-draft-v1", "approval_record_ref": ""},
+            "auto_review": {"trusted_domains": ["winget.run"]}
+        })
+        .to_string(),
+    )
+    .expect("write config");
+    let rescored = summary(&["source", "suggest"]);
+    assert!(
+        rescored.contains("processed=2, unchanged=0"),
+        "domain policy changes rescore: {rescored}"
+    );
+    let full = summary(&["source", "suggest", "--full"]);
+    assert!(full.contains("processed=2, unchanged=0"), "{full}");
+    assert!(full.contains("skipped_reviewed=1"), "{full}");
+
+    let out = run_synora(&home, &["source", "list", "--json"]);
+    let candidates = stdout_json(&out);
+    let chrome = candidates
+        .as_array()
+        .expect("candidates")
+        .iter()
+        .find(|c| c["candidate_id"] == 1)
+        .expect("chrome candidate");
+    assert_eq!(chrome["status"], "approved");
+    assert_eq!(
+        chrome["confidence"], 55,
+        "reviewed candidates are never rescored"
+    );
+    let earth = candidates
+        .as_array()
+        .expect("candidates")
+        .iter()
+        .find(|c| c["software_name"] == "Google Earth")
+        .expect("earth candidate");
+    assert!(earth["confidence"].as_i64().expect("confidence") > 55);
+
+    let subset = summary(&["source", "suggest", "--software-id", "2", "--json"]);
+    let shown = serde_json::from_str::<Value>(&subset).expect("json");
+    assert!(shown["items"]
+        .as_array()
+        .expect("items")
+        .iter()
+        .all(|c| c["software_id"] == 2));
+    assert_eq!(
+        (shown["processed"].as_i64(), shown["unchanged"].as_i64()),
+        (Some(1), Some(0))
+    );
+    assert_eq!(shown["skipped_reviewed"], 0);
+    assert!(shown["elapsed_ms"].is_u64(), "{shown}");
+
+    let out = run_synora(&home, &["source", "suggest", "--software-id", "999"]);
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "unknown software must be refused: {:?}",
+        out
+    );
+}
+
+#[test]
+fn risk_explain_matches_the_level_commands_report_and_enforce() {
+    let home = unique_home();
+    write_fixture(
+        &home,
+        &serde_json::json!([registry_item("Google Chrome", "120.0.6099.71")]),
+    );
+    let out = run_synora(&home, &["software", "discover", "scan", "--json"]);
+    assert!(out.status.success(), "scan failed: {:?}", out);
+    let out = run_synora(&home, &["source", "suggest", "--json"]);
+    assert!(out.status.success(), "suggest failed: {:?}", out);
+    let out = run_synora(
+        &home,
+        &[
+            "source",
+            "review",
+            "--candidate-id",
+            "1",
+            "--approve",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "review failed: {:?}", out);
+    let out = run_synora(&home, &["source", "apply-approved", "--json"]);
+    assert!(out.status.success(), "apply failed: {:?}", out);
+
+    let out = run_synora(
+        &home,
+        &[
+            "risk",
+            "explain",
+            "--action-id",
+            "update.candidate:1",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "explain failed: {:?}", out);
+    let explained = stdout_json(&out);
+    assert_eq!(explained["level"], "medium");
+    assert_eq!(explained["score"], 35);
+    assert_eq!(explained["reasons"][0]["rule"], "action_base");
+    assert_eq!(explained["reasons"][1]["rule"], "source_low_confidence");
+    assert_eq!(explained["facts"]["source_confidence"], 55);
+
+    let out = run_synora(&home, &["update", "check", "--json"]);
+    let checks = stdout_json(&out);
+    assert_eq!(checks[0]["risk_level"], explained["level"]);
+    assert_eq!(checks[0]["risk_score"], explained["score"]);
+
+    // Every action family that needed --confirm before the shared engine still does.
+    for action_id in [
+        "update.apply:1",
+        "update.history:op-missing",
+        "download.retry:job-1",
+        "cleanup.apply:1",
+    ] {
+        let out = run_synora(&home, &["ui", "action-run", "--id", action_id, "--json"]);
+        assert_eq!(
+            out.status.code(),
+            Some(3),
+            "{action_id} must need --confirm: {:?}",
+            out
+        );
+        let out = run_synora(
+            &home,
+            &["ui", "action-run", "--id", action_id, "--confirm", "--json"],
+        );
+        assert_eq!(stdout_json(&out)["risk_level"], "high", "{action_id}");
+    }
+    let out = run_synora(
+        &home,
+        &[
+            "risk",
+            "explain",
+            "--action-id",
+            "source.registry:1",
+            "--json",
+        ],
+    );
+    assert_eq!(stdout_json(&out)["level"], "medium");
+
+    fs::write(
+        home.join("config.json"),
+        serde_json::json!({
+            "execution": {"real_mutation_enabled": false, "gate_version": "phase3-draft-v1", "approval_record_ref": ""},
+            "download_source_policy": {"untrusted_domains": ["winget.run"]}
+        })
+        .to_string(),
+    )
+    .expect("write config");
+    let out = run_synora(
+        &home,
+        &[
+            "risk",
+            "explain",
+            "--action-id",
+            "source.registry:1",
+            "--json",
+        ],
+    );
+    let explained = stdout_json(&out);
+    assert_eq!(explained["level"], "high");
+    assert!(explained["reasons"]
+        .as_array()
+        .expect("reasons")
+        .iter()
+        .any(|r| r["rule"] == "source_untrusted"));
+
+    let out = run_synora(
+        &home,
+        &["ui", "action-run", "--id", "source.registry:1", "--json"],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(3),
+        "high risk actions need --confirm: {:?}",
+        out
+    );
+    let out = run_synora(
+        &home,
+        &[
+            "ui",
+            "action-run",
+            "--id",
+            "source.registry:1",
+            "--confirm",
+            "--json",
+        ],
+    );
+    assert!(out.status.success(), "confirmed action failed: {:?}", out);
+    assert_eq!(stdout_json(&out)["risk_score"], explained["score"]);
+
+    let out = run_synora(
+        &home,
+        &[
+            "risk",
+            "explain",
+            "--action-id",
+            "source.registry:1",
+            "--json",
+        ],
+    );
+    let recent = &stdout_json(&out)["recent_evaluations"];
+    assert_eq!(recent.as_array().map(Vec::len), Some(2));
+    assert_eq!(recent[0]["command"], "ui action-run");
+    assert_eq!(recent[0]["level"], "high");
+
+    let out = run_synora(&home, &["risk", "explain", "--action-id", " "]);
+    assert_eq!(out.status.code(), Some(2));
+}